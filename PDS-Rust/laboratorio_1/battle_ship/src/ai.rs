@@ -0,0 +1,594 @@
+use rand::rngs::StdRng;
+use rand::seq::IndexedRandom;
+use rand::{Rng, SeedableRng};
+
+use crate::board::{Board, Boat, Error, ShotResult, HIT, MISS};
+use crate::game::Game;
+
+
+/* posizionamento casuale */
+
+// tentativi di posizionare l'intera flotta prima di arrendersi: con la regola delle navi che non si
+// toccano una scelta sfortunata può lasciare senza spazio le navi successive
+const PLACEMENT_RESTARTS: usize = 100;
+
+// posiziona a caso tutte le navi ancora da posizionare, seguendo le regole della board (check_boat).
+// Se non c'è una disposizione valida la board resta invariata
+pub fn place_fleet<R: Rng>(board: &mut Board, rng: &mut R) -> Result<(), Error> {
+    'restart: for _ in 0..PLACEMENT_RESTARTS {
+        let mut attempt = board.clone();
+        // the longest boats first, they are the hardest to fit
+        for len in (1..=attempt.boats().len()).rev() {
+            while attempt.boats()[len - 1] > 0 {
+                let placements = valid_placements(&attempt, len);
+                let Some(&(boat, pos)) = placements.choose(rng) else {
+                    continue 'restart;
+                };
+                attempt.add_boat(boat, pos, len as u8)?;
+            }
+        }
+        *board = attempt;
+        return Ok(());
+    }
+    Err(Error::NoSpace)
+}
+
+fn valid_placements(board: &Board, len: usize) -> Vec<(Boat, (usize, usize))> {
+    let mut res = Vec::new();
+    for x in 0..board.rows() {
+        for y in 0..board.cols() {
+            for boat in [Boat::Horizontal(len), Boat::Vertical(len)] {
+                if board.check_boat(boat, (x, y), len as u8).is_ok() {
+                    res.push((boat, (x, y)));
+                }
+            }
+        }
+    }
+    res
+}
+
+
+/* strategie di tiro */
+
+// contenuto di una cella della board avversaria, per quanto è noto a chi spara
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cell {
+    Unknown,
+    Miss,
+    Hit,   // nave colpita ma non ancora affondata
+    Sunk,  // cella di una nave affondata
+    Water, // mai colpita, ma per la regola delle navi che non si toccano non può contenere una nave
+}
+
+// quello che un giocatore sa della board avversaria: i colpi sparati e le navi ancora a galla
+#[derive(Debug, Clone)]
+pub struct View {
+    rows: usize,
+    cols: usize,
+    cells: Vec<Vec<Cell>>,
+    remaining: Vec<u8>, // navi non ancora affondate, l'indice è la lunghezza - 1
+    touching_allowed: bool,
+}
+
+impl View {
+    // vista della board avversaria, le navi non colpite restano nascoste
+    pub fn of(board: &Board) -> View {
+        let mut cells: Vec<Vec<Cell>> = board.data().iter()
+            .map(|row| row.iter()
+                .map(|&c| match c {
+                    HIT => Cell::Hit,
+                    MISS => Cell::Miss,
+                    _ => Cell::Unknown,
+                })
+                .collect())
+            .collect();
+
+        let mut remaining = board.fleet();
+        for ship in board.ships().iter().filter(|ship| board.is_sunk(ship)) {
+            remaining[ship.length() - 1] -= 1;
+            for (x, y) in ship.cells() {
+                cells[x][y] = Cell::Sunk;
+            }
+        }
+
+        let mut view = View { rows: board.rows(), cols: board.cols(), cells, remaining, touching_allowed: board.touching_allowed() };
+        if !view.touching_allowed {
+            view.mark_water();
+        }
+        view
+    }
+
+    // boats are straight and may not touch: the diagonal neighbours of a hit and every neighbour of a
+    // sunk boat are water
+    fn mark_water(&mut self) {
+        for x in 0..self.rows {
+            for y in 0..self.cols {
+                let cell = self.cells[x][y];
+                if cell != Cell::Hit && cell != Cell::Sunk {
+                    continue;
+                }
+                for nx in x.saturating_sub(1)..=(x + 1).min(self.rows - 1) {
+                    for ny in y.saturating_sub(1)..=(y + 1).min(self.cols - 1) {
+                        let diagonal = nx != x && ny != y;
+                        if self.cells[nx][ny] == Cell::Unknown && (cell == Cell::Sunk || diagonal) {
+                            self.cells[nx][ny] = Cell::Water;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // vista di una board di cui non si sa nulla, da aggiornare con record (partita in rete)
+    pub fn new(rows: usize, cols: usize, fleet: &[u8], touching_allowed: bool) -> View {
+        View { rows, cols, cells: vec![vec![Cell::Unknown; cols]; rows], remaining: fleet.to_vec(), touching_allowed }
+    }
+
+    // registra l'esito di un colpo. Per una nave affondata le celle sono la linea di colpi a segno
+    // che passa per (x, y) e ha la lunghezza indicata
+    pub fn record(&mut self, x: usize, y: usize, result: ShotResult) {
+        match result {
+            ShotResult::Miss => self.cells[x][y] = Cell::Miss,
+            ShotResult::Hit => self.cells[x][y] = Cell::Hit,
+            ShotResult::Sunk(len) => {
+                self.cells[x][y] = Cell::Hit;
+                let len = len as usize;
+                let run = |dx: usize, dy: usize| {
+                    let hit = |cx: usize, cy: usize| cx < self.rows && cy < self.cols && self.cells[cx][cy] == Cell::Hit;
+                    let mut start = (x, y);
+                    while start.0 >= dx && start.1 >= dy && hit(start.0 - dx, start.1 - dy) {
+                        start = (start.0 - dx, start.1 - dy);
+                    }
+                    let mut cells = vec![start];
+                    while let Some(&(cx, cy)) = cells.last() && hit(cx + dx, cy + dy) {
+                        cells.push((cx + dx, cy + dy));
+                    }
+                    cells
+                };
+                let horizontal = run(0, 1);
+                let vertical = run(1, 0);
+                let ship = if horizontal.len() == len {
+                    horizontal
+                } else if vertical.len() == len {
+                    vertical
+                } else {
+                    vec![(x, y)]
+                };
+                for (cx, cy) in ship {
+                    self.cells[cx][cy] = Cell::Sunk;
+                }
+                if let Some(n) = len.checked_sub(1).and_then(|i| self.remaining.get_mut(i)) {
+                    *n = n.saturating_sub(1);
+                }
+            }
+            ShotResult::AlreadyShot => {}
+        }
+        if !self.touching_allowed {
+            self.mark_water();
+        }
+    }
+
+    // tutte le navi sono state affondate
+    pub fn all_sunk(&self) -> bool {
+        self.remaining.iter().all(|&n| n == 0)
+    }
+
+    // griglia come Board::grid, con ~ per le celle di sola acqua
+    pub fn grid(&self) -> String {
+        let mut res = String::from("   ");
+        for y in 0..self.cols {
+            res.push_str(&format!("{:>3}", y));
+        }
+        res.push('\n');
+        for (x, row) in self.cells.iter().enumerate() {
+            res.push_str(&format!("{:>3}", x));
+            for &cell in row {
+                let symbol = match cell {
+                    Cell::Unknown => '.',
+                    Cell::Miss => 'o',
+                    Cell::Hit => 'X',
+                    Cell::Sunk => '#',
+                    Cell::Water => '~',
+                };
+                res.push_str(&format!("{:>3}", symbol));
+            }
+            res.push('\n');
+        }
+        res
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn cell(&self, x: usize, y: usize) -> Cell {
+        self.cells[x][y]
+    }
+
+    pub fn remaining(&self) -> &[u8] {
+        &self.remaining
+    }
+
+    // false when every cell is known: with the whole fleet sunk the game is over, otherwise the
+    // results received were not true
+    pub fn has_unknown_cells(&self) -> bool {
+        self.cells.iter().flatten().any(|&c| c == Cell::Unknown)
+    }
+
+    fn unknown_cells(&self) -> Vec<(usize, usize)> {
+        (0..self.rows)
+            .flat_map(|x| (0..self.cols).map(move |y| (x, y)))
+            .filter(|&(x, y)| self.cells[x][y] == Cell::Unknown)
+            .collect()
+    }
+
+    fn unknown_neighbours(&self, x: usize, y: usize) -> Vec<(usize, usize)> {
+        let mut res = Vec::new();
+        if x > 0 {
+            res.push((x - 1, y));
+        }
+        if x + 1 < self.rows {
+            res.push((x + 1, y));
+        }
+        if y > 0 {
+            res.push((x, y - 1));
+        }
+        if y + 1 < self.cols {
+            res.push((x, y + 1));
+        }
+        res.retain(|&(nx, ny)| self.cells[nx][ny] == Cell::Unknown);
+        res
+    }
+}
+
+// strategia di tiro: sceglie la prossima cella da colpire. La cella restituita deve essere Unknown,
+// la vista ne contiene sempre almeno una finché la partita non è finita
+pub trait Strategy {
+    fn name(&self) -> &'static str;
+    fn next_shot(&mut self, view: &View) -> (usize, usize);
+}
+
+// spara a caso su una cella non ancora colpita
+pub struct RandomStrategy {
+    rng: StdRng,
+}
+
+impl RandomStrategy {
+    pub fn new(seed: u64) -> RandomStrategy {
+        RandomStrategy { rng: StdRng::seed_from_u64(seed) }
+    }
+}
+
+impl Strategy for RandomStrategy {
+    fn name(&self) -> &'static str {
+        "random"
+    }
+
+    fn next_shot(&mut self, view: &View) -> (usize, usize) {
+        *view.unknown_cells().choose(&mut self.rng).expect("no cells left to shoot")
+    }
+}
+
+// hunt: colpi casuali su una scacchiera (ogni nave lunga almeno 2 copre una cella di ciascun colore);
+// target: dopo un colpo a segno prosegue sulle celle vicine, lungo la linea se i colpi sono allineati
+pub struct HuntTargetStrategy {
+    rng: StdRng,
+}
+
+impl HuntTargetStrategy {
+    pub fn new(seed: u64) -> HuntTargetStrategy {
+        HuntTargetStrategy { rng: StdRng::seed_from_u64(seed) }
+    }
+}
+
+impl Strategy for HuntTargetStrategy {
+    fn name(&self) -> &'static str {
+        "hunt-target"
+    }
+
+    fn next_shot(&mut self, view: &View) -> (usize, usize) {
+        let hits: Vec<(usize, usize)> = (0..view.rows)
+            .flat_map(|x| (0..view.cols).map(move |y| (x, y)))
+            .filter(|&(x, y)| view.cells[x][y] == Cell::Hit)
+            .collect();
+
+        // target mode: the ends of a line of hits first, then any cell next to a hit
+        let mut in_line = Vec::new();
+        let mut around = Vec::new();
+        for &(x, y) in &hits {
+            for (nx, ny) in view.unknown_neighbours(x, y) {
+                // the cell on the opposite side is a hit too: (nx, ny) continues the line
+                let opposite = (2 * x).checked_sub(nx).zip((2 * y).checked_sub(ny));
+                let aligned = opposite.is_some_and(|(ox, oy)| {
+                    ox < view.rows && oy < view.cols && view.cells[ox][oy] == Cell::Hit
+                });
+                if aligned {
+                    in_line.push((nx, ny));
+                } else {
+                    around.push((nx, ny));
+                }
+            }
+        }
+        if let Some(&cell) = in_line.choose(&mut self.rng).or_else(|| around.choose(&mut self.rng)) {
+            return cell;
+        }
+
+        // hunt mode
+        let unknown = view.unknown_cells();
+        let smallest = view.remaining.iter().position(|&n| n > 0).map_or(1, |i| i + 1);
+        let parity: Vec<(usize, usize)> = unknown.iter().copied().filter(|&(x, y)| (x + y) % smallest == 0).collect();
+        *parity.choose(&mut self.rng)
+            .or_else(|| unknown.choose(&mut self.rng))
+            .expect("no cells left to shoot")
+    }
+}
+
+// densità di probabilità: per ogni nave ancora a galla conta in quante posizioni compatibili con i colpi
+// noti può trovarsi ogni cella e spara sulla cella più probabile. Le posizioni che passano per una
+// nave colpita valgono molto di più, così dopo un colpo a segno la nave viene finita
+pub struct ProbabilityStrategy {
+    rng: StdRng,
+}
+
+impl ProbabilityStrategy {
+    pub fn new(seed: u64) -> ProbabilityStrategy {
+        ProbabilityStrategy { rng: StdRng::seed_from_u64(seed) }
+    }
+
+    pub fn density(view: &View) -> Vec<Vec<u64>> {
+        let mut density = vec![vec![0u64; view.cols]; view.rows];
+        for (i, &count) in view.remaining.iter().enumerate() {
+            if count == 0 {
+                continue;
+            }
+            let len = i + 1;
+            for x in 0..view.rows {
+                for y in 0..view.cols {
+                    for vertical in [false, true] {
+                        let cells: Vec<(usize, usize)> = (0..len)
+                            .map(|k| if vertical { (x + k, y) } else { (x, y + k) })
+                            .collect();
+                        let fits = cells.iter().all(|&(cx, cy)| {
+                            cx < view.rows && cy < view.cols && matches!(view.cells[cx][cy], Cell::Unknown | Cell::Hit)
+                        });
+                        if !fits || (len == 1 && vertical) {
+                            continue;
+                        }
+                        let hits = cells.iter().filter(|&&(cx, cy)| view.cells[cx][cy] == Cell::Hit).count() as u32;
+                        let weight = (count as u64).saturating_mul(100u64.saturating_pow(hits));
+                        for (cx, cy) in cells {
+                            if view.cells[cx][cy] == Cell::Unknown {
+                                density[cx][cy] = density[cx][cy].saturating_add(weight);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        density
+    }
+}
+
+impl Strategy for ProbabilityStrategy {
+    fn name(&self) -> &'static str {
+        "probability"
+    }
+
+    fn next_shot(&mut self, view: &View) -> (usize, usize) {
+        let density = ProbabilityStrategy::density(view);
+        let unknown = view.unknown_cells();
+        let best = unknown.iter().map(|&(x, y)| density[x][y]).max().expect("no cells left to shoot");
+        let candidates: Vec<(usize, usize)> = unknown.into_iter().filter(|&(x, y)| density[x][y] == best).collect();
+        *candidates.choose(&mut self.rng).unwrap()
+    }
+}
+
+// strategie disponibili, per nome
+pub const STRATEGIES: [&str; 3] = ["random", "hunt-target", "probability"];
+
+pub fn strategy_by_name(name: &str, seed: u64) -> Option<Box<dyn Strategy>> {
+    match name {
+        "random" => Some(Box::new(RandomStrategy::new(seed))),
+        "hunt-target" => Some(Box::new(HuntTargetStrategy::new(seed))),
+        "probability" => Some(Box::new(ProbabilityStrategy::new(seed))),
+        _ => None,
+    }
+}
+
+
+/* simulazione */
+
+// spara sulla board finché tutte le navi sono affondate, restituisce il numero di colpi
+pub fn shots_to_win(strategy: &mut dyn Strategy, board: &mut Board) -> usize {
+    let mut shots = 0;
+    while !board.is_defeated() {
+        let (x, y) = strategy.next_shot(&View::of(board));
+        let result = board.fire(x, y).expect("strategy fired outside the board");
+        assert_ne!(result, ShotResult::AlreadyShot, "{} fired twice at {},{}", strategy.name(), x, y);
+        shots += 1;
+    }
+    shots
+}
+
+// partita completa tra due strategie sul motore di gioco, restituisce il vincitore (0 o 1)
+pub fn play_game(strategies: [&mut dyn Strategy; 2], mut game: Game, rng: &mut StdRng) -> Result<usize, Error> {
+    if game.board(0).fleet().iter().all(|&n| n == 0) {
+        return Err(Error::EmptyFleet);
+    }
+    for player in 0..2 {
+        place_fleet(game.board_mut(player), rng)?;
+    }
+    let [first, second] = strategies;
+    loop {
+        if let Some(winner) = game.winner() {
+            return Ok(winner);
+        }
+        let opponent = 1 - game.turn();
+        let view = View::of(game.board(opponent));
+        let (x, y) = if game.turn() == 0 { first.next_shot(&view) } else { second.next_shot(&view) };
+        game.fire(x, y)?;
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulationReport {
+    pub strategy: &'static str,
+    pub games: usize,
+    pub average: f64,
+    pub min: usize,
+    pub max: usize,
+}
+
+// gioca `games` partite per ogni strategia: ogni partita usa una flotta casuale generata dal seme
+// `seed + i`, quindi tutte le strategie affrontano le stesse flotte e i risultati sono riproducibili
+pub fn simulate(template: &Board, games: usize, seed: u64) -> Result<Vec<SimulationReport>, Error> {
+    // without boats no board is ever defeated and the strategies would run out of cells
+    if template.fleet().iter().all(|&n| n == 0) {
+        return Err(Error::EmptyFleet);
+    }
+    let mut fleets = Vec::with_capacity(games);
+    for i in 0..games {
+        let mut board = template.clone();
+        place_fleet(&mut board, &mut StdRng::seed_from_u64(seed.wrapping_add(i as u64)))?;
+        fleets.push(board);
+    }
+
+    let mut reports = Vec::new();
+    for name in STRATEGIES {
+        let mut shots = Vec::with_capacity(games);
+        for (i, fleet) in fleets.iter().enumerate() {
+            let mut strategy = strategy_by_name(name, seed.wrapping_add(i as u64)).unwrap();
+            shots.push(shots_to_win(strategy.as_mut(), &mut fleet.clone()));
+        }
+        reports.push(SimulationReport {
+            strategy: name,
+            games,
+            average: shots.iter().sum::<usize>() as f64 / games.max(1) as f64,
+            min: shots.iter().copied().min().unwrap_or(0),
+            max: shots.iter().copied().max().unwrap_or(0),
+        });
+    }
+    Ok(reports)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_place_fleet_follows_the_rules() {
+        for seed in 0..20 {
+            let mut board = Board::with_size(10, 10, &[4, 3, 2, 1]);
+            place_fleet(&mut board, &mut StdRng::seed_from_u64(seed)).unwrap();
+            assert!(board.is_complete());
+            assert_eq!(board.validate(), Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_place_fleet_without_room() {
+        let mut board = Board::with_size(3, 3, &[0, 0, 3]);
+        let original = board.clone();
+        assert_eq!(place_fleet(&mut board, &mut StdRng::seed_from_u64(1)), Err(Error::NoSpace));
+        assert_eq!(board, original);
+    }
+
+    #[test]
+    fn test_view_hides_ships() {
+        let mut board = Board::with_size(5, 5, &[1, 1]);
+        board.add_boat(Boat::Horizontal(2), (0, 0), 2).unwrap();
+        board.add_boat(Boat::Vertical(1), (4, 4), 1).unwrap();
+        board.fire(0, 0).unwrap();
+        board.fire(2, 2).unwrap();
+        board.fire(4, 4).unwrap();
+
+        let view = View::of(&board);
+        assert_eq!(view.cell(0, 0), Cell::Hit);
+        assert_eq!(view.cell(0, 1), Cell::Unknown);
+        assert_eq!(view.cell(2, 2), Cell::Miss);
+        assert_eq!(view.cell(4, 4), Cell::Sunk);
+        assert_eq!(view.remaining(), &[0, 1]);
+        // no-touch rule: around a sunk boat and on the diagonals of a hit there is only water
+        assert_eq!(view.cell(3, 3), Cell::Water);
+        assert_eq!(view.cell(1, 1), Cell::Water);
+        assert_eq!(view.cell(1, 0), Cell::Unknown);
+        assert_eq!(View::of(&board.clone().allow_touching(true)).cell(3, 3), Cell::Unknown);
+    }
+
+    #[test]
+    fn test_view_record_matches_view_of() {
+        let mut board = Board::with_size(6, 6, &[1, 1, 1]);
+        place_fleet(&mut board, &mut StdRng::seed_from_u64(3)).unwrap();
+        let mut view = View::new(6, 6, &[1, 1, 1], false);
+        let mut strategy = HuntTargetStrategy::new(9);
+        while !board.is_defeated() {
+            let (x, y) = strategy.next_shot(&view);
+            view.record(x, y, board.fire(x, y).unwrap());
+            assert_eq!(view.cells, View::of(&board).cells);
+        }
+        assert!(view.all_sunk());
+    }
+
+    #[test]
+    fn test_hunt_target_finishes_a_ship() {
+        let mut board = Board::with_size(5, 5, &[0, 0, 1]);
+        board.add_boat(Boat::Horizontal(3), (2, 1), 3).unwrap();
+        board.fire(2, 2).unwrap();
+        board.fire(2, 3).unwrap();
+        let (x, y) = HuntTargetStrategy::new(0).next_shot(&View::of(&board));
+        assert!((x, y) == (2, 1) || (x, y) == (2, 4));
+    }
+
+    #[test]
+    fn test_probability_density_prefers_the_centre() {
+        let board = Board::with_size(5, 5, &[0, 0, 1]);
+        let density = ProbabilityStrategy::density(&View::of(&board));
+        assert!(density[2][2] > density[0][0]);
+        assert_eq!(ProbabilityStrategy::new(0).next_shot(&View::of(&board)), (2, 2));
+    }
+
+    #[test]
+    fn test_every_strategy_wins() {
+        let mut template = Board::with_size(8, 8, &[1, 1, 1]);
+        place_fleet(&mut template, &mut StdRng::seed_from_u64(7)).unwrap();
+        for name in STRATEGIES {
+            let mut strategy = strategy_by_name(name, 3).unwrap();
+            let shots = shots_to_win(strategy.as_mut(), &mut template.clone());
+            assert!((6..=64).contains(&shots), "{}: {}", name, shots);
+        }
+    }
+
+    #[test]
+    fn test_simulation_is_reproducible_and_ranks_strategies() {
+        let template = Board::with_size(10, 10, &[0, 1, 2, 1]);
+        let reports = simulate(&template, 30, 42).unwrap();
+        assert_eq!(reports, simulate(&template, 30, 42).unwrap());
+        assert_eq!(reports.len(), 3);
+        // smarter strategies need fewer shots
+        assert!(reports[1].average < reports[0].average);
+        assert!(reports[2].average < reports[1].average);
+    }
+
+    #[test]
+    fn test_simulation_rejects_an_empty_fleet() {
+        assert_eq!(simulate(&Board::with_size(10, 10, &[0, 0]), 2, 0), Err(Error::EmptyFleet));
+        assert_eq!(simulate(&Board::with_size(10, 10, &[]), 2, 0), Err(Error::EmptyFleet));
+        let mut rng = StdRng::seed_from_u64(0);
+        let game = Game::with_size(8, 8, &[0]);
+        assert_eq!(play_game([&mut RandomStrategy::new(1), &mut RandomStrategy::new(2)], game, &mut rng), Err(Error::EmptyFleet));
+    }
+
+    #[test]
+    fn test_play_game() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let mut random = RandomStrategy::new(1);
+        let mut smart = ProbabilityStrategy::new(2);
+        let game = Game::with_size(8, 8, &[1, 1, 1]);
+        let winner = play_game([&mut random, &mut smart], game, &mut rng).unwrap();
+        assert!(winner < 2);
+    }
+}
@@ -0,0 +1,823 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::format::{self, Field, Lines, ParseError, ParseErrorKind};
+
+/* struct Board */
+
+// dimensione predefinita, quella del testo del laboratorio (20x20)
+pub const BSIZE: usize = 20;
+
+// contenuto di una cella della matrice
+pub const WATER: u8 = 0;
+pub const BOAT: u8 = 1;
+pub const HIT: u8 = 2;  // nave colpita
+pub const MISS: u8 = 3; // colpo finito in acqua
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Board {
+    rows: usize,
+    cols: usize,
+    boats: Vec<u8>, // navi ancora da posizionare, l'indice è la lunghezza - 1
+    data: Vec<Vec<u8>>, //NOTE: 0 equals to space and 1 equals to B respect to the text of the lab
+    ships: Vec<Ship>, // navi posizionate, servono per capire quando una nave è affondata
+    no_touch: bool, // le navi non possono toccarsi, nemmeno in diagonale
+}
+
+// nave posizionata: (x, y) è la cella iniziale, le altre seguono verso il basso (Vertical) o verso destra (Horizontal)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Ship {
+    pub x: usize,
+    pub y: usize,
+    pub boat: Boat,
+}
+
+impl Ship {
+    pub fn length(&self) -> usize {
+        match self.boat {
+            Boat::Vertical(len) | Boat::Horizontal(len) => len,
+        }
+    }
+
+    pub fn cells(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (0..self.length()).map(move |i| match self.boat {
+            Boat::Vertical(_) => (self.x + i, self.y),
+            Boat::Horizontal(_) => (self.x, self.y + i),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ShotResult {
+    Hit,
+    Miss,
+    Sunk(u8), // tipo (lunghezza) della nave affondata
+    AlreadyShot,
+}
+
+impl fmt::Display for ShotResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShotResult::Hit => write!(f, "hit"),
+            ShotResult::Miss => write!(f, "miss"),
+            ShotResult::Sunk(boat_type) => write!(f, "sunk {}", boat_type),
+            ShotResult::AlreadyShot => write!(f, "already"),
+        }
+    }
+}
+
+impl ShotResult {
+    pub fn parse(s: &str) -> Option<ShotResult> {
+        let mut parts = s.split_whitespace();
+        let res = match parts.next()? {
+            "hit" => ShotResult::Hit,
+            "miss" => ShotResult::Miss,
+            "sunk" => ShotResult::Sunk(parts.next()?.parse().ok()?),
+            "already" => ShotResult::AlreadyShot,
+            _ => return None,
+        };
+        match parts.next() {
+            Some(_) => None,
+            None => Some(res),
+        }
+    }
+}
+
+impl Board {
+    // crea una board vuota BSIZE x BSIZE con una disponibilità di navi
+    pub fn new(boats: &[u8]) -> Board {
+        Board::with_size(BSIZE, BSIZE, boats)
+    }
+
+    // crea una board vuota di rows x cols celle; boats[i] è il numero di navi lunghe i + 1
+    pub fn with_size(rows: usize, cols: usize, boats: &[u8]) -> Board {
+        Board {
+            rows,
+            cols,
+            boats: boats.to_vec(),
+            data: vec![vec![WATER; cols]; rows],
+            ships: Vec::new(),
+            no_touch: true,
+        }
+    }
+
+    // disattiva (o riattiva) la regola per cui le navi non possono toccarsi
+    pub fn allow_touching(mut self, allow: bool) -> Board {
+        self.no_touch = !allow;
+        self
+    }
+
+    pub fn touching_allowed(&self) -> bool {
+        !self.no_touch
+    }
+
+    /* crea una board a partire da una stringa che rappresenta tutto il contenuto del file board.txt */
+    pub fn from(s: String) -> Board {
+        Board::from_string(&s).unwrap_or_else(|e| {
+            eprintln!("Errore nel parsing: {}", e);
+            // Puoi restituire un board vuoto o fare altro
+            Board::new(&[4, 3, 2, 1])
+        })
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn in_bounds(&self, x: usize, y: usize) -> bool {
+        x < self.rows && y < self.cols
+    }
+
+    pub fn boats(&self) -> &[u8] {
+        &self.boats
+    }
+
+    pub fn data(&self) -> &[Vec<u8>] {
+        &self.data
+    }
+
+    pub fn ships(&self) -> &[Ship] {
+        &self.ships
+    }
+
+    // flotta iniziale: navi ancora da posizionare più quelle già posizionate
+    pub fn fleet(&self) -> Vec<u8> {
+        let mut fleet = self.boats.clone();
+        for ship in &self.ships {
+            fleet[ship.length() - 1] += 1;
+        }
+        fleet
+    }
+
+    // tutte le navi sono state posizionate
+    pub fn is_complete(&self) -> bool {
+        self.boats.iter().all(|&n| n == 0) && !self.ships.is_empty()
+    }
+
+    // tutte le navi posizionate sono state affondate
+    pub fn is_defeated(&self) -> bool {
+        !self.ships.is_empty() && self.ships.iter().all(|ship| self.is_sunk(ship))
+    }
+
+    pub fn is_sunk(&self, ship: &Ship) -> bool {
+        ship.cells().all(|(x, y)| self.data[x][y] == HIT)
+    }
+
+    /* aggiunge la nave alla board, restituendo la nuova board se possibile */
+    /* bonus: provare a *non copiare* data quando si crea e restituisce una nuova board con la barca, come si può fare? */
+    // la board viene modificata solo se il posizionamento è valido
+    pub fn add_boat(&mut self, boat: Boat, pos: (usize, usize), boat_type: u8) -> Result<&mut Board, Error> {
+        let ship = self.check_boat(boat, pos, boat_type)?;
+
+        // decrement number of boat available
+        self.boats[ship.length() - 1] -= 1;
+
+        // update board
+        for (x, y) in ship.cells() {
+            self.data[x][y] = BOAT;
+        }
+        self.ships.push(ship);
+
+        Ok(self)
+    }
+
+    // controlla se la nave può essere posizionata, senza modificare la board
+    pub fn check_boat(&self, boat: Boat, pos: (usize, usize), boat_type: u8) -> Result<Ship, Error> {
+        let len = boat_type as usize;
+        if len == 0 || len > self.boats.len() || self.boats[len - 1] == 0 {
+            return Err(Error::BoatCount);
+        }
+
+        // the length given by the boat type wins over the one stored in the Boat
+        let boat = match boat {
+            Boat::Vertical(_) => Boat::Vertical(len),
+            Boat::Horizontal(_) => Boat::Horizontal(len),
+        };
+        let ship = Ship { x: pos.0, y: pos.1, boat };
+
+        // the whole boat must be inside the board, not only the first cell
+        if ship.cells().any(|(x, y)| !self.in_bounds(x, y)) {
+            return Err(Error::OutOfBounds);
+        }
+        if ship.cells().any(|(x, y)| !check_placement(&x, &y, self)) {
+            return Err(Error::Overlap);
+        }
+        if self.no_touch && ship.cells().any(|(x, y)| self.touches_boat(x, y)) {
+            return Err(Error::Touching);
+        }
+
+        Ok(ship)
+    }
+
+    // una delle 8 celle vicine contiene una nave
+    fn touches_boat(&self, x: usize, y: usize) -> bool {
+        (x.saturating_sub(1)..=(x + 1).min(self.rows - 1))
+            .flat_map(|nx| (y.saturating_sub(1)..=(y + 1).min(self.cols - 1)).map(move |ny| (nx, ny)))
+            .any(|(nx, ny)| !check_placement(&nx, &ny, self))
+    }
+
+    // controlla che la board rispetti le regole: le navi vengono riposizionate una alla volta su una
+    // board vuota e le celle occupate devono corrispondere a quelle delle navi
+    pub fn validate(&self) -> Result<(), Error> {
+        let mut check = Board::with_size(self.rows, self.cols, &self.fleet()).allow_touching(self.touching_allowed());
+        for ship in &self.ships {
+            check.add_boat(ship.boat, (ship.x, ship.y), ship.length() as u8)?;
+        }
+        for x in 0..self.rows {
+            for y in 0..self.cols {
+                let cell = self.data[x][y];
+                let occupied = check.data[x][y] == BOAT;
+                let valid = match cell {
+                    BOAT | HIT => occupied,
+                    WATER | MISS => !occupied,
+                    _ => false,
+                };
+                if !valid {
+                    return Err(Error::Inconsistent);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // toglie tutti i colpi, le navi restano al loro posto
+    pub fn reset_shots(&mut self) {
+        for cell in self.data.iter_mut().flatten() {
+            *cell = match *cell {
+                HIT => BOAT,
+                MISS => WATER,
+                c => c,
+            };
+        }
+    }
+
+    // numero di colpi ricevuti
+    pub fn shots_received(&self) -> usize {
+        self.data.iter().flatten().filter(|&&c| c == HIT || c == MISS).count()
+    }
+
+    // griglia con le coordinate: x sono le righe, y le colonne. Se reveal è falso le navi non ancora
+    // colpite non vengono mostrate (vista dell'avversario)
+    pub fn grid(&self, reveal: bool) -> String {
+        let mut res = String::from("   ");
+        for y in 0..self.cols {
+            res.push_str(&format!("{:>3}", y));
+        }
+        res.push('\n');
+        for (x, row) in self.data.iter().enumerate() {
+            res.push_str(&format!("{:>3}", x));
+            for &cell in row {
+                let symbol = match cell {
+                    BOAT if reveal => '#',
+                    HIT => 'X',
+                    MISS => 'o',
+                    _ => '.',
+                };
+                res.push_str(&format!("{:>3}", symbol));
+            }
+            res.push('\n');
+        }
+        res
+    }
+
+    // colpo nella cella (x, y)
+    pub fn fire(&mut self, x: usize, y: usize) -> Result<ShotResult, Error> {
+        if !self.in_bounds(x, y) {
+            return Err(Error::OutOfBounds);
+        }
+
+        match self.data[x][y] {
+            HIT | MISS => Ok(ShotResult::AlreadyShot),
+            BOAT => {
+                self.data[x][y] = HIT;
+                let ship = self.ships.iter().find(|ship| ship.cells().any(|c| c == (x, y)));
+                match ship {
+                    Some(ship) if self.is_sunk(ship) => Ok(ShotResult::Sunk(ship.length() as u8)),
+                    _ => Ok(ShotResult::Hit),
+                }
+            }
+            _ => {
+                self.data[x][y] = MISS;
+                Ok(ShotResult::Miss)
+            }
+        }
+    }
+
+    // Converte una stringa in struttura Board: file della versione 2 oppure di una versione precedente
+    pub fn from_string(s: &str) -> Result<Self, ParseError> {
+        match format::read(s)? {
+            Some((kind, mut lines)) if kind.value == "board" => {
+                let board = Board::parse_body(&mut lines)?;
+                lines.end()?;
+                Ok(board)
+            },
+            Some((kind, _)) => Err(kind.error(ParseErrorKind::WrongContent { expected: "board", found: kind.value.to_string() })),
+            None => Board::parse_legacy(&mut Lines::new(s, 1), None),
+        }
+    }
+
+    // corpo del file nella versione 2, senza intestazione:
+    //   Size: 10x10
+    //   Boats: 0,0,0,0
+    //   Touching: no
+    //   Ships: v,3,2,4; h,2,0,0
+    //   Data:
+    //   una riga per ogni riga della matrice
+    pub fn body(&self) -> String {
+        let boats: Vec<String> = self.boats.iter().map(|n| n.to_string()).collect();
+        let mut res = format!("Size: {}x{}\n", self.rows, self.cols);
+        res.push_str(&format!("Boats: {}\n", boats.join(",")));
+        res.push_str(&format!("Touching: {}\n", if self.no_touch { "no" } else { "yes" }));
+        res.push_str(&format!("Ships: {}\n", format_ships(&self.ships)));
+        res.push_str("Data:");
+        for row in &self.data {
+            let row: Vec<String> = row.iter().map(|cell| cell.to_string()).collect();
+            res.push('\n');
+            res.push_str(&row.join(" "));
+        }
+        res
+    }
+
+    // legge il corpo scritto da body(), le righe della matrice devono essere esattamente rows
+    pub fn parse_body(lines: &mut Lines) -> Result<Board, ParseError> {
+        let size = lines.field("Size:")?;
+        let (rows, cols) = parse_size(size.value).map_err(|e| size.error(ParseErrorKind::InvalidValue(e)))?;
+        let boats = lines.field("Boats:")?.split(',').map(|n| n.parse()).collect::<Result<Vec<u8>, _>>()?;
+        let touching = lines.field("Touching:")?;
+        let allow = match touching.value {
+            "yes" => true,
+            "no" => false,
+            v => return Err(touching.error(ParseErrorKind::InvalidValue(format!("expected yes or no, found '{}'", v)))),
+        };
+        let ships = lines.field("Ships:")?;
+        lines.expect("Data:")?;
+
+        let mut board = Board::with_size(rows, cols, &boats).allow_touching(allow);
+        for x in 0..rows {
+            let (line, text) = lines.next()
+                .ok_or(ParseError::new(lines.number(), 0, ParseErrorKind::MissingLines { expected: rows, found: x }))?;
+            board.data[x] = parse_row(Field::new(line, text), cols)?;
+        }
+        board.ships = parse_ship_list(ships, &board)?;
+        Ok(board)
+    }
+
+    // formato precedente alla versione 2 (migrazione): "Boats: [4, 3, 2, 1]", le righe facoltative Size,
+    // Ships e Touching, "Data:" e le righe della matrice; le righe vuote restano acqua. stop è la riga che
+    // chiude la board quando nel file c'è altro dopo. Senza la riga Touching le navi possono toccarsi:
+    // la versione originale non controllava la regola, le sue board la possono violare
+    pub fn parse_legacy(lines: &mut Lines, stop: Option<&str>) -> Result<Board, ParseError> {
+        let field = lines.field("Boats:")?;
+        let list = field.value.strip_prefix('[').and_then(|v| v.strip_suffix(']'))
+            .ok_or(field.error(ParseErrorKind::InvalidValue(String::from("expected a list like [4, 3, 2, 1]"))))?;
+        let boats = field.sub(list).split(',').map(|n| n.parse()).collect::<Result<Vec<u8>, _>>()?;
+
+        let (mut rows, mut cols) = (BSIZE, BSIZE);
+        let mut ships = None;
+        let mut allow = true;
+        loop {
+            let (line, text) = lines.next()
+                .ok_or(ParseError::new(lines.number(), 1, ParseErrorKind::MissingField("Data:")))?;
+            let field = Field::new(line, text);
+            match text.split_once(':') {
+                Some(("Data", "")) => break,
+                Some(("Size", value)) => {
+                    let size = field.sub(value.trim());
+                    (rows, cols) = parse_size(size.value).map_err(|e| size.error(ParseErrorKind::InvalidValue(e)))?;
+                },
+                Some(("Ships", value)) => ships = Some(field.sub(value.trim())),
+                Some(("Touching", value)) => allow = value.trim() == "allowed",
+                _ => return Err(field.error(ParseErrorKind::UnexpectedLine)),
+            }
+        }
+
+        let mut board = Board::with_size(rows, cols, &boats).allow_touching(allow);
+        let mut x = 0;
+        while lines.peek().is_some_and(|text| Some(text) != stop) {
+            let Some((line, text)) = lines.next() else { break };
+            if !text.trim().is_empty() {
+                if x >= rows {
+                    return Err(ParseError::new(line, 1, ParseErrorKind::TooManyRows(rows)));
+                }
+                board.data[x] = parse_row(Field::new(line, text), cols)?;
+            }
+            x += 1;
+        }
+
+        board.ships = match ships {
+            Some(ships) => parse_ship_list(ships, &board)?,
+            None => detect_ships(&board),
+        };
+        Ok(board)
+    }
+
+    // controlla che la struttura sia utilizzabile (dimensioni della matrice, celle, navi dentro la board);
+    // serve per i dati che non arrivano dal formato testuale, come il JSON
+    pub fn check_shape(&self) -> Result<(), String> {
+        if self.rows == 0 || self.cols == 0 {
+            return Err(format!("invalid size {}x{}", self.rows, self.cols));
+        }
+        if self.data.len() != self.rows || self.data.iter().any(|row| row.len() != self.cols) {
+            return Err(format!("data is not a {}x{} matrix", self.rows, self.cols));
+        }
+        if let Some(cell) = self.data.iter().flatten().find(|&&cell| cell > MISS) {
+            return Err(format!("invalid cell {}", cell));
+        }
+        for ship in &self.ships {
+            if ship.length() == 0 || ship.length() > self.boats.len() || ship.cells().any(|(x, y)| !self.in_bounds(x, y)) {
+                return Err(format!("invalid ship {}", format_ships(&[*ship])));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn to_json(&self) -> String {
+        format::to_json(self)
+    }
+
+    pub fn from_json(s: &str) -> Result<Board, ParseError> {
+        let board: Board = format::from_json(s)?;
+        board.check_shape().map_err(|e| ParseError::new(0, 0, ParseErrorKind::InvalidValue(e)))?;
+        Ok(board)
+    }
+}
+
+// riga della matrice: cols celle separate da spazi
+fn parse_row(row: Field, cols: usize) -> Result<Vec<u8>, ParseError> {
+    let cells: Vec<Field> = row.words().collect();
+    if cells.len() != cols {
+        // the error points to the first extra cell, or to the end of a short row
+        let column = cells.get(cols).map_or(row.value.len() + 1, |cell| cell.column);
+        return Err(ParseError::new(row.line, column, ParseErrorKind::WrongRowLength { expected: cols, found: cells.len() }));
+    }
+    cells.iter()
+        .map(|cell| match cell.parse()? {
+            c @ WATER..=MISS => Ok(c),
+            c => Err(cell.error(ParseErrorKind::InvalidValue(format!("invalid cell {}", c)))),
+        })
+        .collect()
+}
+
+fn parse_ship_list(ships: Field, board: &Board) -> Result<Vec<Ship>, ParseError> {
+    ships.split(';')
+        .map(|s| parse_ship(s.value, board).map_err(|e| s.error(ParseErrorKind::InvalidValue(e))))
+        .collect()
+}
+
+// dimensione nella forma righe x colonne, es. 10x10
+pub fn parse_size(s: &str) -> Result<(usize, usize), String> {
+    let invalid = || format!("Dimensione non valida: {} (atteso righe x colonne, es. 10x10)", s);
+    let (rows, cols) = s.split_once('x').ok_or_else(invalid)?;
+    let rows: usize = rows.trim().parse().map_err(|_| invalid())?;
+    let cols: usize = cols.trim().parse().map_err(|_| invalid())?;
+    if rows == 0 || cols == 0 {
+        return Err(invalid());
+    }
+    Ok((rows, cols))
+}
+
+// Ships: v,3,2,4; h,2,0,0
+// le navi devono stare nella board e non essere più lunghe della nave più lunga della flotta
+pub fn parse_ships(line: &str, board: &Board) -> Result<Vec<Ship>, String> {
+    line.split(';')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| parse_ship(s, board))
+        .collect()
+}
+
+fn parse_ship(s: &str, board: &Board) -> Result<Ship, String> {
+    let parts: Vec<&str> = s.split(',').collect();
+    let invalid = || format!("Nave non valida: {}", s);
+    if parts.len() != 4 {
+        return Err(invalid());
+    }
+    let len: usize = parts[1].parse().map_err(|_| invalid())?;
+    let x: usize = parts[2].parse().map_err(|_| invalid())?;
+    let y: usize = parts[3].parse().map_err(|_| invalid())?;
+    let boat = match parts[0] {
+        "v" => Boat::Vertical(len),
+        "h" => Boat::Horizontal(len),
+        _ => return Err(invalid()),
+    };
+    let ship = Ship { x, y, boat };
+    if len == 0 || len > board.boats.len() || ship.cells().any(|(x, y)| !board.in_bounds(x, y)) {
+        return Err(invalid());
+    }
+    Ok(ship)
+}
+
+pub fn format_ships(ships: &[Ship]) -> String {
+    let ships: Vec<String> = ships.iter()
+        .map(|ship| match ship.boat {
+            Boat::Vertical(len) => format!("v,{},{},{}", len, ship.x, ship.y),
+            Boat::Horizontal(len) => format!("h,{},{},{}", len, ship.x, ship.y),
+        })
+        .collect();
+    ships.join("; ")
+}
+
+// ricostruisce le navi di un file senza la riga Ships: ogni sequenza orizzontale o verticale di celle
+// occupate è una nave. Da ogni cella si segue la direzione con la sequenza più lunga, così due navi
+// parallele che si toccano non vengono spezzate in tante navi corte nell'altra direzione
+fn detect_ships(board: &Board) -> Vec<Ship> {
+    let max_len = board.boats.len();
+    let mut seen = vec![vec![false; board.cols]; board.rows];
+    let mut ships = Vec::new();
+
+    for x in 0..board.rows {
+        for y in 0..board.cols {
+            let free = |x: usize, y: usize, seen: &Vec<Vec<bool>>| {
+                board.in_bounds(x, y) && !check_placement(&x, &y, board) && !seen[x][y]
+            };
+            if !free(x, y, &seen) {
+                continue;
+            }
+            let run = |dx: usize, dy: usize| {
+                (0..max_len).take_while(|&i| free(x + i * dx, y + i * dy, &seen)).count()
+            };
+            let (horizontal, vertical) = (run(0, 1), run(1, 0));
+            let boat = if horizontal > vertical { Boat::Horizontal(horizontal) } else { Boat::Vertical(vertical) };
+            let ship = Ship { x, y, boat };
+            for (cx, cy) in ship.cells() {
+                seen[cx][cy] = true;
+            }
+            ships.push(ship);
+        }
+    }
+    ships
+}
+
+// Implementazione di Display per una stampa più leggibile, è anche il formato del file board.txt
+impl fmt::Display for Board {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        format::write(f, "board", &self.body())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    Overlap,
+    OutOfBounds,
+    BoatCount,
+    Touching, // la nave tocca un'altra nave, anche solo in diagonale
+    Inconsistent, // la matrice non corrisponde alle navi posizionate
+    NoSpace, // le navi rimanenti non possono essere posizionate
+    FleetIncomplete, // si può sparare solo dopo aver posizionato tutte le navi
+    GameOver,
+    EmptyFleet, // la flotta non ha navi, la partita non potrebbe mai finire
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Overlap => write!(f, "position already used by another boat"),
+            Error::OutOfBounds => write!(f, "coordinate out of bounds"),
+            Error::BoatCount => write!(f, "maximum number of boats of this type already placed"),
+            Error::Touching => write!(f, "boats may not touch, not even diagonally"),
+            Error::Inconsistent => write!(f, "board data does not match the placed boats"),
+            Error::NoSpace => write!(f, "there is no room left for the remaining boats"),
+            Error::FleetIncomplete => write!(f, "both fleets must be placed before firing"),
+            Error::GameOver => write!(f, "the game is over"),
+            Error::EmptyFleet => write!(f, "the fleet has no boats"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Boat {
+    Vertical(usize),
+    Horizontal(usize),
+}
+
+pub fn check_placement(&x:&usize, &y:&usize, b: &Board) -> bool {
+    if b.data[x][y] == BOAT || b.data[x][y] == HIT {
+        return false;
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn board() -> Board {
+        let mut b = Board::new(&[1, 1, 0, 0]);
+        b.add_boat(Boat::Horizontal(2), (0, 0), 2).ok();
+        b.add_boat(Boat::Vertical(1), (5, 5), 1).ok();
+        b
+    }
+
+    #[test]
+    fn test_fire() {
+        let mut b = board();
+        assert_eq!(b.fire(3, 3), Ok(ShotResult::Miss));
+        assert_eq!(b.fire(0, 0), Ok(ShotResult::Hit));
+        assert_eq!(b.fire(0, 0), Ok(ShotResult::AlreadyShot));
+        assert_eq!(b.fire(3, 3), Ok(ShotResult::AlreadyShot));
+        assert!(!b.is_defeated());
+        assert_eq!(b.fire(0, 1), Ok(ShotResult::Sunk(2)));
+        assert_eq!(b.fire(5, 5), Ok(ShotResult::Sunk(1)));
+        assert!(b.is_defeated());
+        assert_eq!(b.fire(BSIZE, 0), Err(Error::OutOfBounds));
+    }
+
+    #[test]
+    fn test_add_boat_errors() {
+        let mut b = Board::new(&[1, 1, 1, 1]);
+        assert_eq!(b.add_boat(Boat::Horizontal(4), (0, BSIZE - 3), 4).err(), Some(Error::OutOfBounds));
+        assert_eq!(b.add_boat(Boat::Vertical(3), (BSIZE - 2, 0), 3).err(), Some(Error::OutOfBounds));
+        assert_eq!(b.add_boat(Boat::Vertical(1), (0, 0), 0).err(), Some(Error::BoatCount));
+        assert_eq!(b.add_boat(Boat::Vertical(5), (0, 0), 5).err(), Some(Error::BoatCount));
+        // failed placements do not use up a boat
+        assert_eq!(b.boats(), &[1, 1, 1, 1]);
+
+        b.add_boat(Boat::Horizontal(4), (0, BSIZE - 4), 4).unwrap();
+        assert_eq!(b.add_boat(Boat::Horizontal(4), (5, 5), 4).err(), Some(Error::BoatCount));
+        assert_eq!(b.add_boat(Boat::Vertical(3), (0, BSIZE - 1), 3).err(), Some(Error::Overlap));
+        assert_eq!(b.boats(), &[1, 1, 1, 0]);
+        assert_eq!(b.ships().len(), 1);
+    }
+
+    #[test]
+    fn test_touching_rule() {
+        let mut b = Board::new(&[0, 3, 0, 0]);
+        b.add_boat(Boat::Horizontal(2), (5, 5), 2).unwrap();
+        assert_eq!(b.add_boat(Boat::Horizontal(2), (6, 7), 2).err(), Some(Error::Touching));
+        assert_eq!(b.add_boat(Boat::Vertical(2), (3, 4), 2).err(), Some(Error::Touching));
+        assert!(b.add_boat(Boat::Vertical(2), (2, 4), 2).is_ok());
+
+        let mut b = Board::new(&[0, 2, 0, 0]).allow_touching(true);
+        b.add_boat(Boat::Horizontal(2), (5, 5), 2).unwrap();
+        assert!(b.add_boat(Boat::Horizontal(2), (6, 6), 2).is_ok());
+        assert!(Board::from_string(&b.to_string()).unwrap().touching_allowed());
+    }
+
+    #[test]
+    fn test_validate_and_reset() {
+        let mut b = board();
+        b.fire(0, 0).unwrap();
+        b.fire(9, 9).unwrap();
+        assert_eq!(b.validate(), Ok(()));
+        assert_eq!(b.shots_received(), 2);
+
+        b.reset_shots();
+        assert_eq!(b, board());
+
+        let mut corrupted = board();
+        corrupted.data[10][10] = BOAT;
+        assert_eq!(corrupted.validate(), Err(Error::Inconsistent));
+    }
+
+    #[test]
+    fn test_grid() {
+        let mut b = board();
+        b.fire(0, 0).unwrap();
+        b.fire(1, 0).unwrap();
+        let grid = b.grid(true);
+        let lines: Vec<&str> = grid.lines().collect();
+        assert_eq!(lines.len(), BSIZE + 1);
+        assert!(lines[0].starts_with("     0  1  2"));
+        assert!(lines[1].starts_with("  0  X  #  ."));
+        assert!(lines[2].starts_with("  1  o  .  ."));
+        assert!(b.grid(false).lines().nth(1).unwrap().starts_with("  0  X  .  ."));
+    }
+
+    #[test]
+    fn test_custom_size_and_fleet() {
+        let mut b = Board::with_size(10, 8, &[0, 0, 0, 0, 0, 1]);
+        assert_eq!(b.add_boat(Boat::Horizontal(6), (0, 3), 6).err(), Some(Error::OutOfBounds));
+        b.add_boat(Boat::Vertical(6), (4, 7), 6).unwrap();
+        assert!(b.is_complete());
+        assert_eq!(b.fire(10, 0), Err(Error::OutOfBounds));
+        assert_eq!(b.fire(9, 7), Ok(ShotResult::Hit));
+
+        let loaded = Board::from_string(&b.to_string()).unwrap();
+        assert_eq!(loaded, b);
+        assert_eq!((loaded.rows(), loaded.cols()), (10, 8));
+        assert_eq!(loaded.grid(true).lines().count(), 11);
+    }
+
+    #[test]
+    fn test_string_roundtrip() {
+        let mut b = board();
+        b.fire(0, 0).unwrap();
+        b.fire(7, 7).unwrap();
+        let loaded = Board::from_string(&b.to_string()).unwrap();
+        assert_eq!(loaded, b);
+        assert_eq!(loaded.fleet(), vec![1, 1, 0, 0]);
+    }
+
+    #[test]
+    fn test_legacy_file_without_ships() {
+        let mut s = String::from("Boats: [0, 0, 0, 0]\nData:\n");
+        for x in 0..BSIZE {
+            let row: Vec<&str> = (0..BSIZE).map(|y| if x == 2 && (4..7).contains(&y) { "1" } else { "0" }).collect();
+            s.push_str(&row.join(" "));
+            s.push_str(" \n");
+        }
+        let b = Board::from_string(&s).unwrap();
+        assert_eq!(b.ships(), &[Ship { x: 2, y: 4, boat: Boat::Horizontal(3) }]);
+        assert!(b.is_complete());
+    }
+
+    #[test]
+    fn test_legacy_file_with_adjacent_ships() {
+        // the original version did not check the no-touch rule: two parallel boats side by side and a
+        // third one touching them diagonally
+        let mut s = String::from("Boats: [3, 3, 2, 1]\nData:\n");
+        for x in 0..BSIZE {
+            let row: Vec<&str> = (0..BSIZE)
+                .map(|y| if (x < 3 && y < 2) || (x, y) == (3, 2) { "1" } else { "0" })
+                .collect();
+            s.push_str(&row.join(" "));
+            s.push_str(" \n");
+        }
+        let b = Board::from_string(&s).unwrap();
+        assert!(b.touching_allowed());
+        assert_eq!(b.ships(), &[
+            Ship { x: 0, y: 0, boat: Boat::Vertical(3) },
+            Ship { x: 0, y: 1, boat: Boat::Vertical(3) },
+            Ship { x: 3, y: 2, boat: Boat::Vertical(1) },
+        ]);
+        assert_eq!(b.fleet(), vec![4, 3, 4, 1]);
+        assert_eq!(b.validate(), Ok(()));
+
+        // the migrated file keeps the rule of the original one
+        let migrated = Board::from_string(&b.to_string()).unwrap();
+        assert_eq!(migrated, b);
+        assert_eq!(migrated.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_format_header_and_whitespace() {
+        let b = board();
+        let s = b.to_string();
+        assert!(s.starts_with("BATTLESHIP 2 board\nChecksum: "));
+        // trailing whitespace and CRLF line endings do not change the content
+        let spaced: String = s.lines().map(|line| format!("{}  \r\n", line)).collect();
+        assert_eq!(Board::from_string(&spaced).unwrap(), b);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let s = board().to_string();
+        let edited = s.replacen("Touching: no", "Touching: yes", 1);
+        let err = Board::from_string(&edited).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 11));
+        assert!(matches!(err.kind, ParseErrorKind::ChecksumMismatch { .. }));
+
+        // the checksum is recomputed so that the body errors are reached
+        let with_body = |body: &str| format!("BATTLESHIP 2 board\nChecksum: {:016x}\n{}", format::checksum(body), body);
+        let body = board().body();
+        let err = Board::from_string(&with_body(&body.replacen("\n0 0 0", "\n0 7 0", 1))).unwrap_err();
+        assert_eq!(err.to_string(), "line 9, column 3: invalid cell 7");
+        let err = Board::from_string(&with_body(&body.replacen("0 0\n", "0 0 0\n", 1))).unwrap_err();
+        assert_eq!(err, ParseError::new(8, 41, ParseErrorKind::WrongRowLength { expected: BSIZE, found: BSIZE + 1 }));
+        let err = Board::from_string(&with_body(&body.replacen("Boats: 0,0", "Boats: 0,x", 1))).unwrap_err();
+        assert_eq!((err.line, err.column), (4, 10));
+        let truncated: Vec<&str> = body.lines().take(10).collect();
+        let err = Board::from_string(&with_body(&truncated.join("\n"))).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::MissingLines { expected: BSIZE, found: 5 });
+        let err = Board::from_string(&with_body(&format!("{}\n0 0", body))).unwrap_err();
+        assert_eq!(err, ParseError::new(BSIZE + 8, 1, ParseErrorKind::UnexpectedLine));
+
+        let err = Board::from_string("Boats: [1, 1]\nSize: 2x2\nData:\n0 0\n0 0\n0 1").unwrap_err();
+        assert_eq!(err, ParseError::new(6, 1, ParseErrorKind::TooManyRows(2)));
+        assert_eq!(Board::from_string("Boats: [1]").unwrap_err().to_string(), "line 2, column 1: expected 'Data:'");
+    }
+
+    #[test]
+    fn test_legacy_migration() {
+        // format with Size and Ships, before the header was introduced
+        let legacy = "Boats: [0, 0]\nSize: 3x4\nShips: h,2,1,1\nTouching: allowed\nData:\n0 0 0 0\n0 1 2 0\n0 0 0 0";
+        let b = Board::from_string(legacy).unwrap();
+        assert_eq!((b.rows(), b.cols()), (3, 4));
+        assert_eq!(b.ships(), &[Ship { x: 1, y: 1, boat: Boat::Horizontal(2) }]);
+        assert!(b.touching_allowed());
+        assert_eq!(Board::from_string(&b.to_string()).unwrap(), b);
+    }
+
+    #[test]
+    fn test_json() {
+        let mut b = board();
+        b.fire(0, 1).unwrap();
+        let json = b.to_json();
+        assert!(json.contains("\"version\": 2"));
+        assert_eq!(Board::from_json(&json).unwrap(), b);
+
+        let err = Board::from_json("{\"version\": 2,\n \"rows\": -1}").unwrap_err();
+        assert_eq!(err.line, 2);
+        assert!(matches!(err.kind, ParseErrorKind::Json(_)));
+        let broken = json.replacen("\"rows\": 20", "\"rows\": 21", 1);
+        assert_eq!(Board::from_json(&broken).unwrap_err().to_string(), "data is not a 21x20 matrix");
+        let old = json.replacen("\"version\": 2", "\"version\": 1", 1);
+        assert_eq!(Board::from_json(&old).unwrap_err().kind, ParseErrorKind::UnsupportedVersion(String::from("1")));
+    }
+}
@@ -0,0 +1,296 @@
+use std::error;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+
+/* formato dei file */
+
+// Un file della versione 2 inizia con due righe di intestazione:
+//
+//   BATTLESHIP 2 game
+//   Checksum: 3f0c6a1e8d2b9a47
+//
+// seguite dal corpo (una board o una partita). Il checksum è l'FNV-1a a 64 bit del corpo senza gli spazi
+// in fondo alle righe e senza le righe vuote finali, così un file modificato a mano viene riconosciuto
+// mentre uno salvato da un editor che toglie gli spazi resta valido.
+// I file senza intestazione sono quelli delle versioni precedenti: vengono letti dal parser legacy e
+// riscritti nella versione 2 al primo salvataggio.
+
+pub const MAGIC: &str = "BATTLESHIP";
+pub const VERSION: u32 = 2;
+
+// errore di lettura di un file: riga e colonna partono da 1, 0 se non sono note (es. JSON non valido
+// nella forma ma corretto nella sintassi)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: ParseErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    UnsupportedVersion(String),
+    WrongContent { expected: &'static str, found: String }, // es. una partita letta come board
+    ChecksumMismatch { expected: String, found: String },
+    MissingField(&'static str),
+    InvalidNumber(String),
+    InvalidValue(String),
+    WrongRowLength { expected: usize, found: usize },
+    TooManyRows(usize),
+    MissingLines { expected: usize, found: usize },
+    UnexpectedLine,
+    Json(String),
+}
+
+impl ParseError {
+    pub fn new(line: usize, column: usize, kind: ParseErrorKind) -> ParseError {
+        ParseError { line, column, kind }
+    }
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseErrorKind::UnsupportedVersion(v) => write!(f, "unsupported format version {} (expected {})", v, VERSION),
+            ParseErrorKind::WrongContent { expected, found } => write!(f, "expected a {} file, found a {} file", expected, found),
+            ParseErrorKind::ChecksumMismatch { expected, found } => {
+                write!(f, "checksum mismatch: the header says {}, the content gives {}", expected, found)
+            },
+            ParseErrorKind::MissingField(key) => write!(f, "expected '{}'", key),
+            ParseErrorKind::InvalidNumber(s) => write!(f, "invalid number '{}'", s),
+            ParseErrorKind::InvalidValue(msg) => write!(f, "{}", msg),
+            ParseErrorKind::WrongRowLength { expected, found } => write!(f, "expected {} cells, found {}", expected, found),
+            ParseErrorKind::TooManyRows(rows) => write!(f, "too many rows, expected {}", rows),
+            ParseErrorKind::MissingLines { expected, found } => write!(f, "expected {} lines, found {}", expected, found),
+            ParseErrorKind::UnexpectedLine => write!(f, "unexpected line"),
+            ParseErrorKind::Json(msg) => write!(f, "invalid JSON: {}", msg),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.line, self.column) {
+            (0, _) => write!(f, "{}", self.kind),
+            (line, 0) => write!(f, "line {}: {}", line, self.kind),
+            (line, column) => write!(f, "line {}, column {}: {}", line, column, self.kind),
+        }
+    }
+}
+
+impl error::Error for ParseError {}
+
+// FNV-1a a 64 bit del corpo normalizzato
+pub fn checksum(body: &str) -> u64 {
+    let lines: Vec<&str> = body.lines().map(str::trim_end).collect();
+    let end = lines.iter().rposition(|line| !line.is_empty()).map_or(0, |i| i + 1);
+    lines[..end].join("\n").bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+// scrive intestazione e corpo, kind è "board" o "game"
+pub fn write(f: &mut fmt::Formatter<'_>, kind: &str, body: &str) -> fmt::Result {
+    writeln!(f, "{} {} {}", MAGIC, VERSION, kind)?;
+    writeln!(f, "Checksum: {:016x}", checksum(body))?;
+    write!(f, "{}", body)
+}
+
+// legge e controlla l'intestazione: restituisce il tipo di contenuto e le righe del corpo,
+// None se il testo non ha intestazione (formato precedente)
+pub fn read(s: &str) -> Result<Option<(Field<'_>, Lines<'_>)>, ParseError> {
+    let mut parts = s.splitn(3, '\n');
+    let header = Field::new(1, parts.next().unwrap_or("").trim_end());
+    if !header.value.starts_with(MAGIC) {
+        return Ok(None);
+    }
+
+    let words: Vec<Field> = header.words().collect();
+    match words.get(1) {
+        Some(version) if version.value == VERSION.to_string() => {},
+        Some(version) => return Err(version.error(ParseErrorKind::UnsupportedVersion(version.value.to_string()))),
+        None => return Err(header.error(ParseErrorKind::MissingField("version"))),
+    }
+    let kind = match &words[..] {
+        [_, _, kind] => *kind,
+        [_, _, _, extra, ..] => return Err(extra.error(ParseErrorKind::InvalidValue(format!("unexpected '{}'", extra.value)))),
+        _ => return Err(header.error(ParseErrorKind::MissingField("board or game"))),
+    };
+
+    let mut checksum_line = Lines::new(parts.next().unwrap_or(""), 2);
+    let expected = checksum_line.field("Checksum:")?;
+    let body = parts.next().unwrap_or("");
+    let found = format!("{:016x}", checksum(body));
+    if !expected.value.eq_ignore_ascii_case(&found) {
+        return Err(expected.error(ParseErrorKind::ChecksumMismatch { expected: expected.value.to_string(), found }));
+    }
+    Ok(Some((kind, Lines::new(body, 3))))
+}
+
+/* righe del file */
+
+// cursore sulle righe di un file che tiene il numero di riga per gli errori; le righe sono già senza
+// spazi finali (e senza \r)
+pub struct Lines<'a> {
+    lines: Vec<&'a str>,
+    pos: usize,
+    first: usize, // numero della prima riga nel file
+}
+
+impl<'a> Lines<'a> {
+    pub fn new(s: &'a str, first: usize) -> Lines<'a> {
+        Lines { lines: s.lines().map(str::trim_end).collect(), pos: 0, first }
+    }
+
+    // numero della prossima riga
+    pub fn number(&self) -> usize {
+        self.first + self.pos
+    }
+
+    pub fn peek(&self) -> Option<&'a str> {
+        self.lines.get(self.pos).copied()
+    }
+
+    // la prossima riga deve essere "key valore", es. "Size: 10x10"
+    pub fn field(&mut self, key: &'static str) -> Result<Field<'a>, ParseError> {
+        let Some((line, text)) = self.next() else {
+            return Err(ParseError::new(self.number(), 1, ParseErrorKind::MissingField(key)));
+        };
+        let rest = text.strip_prefix(key).ok_or(ParseError::new(line, 1, ParseErrorKind::MissingField(key)))?;
+        Ok(Field::new(line, text).sub(rest.trim_start()))
+    }
+
+    // riga che contiene solo key, es. "Data:"
+    pub fn expect(&mut self, key: &'static str) -> Result<(), ParseError> {
+        let field = self.field(key)?;
+        match field.value {
+            "" => Ok(()),
+            _ => Err(field.error(ParseErrorKind::UnexpectedLine)),
+        }
+    }
+
+    // dopo il contenuto possono esserci solo righe vuote
+    pub fn end(&mut self) -> Result<(), ParseError> {
+        match self.find(|(_, text)| !text.is_empty()) {
+            Some((line, _)) => Err(ParseError::new(line, 1, ParseErrorKind::UnexpectedLine)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<'a> Iterator for Lines<'a> {
+    type Item = (usize, &'a str);
+
+    fn next(&mut self) -> Option<(usize, &'a str)> {
+        let text = self.peek()?;
+        let line = self.number();
+        self.pos += 1;
+        Some((line, text))
+    }
+}
+
+// parte di una riga con la sua posizione, serve per riportare gli errori alla colonna giusta
+#[derive(Debug, Clone, Copy)]
+pub struct Field<'a> {
+    pub line: usize,
+    pub column: usize,
+    pub value: &'a str,
+}
+
+impl<'a> Field<'a> {
+    // riga intera
+    pub fn new(line: usize, text: &'a str) -> Field<'a> {
+        Field { line, column: 1, value: text }
+    }
+
+    // part deve essere una sottostringa di value
+    pub fn sub(&self, part: &'a str) -> Field<'a> {
+        let offset = part.as_ptr() as usize - self.value.as_ptr() as usize;
+        Field { line: self.line, column: self.column + offset, value: part }
+    }
+
+    pub fn words(&self) -> impl Iterator<Item = Field<'a>> + '_ {
+        self.value.split_whitespace().map(|w| self.sub(w))
+    }
+
+    // elementi separati da sep, senza spazi e senza elementi vuoti
+    pub fn split(&self, sep: char) -> impl Iterator<Item = Field<'a>> + '_ {
+        self.value.split(sep).map(str::trim).filter(|s| !s.is_empty()).map(|s| self.sub(s))
+    }
+
+    pub fn error(&self, kind: ParseErrorKind) -> ParseError {
+        ParseError::new(self.line, self.column, kind)
+    }
+
+    pub fn parse<T: FromStr>(&self) -> Result<T, ParseError> {
+        self.value.parse().map_err(|_| self.error(ParseErrorKind::InvalidNumber(self.value.to_string())))
+    }
+}
+
+/* JSON */
+
+// il JSON contiene la versione del formato accanto ai campi della board o della partita
+#[derive(Serialize, Deserialize)]
+struct Versioned<T> {
+    version: u32,
+    #[serde(flatten)]
+    content: T,
+}
+
+pub fn to_json<T: Serialize>(content: &T) -> String {
+    serde_json::to_string_pretty(&Versioned { version: VERSION, content })
+        .expect("boards and games have only string keys")
+}
+
+pub fn from_json<T: DeserializeOwned>(s: &str) -> Result<T, ParseError> {
+    let doc: Versioned<T> = serde_json::from_str(s).map_err(|e| {
+        // the position is already in line and column, not in the message
+        let msg = e.to_string();
+        let msg = msg.split(" at line ").next().unwrap_or(&msg).to_string();
+        ParseError::new(e.line(), e.column(), ParseErrorKind::Json(msg))
+    })?;
+    if doc.version != VERSION {
+        return Err(ParseError::new(0, 0, ParseErrorKind::UnsupportedVersion(doc.version.to_string())));
+    }
+    Ok(doc.content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checksum_ignores_trailing_whitespace() {
+        let body = "Size: 2x2\nData:\n0 1\n1 0";
+        assert_eq!(checksum(body), checksum("Size: 2x2 \r\nData:\n0 1\t\n1 0\n\n"));
+        assert_ne!(checksum(body), checksum("Size: 2x2\nData:\n0 1\n1 1"));
+        // FNV-1a test vector
+        assert_eq!(checksum("a"), 0xaf63dc4c8601ec8c);
+    }
+
+    #[test]
+    fn test_fields_and_errors() {
+        let mut lines = Lines::new("Size:  10x8\nBoats: 1, x", 3);
+        let size = lines.field("Size:").unwrap();
+        assert_eq!((size.line, size.column, size.value), (3, 8, "10x8"));
+        let boats = lines.field("Boats:").unwrap();
+        let err = boats.split(',').map(|n| n.parse::<u8>()).collect::<Result<Vec<u8>, _>>().unwrap_err();
+        assert_eq!(err, ParseError::new(4, 11, ParseErrorKind::InvalidNumber(String::from("x"))));
+        assert_eq!(err.to_string(), "line 4, column 11: invalid number 'x'");
+        assert_eq!(lines.field("Data:").unwrap_err().to_string(), "line 5, column 1: expected 'Data:'");
+    }
+
+    #[test]
+    fn test_header() {
+        assert!(read("Boats: [1]\nData:").unwrap().is_none());
+        let err = read("BATTLESHIP 3 board\nChecksum: 0\n").err().unwrap();
+        assert_eq!((err.line, err.column), (1, 12));
+        assert_eq!(err.kind, ParseErrorKind::UnsupportedVersion(String::from("3")));
+        let err = read("BATTLESHIP 2 board\nChecksum: 0000000000000000\nSize: 1x1").err().unwrap();
+        assert_eq!((err.line, err.column), (2, 11));
+    }
+}
@@ -0,0 +1,343 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::board::{Board, Error, ShotResult, BSIZE};
+use crate::format::{self, Field, Lines, ParseError, ParseErrorKind};
+
+
+/* struct Game */
+
+// colpo sparato da un giocatore (0 o 1) sulla board dell'avversario
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Shot {
+    pub player: usize,
+    pub x: usize,
+    pub y: usize,
+    pub result: ShotResult,
+}
+
+// partita tra due giocatori: ogni giocatore posiziona le navi sulla propria board e spara su quella dell'avversario
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Game {
+    boards: [Board; 2],
+    turn: usize,
+    shots: Vec<Shot>,
+    winner: Option<usize>,
+}
+
+impl Game {
+    // nuova partita, entrambi i giocatori hanno la stessa disponibilità di navi
+    pub fn new(boats: &[u8]) -> Game {
+        Game::with_size(BSIZE, BSIZE, boats)
+    }
+
+    // nuova partita su board di rows x cols celle
+    pub fn with_size(rows: usize, cols: usize, boats: &[u8]) -> Game {
+        let board = Board::with_size(rows, cols, boats);
+        Game { boards: [board.clone(), board], turn: 0, shots: Vec::new(), winner: None }
+    }
+
+    // partita a partire dalla board di un vecchio board.txt: diventa la board del primo giocatore,
+    // l'avversario riceve una board vuota con la stessa flotta
+    pub fn from_board(board: Board) -> Game {
+        let opponent = Board::with_size(board.rows(), board.cols(), &board.fleet())
+            .allow_touching(board.touching_allowed());
+        Game { boards: [board, opponent], turn: 0, shots: Vec::new(), winner: None }
+    }
+
+    // regola delle navi che non si toccano, per entrambe le board
+    pub fn allow_touching(self, allow: bool) -> Game {
+        let [first, second] = self.boards;
+        Game { boards: [first.allow_touching(allow), second.allow_touching(allow)], ..self }
+    }
+
+    pub fn board(&self, player: usize) -> &Board {
+        &self.boards[player]
+    }
+
+    pub fn board_mut(&mut self, player: usize) -> &mut Board {
+        &mut self.boards[player]
+    }
+
+    // giocatore che deve sparare
+    pub fn turn(&self) -> usize {
+        self.turn
+    }
+
+    pub fn winner(&self) -> Option<usize> {
+        self.winner
+    }
+
+    pub fn shots(&self) -> &[Shot] {
+        &self.shots
+    }
+
+    // il giocatore di turno spara sulla board dell'avversario; il turno passa all'altro giocatore,
+    // tranne quando la cella era già stata colpita
+    pub fn fire(&mut self, x: usize, y: usize) -> Result<ShotResult, Error> {
+        if self.winner.is_some() {
+            return Err(Error::GameOver);
+        }
+        if !self.boards.iter().all(Board::is_complete) {
+            return Err(Error::FleetIncomplete);
+        }
+
+        let opponent = 1 - self.turn;
+        let result = self.boards[opponent].fire(x, y)?;
+        if result == ShotResult::AlreadyShot {
+            return Ok(result);
+        }
+
+        self.shots.push(Shot { player: self.turn, x, y, result });
+        if self.boards[opponent].is_defeated() {
+            self.winner = Some(self.turn);
+        } else {
+            self.turn = opponent;
+        }
+        Ok(result)
+    }
+
+    // controlla le due board e che lo storico dei colpi corrisponda ai colpi presenti sulle board
+    pub fn validate(&self) -> Result<(), String> {
+        for (player, board) in self.boards.iter().enumerate() {
+            board.validate().map_err(|e| format!("board {}: {}", player + 1, e))?;
+            let fired = self.shots.iter().filter(|shot| shot.player != player).count();
+            if fired != board.shots_received() {
+                return Err(format!("board {}: {} shots received, {} in the history", player + 1, board.shots_received(), fired));
+            }
+        }
+        let defeated = self.boards.iter().position(Board::is_defeated);
+        if self.winner.is_some() && self.winner.map(|w| 1 - w) != defeated {
+            return Err(String::from("the winner does not match the boards"));
+        }
+        Ok(())
+    }
+
+    // ricomincia la partita con le navi già posizionate
+    pub fn reset(&mut self) {
+        for board in self.boards.iter_mut() {
+            board.reset_shots();
+        }
+        self.shots.clear();
+        self.turn = 0;
+        self.winner = None;
+    }
+
+    // Converte una stringa (contenuto di board.txt) in una partita. Accetta anche un file con una sola board
+    // e i file delle versioni precedenti, che al salvataggio vengono riscritti nella versione 2
+    pub fn from_string(s: &str) -> Result<Game, ParseError> {
+        match format::read(s)? {
+            Some((kind, mut lines)) => {
+                let game = match kind.value {
+                    "game" => Game::parse_body(&mut lines, false)?,
+                    "board" => Game::from_board(Board::parse_body(&mut lines)?),
+                    found => return Err(kind.error(ParseErrorKind::WrongContent { expected: "game", found: found.to_string() })),
+                };
+                lines.end()?;
+                Ok(game)
+            },
+            None if s.starts_with("Boats:") => Board::from_string(s).map(Game::from_board),
+            None => Game::parse_body(&mut Lines::new(s, 1), true),
+        }
+    }
+
+    // stato della partita, storico dei colpi e le due board; legacy indica il formato precedente alla
+    // versione 2, in cui le board sono nel vecchio formato
+    fn parse_body(lines: &mut Lines, legacy: bool) -> Result<Game, ParseError> {
+        let player = |field: Field| -> Result<usize, ParseError> {
+            match field.value {
+                "1" => Ok(0),
+                "2" => Ok(1),
+                p => Err(field.error(ParseErrorKind::InvalidValue(format!("invalid player '{}', expected 1 or 2", p)))),
+            }
+        };
+
+        let turn = player(lines.field("Turn:")?)?;
+        let winner = lines.field("Winner:")?;
+        let winner = match winner.value {
+            "-" => None,
+            _ => Some(player(winner)?),
+        };
+        let count: usize = lines.field("Shots:")?.parse()?;
+
+        let mut shots = Vec::with_capacity(count);
+        for i in 0..count {
+            let (line, text) = lines.next()
+                .ok_or(ParseError::new(lines.number(), 0, ParseErrorKind::MissingLines { expected: count, found: i }))?;
+            let field = Field::new(line, text);
+            // player x y result, the result can have two words (sunk 3)
+            let parts: Vec<Field> = text.splitn(4, ' ').map(|part| field.sub(part)).collect();
+            let [shot_player, x, y, result] = parts[..] else {
+                return Err(field.error(ParseErrorKind::InvalidValue(String::from("expected 'player x y result'"))));
+            };
+            let result = ShotResult::parse(result.value)
+                .ok_or(result.error(ParseErrorKind::InvalidValue(format!("invalid shot result '{}'", result.value))))?;
+            shots.push(Shot { player: player(shot_player)?, x: x.parse()?, y: y.parse()?, result });
+        }
+
+        // the two boards follow, each one introduced by its own header line
+        lines.expect("Board 1:")?;
+        let first_board = match legacy {
+            true => Board::parse_legacy(lines, Some("Board 2:"))?,
+            false => Board::parse_body(lines)?,
+        };
+        lines.expect("Board 2:")?;
+        let second_board = match legacy {
+            true => Board::parse_legacy(lines, None)?,
+            false => Board::parse_body(lines)?,
+        };
+
+        Ok(Game { boards: [first_board, second_board], turn, shots, winner })
+    }
+
+    // corpo del file nella versione 2, senza intestazione
+    pub fn body(&self) -> String {
+        let mut res = format!("Turn: {}\n", self.turn + 1);
+        match self.winner {
+            Some(w) => res.push_str(&format!("Winner: {}\n", w + 1)),
+            None => res.push_str("Winner: -\n"),
+        }
+        res.push_str(&format!("Shots: {}\n", self.shots.len()));
+        for shot in &self.shots {
+            res.push_str(&format!("{} {} {} {}\n", shot.player + 1, shot.x, shot.y, shot.result));
+        }
+        for (i, board) in self.boards.iter().enumerate() {
+            res.push_str(&format!("Board {}:\n{}\n", i + 1, board.body()));
+        }
+        res
+    }
+
+    pub fn to_json(&self) -> String {
+        format::to_json(self)
+    }
+
+    // il JSON non passa dal parser testuale: turno, giocatori e board vengono controllati qui
+    pub fn from_json(s: &str) -> Result<Game, ParseError> {
+        let game: Game = format::from_json(s)?;
+        let invalid = |msg: String| ParseError::new(0, 0, ParseErrorKind::InvalidValue(msg));
+        for (i, board) in game.boards.iter().enumerate() {
+            board.check_shape().map_err(|e| invalid(format!("board {}: {}", i + 1, e)))?;
+        }
+        if game.turn > 1 || game.winner.is_some_and(|w| w > 1) || game.shots.iter().any(|shot| shot.player > 1) {
+            return Err(invalid(String::from("players are 0 and 1")));
+        }
+        Ok(game)
+    }
+}
+
+// formato di board.txt: intestazione, stato della partita, storico dei colpi e le due board
+impl fmt::Display for Game {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        format::write(f, "game", &self.body())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Boat;
+
+    fn ready_game() -> Game {
+        let mut game = Game::new(&[1, 1, 0, 0]);
+        for player in 0..2 {
+            let board = game.board_mut(player);
+            board.add_boat(Boat::Horizontal(2), (0, 0), 2).ok();
+            board.add_boat(Boat::Vertical(1), (4, 4), 1).ok();
+        }
+        game
+    }
+
+    #[test]
+    fn test_fire_requires_complete_fleets() {
+        let mut game = Game::new(&[1, 0, 0, 0]);
+        assert_eq!(game.fire(0, 0), Err(Error::FleetIncomplete));
+    }
+
+    #[test]
+    fn test_turns_and_winner() {
+        let mut game = ready_game();
+        assert_eq!(game.fire(0, 0), Ok(ShotResult::Hit));
+        assert_eq!(game.turn(), 1);
+        assert_eq!(game.fire(9, 9), Ok(ShotResult::Miss));
+        // a cell already shot does not use the turn
+        assert_eq!(game.fire(0, 0), Ok(ShotResult::AlreadyShot));
+        assert_eq!(game.turn(), 0);
+        assert_eq!(game.fire(0, 1), Ok(ShotResult::Sunk(2)));
+        game.fire(8, 8).unwrap();
+        assert_eq!(game.fire(4, 4), Ok(ShotResult::Sunk(1)));
+        assert_eq!(game.winner(), Some(0));
+        assert_eq!(game.fire(1, 1), Err(Error::GameOver));
+        assert_eq!(game.shots().len(), 5);
+    }
+
+    #[test]
+    fn test_string_roundtrip() {
+        let mut game = ready_game();
+        game.fire(0, 0).unwrap();
+        game.fire(3, 3).unwrap();
+        game.fire(4, 4).unwrap();
+        let loaded = Game::from_string(&game.to_string()).unwrap();
+        assert_eq!(loaded, game);
+    }
+
+    #[test]
+    fn test_validate_and_reset() {
+        let mut game = ready_game();
+        game.fire(0, 0).unwrap();
+        game.fire(3, 3).unwrap();
+        assert_eq!(game.validate(), Ok(()));
+
+        let mut tampered = game.clone();
+        tampered.shots.pop();
+        assert!(tampered.validate().is_err());
+
+        game.reset();
+        assert_eq!(game, ready_game());
+    }
+
+    #[test]
+    fn test_legacy_board_file() {
+        let board = Board::new(&[4, 3, 2, 1]);
+        let game = Game::from_string(&board.to_string()).unwrap();
+        assert_eq!(game.board(0), &board);
+        assert_eq!(game.board(1).boats(), &[4, 3, 2, 1]);
+    }
+
+    #[test]
+    fn test_legacy_game_file() {
+        let mut game = ready_game();
+        game.fire(0, 0).unwrap();
+        game.fire(3, 3).unwrap();
+        // game file written before the format version 2: no header, boards in the old format
+        let boards: Vec<String> = (0..2)
+            .map(|i| {
+                let board = game.board(i);
+                let rows: Vec<String> = board.data().iter()
+                    .map(|row| row.iter().map(|c| c.to_string()).collect::<Vec<_>>().join(" "))
+                    .collect();
+                format!("Board {}:\nBoats: {:?}\nSize: 20x20\nShips: {}\nData:\n{}",
+                    i + 1, board.boats(), crate::board::format_ships(board.ships()), rows.join("\n"))
+            })
+            .collect();
+        let legacy = format!("Turn: 1\nWinner: -\nShots: 2\n1 0 0 hit\n2 3 3 miss\n{}\n{}\n", boards[0], boards[1]);
+        // without the Touching line the old boards allow touching ships
+        assert_eq!(Game::from_string(&legacy).unwrap(), game.allow_touching(true));
+
+        let err = Game::from_string("Turn: 3\nWinner: -").unwrap_err();
+        assert_eq!(err.to_string(), "line 1, column 7: invalid player '3', expected 1 or 2");
+    }
+
+    #[test]
+    fn test_json_roundtrip() {
+        let mut game = ready_game();
+        game.fire(0, 0).unwrap();
+        game.fire(4, 4).unwrap();
+        let loaded = Game::from_json(&game.to_json()).unwrap();
+        assert_eq!(loaded, game);
+
+        let tampered = game.to_json().replacen("\"turn\": 0", "\"turn\": 2", 1);
+        assert!(Game::from_json(&tampered).is_err());
+        assert!(Game::from_string(&game.to_json()).is_err());
+    }
+}
@@ -1,287 +1,351 @@
-use std::{env};
 use std::fs::File;
 use std::io::{self, BufRead, Read, Write};
-use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Duration;
 
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
 
-/** I-O file **/
+pub mod ai;
+pub mod board;
+pub mod format;
+pub mod game;
+pub mod net;
+
+use board::Boat;
+use game::Game;
+
+
+/* I-O file */
 
 const FILENAME: &str = "board.txt";
-fn read_file_content(filename: &str) -> io::Result<String> {
+fn read_file_content(filename: &Path) -> io::Result<String> {
     let mut file = File::open(filename)?;
     let mut contents = String::new();
     file.read_to_string(&mut contents)?;
     Ok(contents)
 }
-fn write_to_file(filename: &str, content: &str) -> io::Result<()> {
+fn write_to_file(filename: &Path, content: &str) -> io::Result<()> {
     let mut file = File::create(filename)?;
     file.write_all(content.as_bytes())?;
     Ok(())
 }
 
-// Implementazione di Display per una stampa più leggibile
-impl fmt::Display for Board {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Boats: {:?}\nData:\n", self.boats)?;
-        for row in &self.data {
-            for &cell in row {
-                write!(f, "{} ", cell)?;
-            }
-            writeln!(f)?;
-        }
-        Ok(())
-    }
-}
-
+/* CLI */
 
-/** struct Board **/
+#[derive(Parser)]
+#[command(name = "battle_ship", about = "Battaglia navale a due giocatori, la partita è salvata su file")]
+struct Cli {
+    /// File con lo stato della partita
+    #[arg(long, global = true, value_name = "PATH", default_value = FILENAME)]
+    board: PathBuf,
 
-const BSIZE: usize = 20;
-
-pub struct Board {
-    boats: [u8; 4],
-    data: [[u8; BSIZE]; BSIZE], //NOTE: 0 equals to space and 1 equals to B respect to the text of the lab
+    #[command(subcommand)]
+    command: Command,
 }
-impl Board {
-    // crea una board vuota con una disponibilità di navi
-    pub fn new(boats: &[u8]) -> Board {
-        let mut b = [0; 4];
-        for (i, &boat) in boats.iter().enumerate() {
-            b[i] = boat;
-            println!("{}", boat);
-        }
-
-        Board{ boats: b, data: [[0; BSIZE]; BSIZE] }
-    }
 
-    /* crea una board a partire da una stringa che rappresenta tutto il contenuto del file board.txt */
-    pub fn from(s: String) -> Board {
-        Board::from_string(&s).unwrap_or_else(|e| {
-            eprintln!("Errore nel parsing: {}", e);
-            // Puoi restituire un board vuoto o fare altro
-            Board::new(&[4, 3, 2, 1])
-        })
-    }
-
-    /* aggiunge la nave alla board, restituendo la nuova board se possibile */
-    /* bonus: provare a *non copiare* data quando si crea e restituisce una nuova board con la barca, come si può fare? */
-    pub fn add_boat(&mut self, boat: Boat, pos: (usize, usize), boat_type: u8) -> Result<&mut Board, Error> {
-        // decrement number of boat available
-        let index = (boat_type.saturating_sub(1)) as usize;
-        self.boats[index] -= 1;
-
-        // update board
-        match boat {
-            Boat::Vertical(y) => {
-                for i in 0..boat_type as usize {
-                    if !check_placement(&(pos.0 + i), &pos.1, &self){
-                        panic!("Position already used by another boat");
-                    }
-                }
-                for i in 0..boat_type as usize {
-                    self.data[pos.0 + i][pos.1] = 1;
-                }
-            },
-            Boat::Horizontal(x) => {
-                for i in 0..boat_type as usize {
-                    if !check_placement(&pos.0, &(pos.1 + i), &self){
-                        panic!("Position already used by another boat");
-                    }
-                }
-                for i in 0..boat_type as usize {
-                    self.data[pos.0][pos.1 + i] = 1;
-                }
-            }
-        }
-
-        Ok(self)
-    }
-
-    /* converte la board in una stringa salvabile su file */
-    // Converte la struttura Board in una stringa
-    pub fn to_string(&self) -> String {
-        // Converti boats in stringa
-        let boats_str = format!("{:?}", self.boats);
-
-        // Converti data in stringa
-        let data_str = self.data
-            .iter()
-            .map(|row|
-                row.iter()
-                    .map(|&num| num.to_string())
-                    .collect::<Vec<String>>()
-                    .join(" ")
-            )
-            .collect::<Vec<String>>()
-            .join("\n");
-
-        format!("Boats: {}\nData:\n{}", boats_str, data_str)
-    }
-
-    // Converte una stringa in struttura Board
-    pub fn from_string(s: &str) -> Result<Self, String> {
-        // Splitta la stringa in righe
-        let lines: Vec<&str> = s.split('\n').collect();
-
-        // Estrai boats
-        let boats_str = lines[0].trim_start_matches("Boats: ").trim_matches(&['[', ']']);
-        let boats: [u8; 4] = boats_str
-            .split(", ")
-            .map(|x| x.parse().map_err(|_| "Errore nel parsing dei boats"))
-            .collect::<Result<Vec<u8>, _>>()?
-            .try_into()
-            .map_err(|_| "Numero di boats non corretto")?;
-
-        // Converti data
-        let mut data = [[0u8; BSIZE]; BSIZE];
-        for (i, line) in lines[2..].iter().enumerate() {
-            if !line.is_empty() {
-                let row: Vec<u8> = line
-                    .split_whitespace()
-                    .map(|x| x.parse().map_err(|_| "Errore nel parsing dei dati"))
-                    .collect::<Result<Vec<u8>, _>>()?;
-
-                if row.len() != BSIZE {
-                    return Err(format!("Lunghezza riga {} invalida. Atteso {}, trovato {}", i, BSIZE, row.len()));
-                }
-
-                data[i].copy_from_slice(&row);
-            }
-        }
+#[derive(Subcommand)]
+enum Command {
+    /// Crea una nuova partita, sovrascrivendo il file
+    New {
+        /// Numero di navi per lunghezza (1, 2, 3, ...), es. 4,3,2,1 oppure 0,0,1,2,1,1
+        #[arg(value_delimiter = ',', default_value = "4,3,2,1")]
+        boats: Vec<u8>,
+        /// Dimensione della board, righe x colonne
+        #[arg(long, value_name = "ROWSxCOLS", default_value = "20x20", value_parser = board::parse_size)]
+        size: (usize, usize),
+        /// Permette alle navi di toccarsi
+        #[arg(long)]
+        allow_touch: bool,
+    },
+    /// Posiziona una nave sulla board di un giocatore
+    AddBoat {
+        #[command(flatten)]
+        boat: PlaceBoat,
+        /// Giocatore proprietario della board
+        #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u8).range(1..=2))]
+        player: u8,
+    },
+    /// Posiziona a caso le navi ancora da posizionare di un giocatore
+    Place {
+        #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u8).range(1..=2))]
+        player: u8,
+        /// Seme del generatore casuale, per ottenere sempre la stessa disposizione
+        #[arg(long)]
+        seed: Option<u64>,
+    },
+    /// Mostra la partita
+    Show {
+        /// Mostra la partita dal punto di vista del giocatore: le navi avversarie non colpite sono nascoste
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        player: Option<u8>,
+    },
+    /// Il giocatore di turno spara sulla board dell'avversario
+    Fire {
+        x: usize,
+        y: usize,
+    },
+    /// Controlla che il file rispetti le regole del gioco
+    Validate,
+    /// Ricomincia la partita lasciando le navi al loro posto
+    Reset,
+    /// Riscrive un file di una versione precedente nel formato attuale
+    Migrate,
+    /// Esporta la partita in JSON (per il frontend web)
+    Export {
+        /// File JSON da scrivere, altrimenti stampa su stdout
+        #[arg(long, value_name = "PATH")]
+        output: Option<PathBuf>,
+    },
+    /// Importa una partita in JSON, sovrascrivendo il file
+    Import {
+        /// File JSON da leggere
+        json: PathBuf,
+    },
+    /// Ospita una partita in rete: attende l'avversario e spara per primo
+    Host {
+        #[command(flatten)]
+        net: NetArgs,
+    },
+    /// Partecipa a una partita in rete ospitata da un altro giocatore
+    Join {
+        #[command(flatten)]
+        net: NetArgs,
+    },
+    /// Confronta le strategie di tiro su molte partite con flotte casuali (il file non viene usato)
+    Simulate {
+        /// Numero di navi per lunghezza (1, 2, 3, ...)
+        #[arg(value_delimiter = ',', default_value = "4,3,2,1")]
+        boats: Vec<u8>,
+        #[arg(long, value_name = "ROWSxCOLS", default_value = "10x10", value_parser = board::parse_size)]
+        size: (usize, usize),
+        #[arg(long, default_value_t = 1000)]
+        games: usize,
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+        #[arg(long)]
+        allow_touch: bool,
+    },
+}
 
-        Ok(Board { boats, data })
-    }
+#[derive(Clone, Copy, ValueEnum)]
+enum Direction {
+    #[value(name = "v", alias = "vertical")]
+    Vertical,
+    #[value(name = "h", alias = "horizontal")]
+    Horizontal,
 }
 
+// la flotta locale è quella del giocatore 1 nel file indicato da --board
+#[derive(Args)]
+struct NetArgs {
+    /// Indirizzo su cui attendere (host) o a cui collegarsi (join)
+    #[arg(long, default_value = "127.0.0.1:7878")]
+    addr: String,
+    /// Fa giocare una strategia (random, hunt-target, probability) invece di chiedere i colpi
+    #[arg(long, value_name = "STRATEGY")]
+    auto: Option<String>,
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+    /// Secondi di attesa massima di una risposta dell'avversario, 0 = nessun limite
+    #[arg(long, default_value_t = net::DEFAULT_TIMEOUT.as_secs())]
+    timeout: u64,
+}
 
-/** struct PlaceBoat **/
+/* struct PlaceBoat */
 
+#[derive(Args)]
 pub struct PlaceBoat {
-    direction: String,
+    /// Direzione della nave, verso il basso (v) o verso destra (h)
+    #[arg(value_enum)]
+    direction: Direction,
+    /// Lunghezza della nave
     boat_type: u8,
-    x: u8,
-    y: u8,
+    /// Riga della prima cella
+    x: usize,
+    /// Colonna della prima cella
+    y: usize,
 }
 impl PlaceBoat {
-    pub fn get_boat_enum(&self) -> Option<Boat> {
-        match self.direction.to_lowercase().as_str() {
-            "v" => Some(Boat::Vertical(self.boat_type as usize)),
-            "h" => Some(Boat::Horizontal(self.boat_type as usize)),
-            _ => None
+    pub fn get_boat_enum(&self) -> Boat {
+        match self.direction {
+            Direction::Vertical => Boat::Vertical(self.boat_type as usize),
+            Direction::Horizontal => Boat::Horizontal(self.boat_type as usize),
         }
     }
 }
 
-pub enum Error {
-    Overlap,
-    OutOfBounds,
-    BoatCount,
-}
-pub enum Boat {
-    Vertical(usize),
-    Horizontal(usize),
+// carica la partita, accetta anche i file con una sola board
+fn load_game(path: &Path) -> Result<Game, String> {
+    let content = read_file_content(path).map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+    Game::from_string(&content).map_err(|e| format!("invalid board file {}: {}", path.display(), e))
 }
 
+fn save_game(path: &Path, game: &Game) -> Result<(), String> {
+    write_to_file(path, &game.to_string()).map_err(|e| format!("cannot write {}: {}", path.display(), e))
+}
 
-/** functions **/
-fn check_number_of_ship(b:&Board, pl:&PlaceBoat) -> bool {
-    let index = (pl.boat_type.saturating_sub(1)) as usize;
-    if b.boats[index] == 0 {
-        return false;
+fn show(game: &Game, player: Option<usize>) {
+    match game.winner() {
+        Some(winner) => println!("Player {} won", winner + 1),
+        None => println!("Turn: player {}", game.turn() + 1),
+    }
+    for i in 0..2 {
+        let board = game.board(i);
+        // without a point of view everything is shown
+        let reveal = player.is_none_or(|p| p == i);
+        println!("\nPlayer {} (boats to place: {:?})", i + 1, board.boats());
+        print!("{}", board.grid(reveal));
     }
-
-    true
 }
 
-fn check_coordinate(&coordinate: &u8) -> bool {
-    if coordinate >= 0 && coordinate < BSIZE as u8 {
-        return true;
+// colpo chiesto su stdin nella forma "x y" (oppure "x,y"), None se stdin è chiuso: il giocatore
+// lascia la partita
+fn ask_shot(view: &ai::View) -> Option<(usize, usize)> {
+    print!("\n{}", view.grid());
+    let stdin = io::stdin();
+    loop {
+        print!("Your shot (x y): ");
+        io::stdout().flush().ok();
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line).unwrap_or(0) == 0 {
+            return None;
+        }
+        let coordinates: Vec<usize> = line.split([' ', ',']).filter_map(|s| s.trim().parse().ok()).collect();
+        match coordinates[..] {
+            [x, y] if x < view.rows() && y < view.cols() && view.cell(x, y) == ai::Cell::Unknown => return Some((x, y)),
+            [x, y] if x < view.rows() && y < view.cols() => println!("{},{} is already known", x, y),
+            _ => println!("expected two coordinates inside the board"),
+        }
     }
-
-    false
 }
 
-fn check_placement(&x:&usize, &y:&usize, b: &Board) -> bool {
-    if b.data[x][y] == 1 {
-        return false;
+fn play_online(path: &Path, args: NetArgs, host: bool) -> Result<(), String> {
+    let board = load_game(path)?.board(0).clone();
+    if !board.is_complete() {
+        return Err(format!("place the fleet of player 1 in {} first", path.display()));
     }
-
-    true
+    let mut strategy = match &args.auto {
+        Some(name) => Some(ai::strategy_by_name(name, args.seed).ok_or(format!("unknown strategy {}", name))?),
+        None => None,
+    };
+
+    let mut peer = if host {
+        println!("Waiting for the opponent on {}", args.addr);
+        net::Peer::host(&args.addr)
+    } else {
+        net::Peer::join(&args.addr)
+    }.map_err(|e| format!("cannot connect to {}: {}", args.addr, e))?;
+    let timeout = (args.timeout > 0).then(|| Duration::from_secs(args.timeout));
+    peer.set_timeout(timeout).map_err(|e| e.to_string())?;
+
+    let mut shoot = |view: &ai::View| match strategy.as_mut() {
+        Some(strategy) => Some(strategy.next_shot(view)),
+        None => ask_shot(view),
+    };
+    let outcome = net::play(&mut peer, board, host, &mut shoot, &mut io::stdout()).map_err(|e| e.to_string())?;
+    if outcome.won {
+        println!("You win! ({} shots)", outcome.shots_fired);
+    } else {
+        println!("You lose ({} shots fired)", outcome.shots_fired);
+    }
+    Ok(())
 }
 
-pub fn main() {
-    println!("\n\n Console:\n");
-    let args: Vec<String> = env::args().collect();
-
-    if args.len() > 1 {
-        let functions = &args[2];
-
-
-        /** Bisognava usare la libreria Clap **/
-        match functions.as_str() {
-            "new" => {
-                let parameters: Vec<u8> = args[3]
-                    .split(',')
-                    .filter_map(|s| s.trim().parse::<u8>().ok()) // Converte e gestisce eventuali errori
-                    .collect();
-                let b = Board::new(parameters.as_slice());
-
-                if let Err(e) = write_to_file(FILENAME, &b.to_string()) {
-                    eprintln!("Error writing file: {}", e);
-                }
-            },
-            "add_boat" => {
-                match read_file_content(FILENAME) {
-                    Ok(content) => {
-                        match Board::from_string(content.as_str()) {
-                            Ok(b) => {
-                                let mut board = b;
-
-                                let parameters: Vec<String> = args[3]
-                                    .split(',')
-                                    .map(|s| s.to_string())
-                                    .collect();
-                                let mut place_boat = PlaceBoat {
-                                    direction: parameters[0].clone(),
-                                    boat_type: parameters[1].parse::<u8>().unwrap(),
-                                    x: parameters[2].parse::<u8>().unwrap(),
-                                    y: parameters[3].parse::<u8>().unwrap()
-                                };
-                                println!("{} {} {} {}", place_boat.direction, place_boat.boat_type, place_boat.x, place_boat.y);
-
-                                //check data validity with a function
-                                if !check_number_of_ship(&board, &place_boat) {
-                                    panic!("maximus number of boat of this type already placed")
-                                }
-
-                                if !check_coordinate(&place_boat.x) || !check_coordinate(&place_boat.y) {
-                                    panic!("coordinate out of bounds");
-                                }
-
-
-                                //update board
-                                let boat = PlaceBoat::get_boat_enum(&place_boat).unwrap();
-                                match Board::add_boat(&mut board, boat, (place_boat.x as usize, place_boat.y as usize), place_boat.boat_type){
-                                    Ok(b) => {
-                                        if let Err(e) = write_to_file(FILENAME, &b.to_string()) {
-                                            eprintln!("Error writing file: {}", e);
-                                        }
-                                    },
-                                    Err(e) => {eprintln!("error adding boat");}
-                                }
-                            },
-                            Err(e) => {
-                                eprintln!("Errore {}: {}", FILENAME, e);
-                                panic!("Failed to create board from file content");
-                            }
+fn run(cli: Cli) -> Result<(), String> {
+    let path = cli.board.as_path();
+    match cli.command {
+        Command::New { boats, size: (rows, cols), allow_touch } => {
+            if boats.len() > rows.max(cols) {
+                return Err(format!("boats longer than {} do not fit in a {}x{} board", rows.max(cols), rows, cols));
+            }
+            save_game(path, &Game::with_size(rows, cols, &boats).allow_touching(allow_touch))?;
+            println!("New game saved in {}", path.display());
+        },
+        Command::AddBoat { boat, player } => {
+            let mut game = load_game(path)?;
+            // add_boat checks the placement and leaves the board unchanged on error
+            game.board_mut(player as usize - 1)
+                .add_boat(boat.get_boat_enum(), (boat.x, boat.y), boat.boat_type)
+                .map_err(|e| format!("cannot add boat: {}", e))?;
+            save_game(path, &game)?;
+        },
+        Command::Place { player, seed } => {
+            let mut game = load_game(path)?;
+            let mut rng = StdRng::seed_from_u64(seed.unwrap_or_else(rand::random));
+            ai::place_fleet(game.board_mut(player as usize - 1), &mut rng)
+                .map_err(|e| format!("cannot place the fleet: {}", e))?;
+            save_game(path, &game)?;
+        },
+        Command::Show { player } => {
+            show(&load_game(path)?, player.map(|p| p as usize - 1));
+        },
+        Command::Fire { x, y } => {
+            let mut game = load_game(path)?;
+            let player = game.turn();
+            let result = game.fire(x, y).map_err(|e| format!("cannot fire: {}", e))?;
+            println!("Player {} fires at {},{}: {}", player + 1, x, y, result);
+            match game.winner() {
+                Some(winner) => println!("Player {} wins!", winner + 1),
+                None => println!("Next turn: player {}", game.turn() + 1),
+            }
+            save_game(path, &game)?;
+        },
+        Command::Validate => {
+            load_game(path)?.validate()?;
+            println!("{} is valid", path.display());
+        },
+        Command::Reset => {
+            let mut game = load_game(path)?;
+            game.reset();
+            save_game(path, &game)?;
+        },
+        Command::Migrate => {
+            let content = read_file_content(path).map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+            if content.starts_with(format::MAGIC) {
+                load_game(path)?;
+                println!("{} is already in format version {}", path.display(), format::VERSION);
+            } else {
+                save_game(path, &load_game(path)?)?;
+                println!("{} converted to format version {}", path.display(), format::VERSION);
+            }
+        },
+        Command::Export { output } => {
+            let json = load_game(path)?.to_json();
+            match output {
+                Some(output) => write_to_file(&output, &json).map_err(|e| format!("cannot write {}: {}", output.display(), e))?,
+                None => println!("{}", json),
+            }
+        },
+        Command::Import { json } => {
+            let content = read_file_content(&json).map_err(|e| format!("cannot read {}: {}", json.display(), e))?;
+            let game = Game::from_json(&content).map_err(|e| format!("invalid JSON file {}: {}", json.display(), e))?;
+            save_game(path, &game)?;
+            println!("{} imported into {}", json.display(), path.display());
+        },
+        Command::Host { net } => play_online(path, net, true)?,
+        Command::Join { net } => play_online(path, net, false)?,
+        Command::Simulate { boats, size: (rows, cols), games, seed, allow_touch } => {
+            let template = board::Board::with_size(rows, cols, &boats).allow_touching(allow_touch);
+            let reports = ai::simulate(&template, games, seed).map_err(|e| format!("cannot simulate: {}", e))?;
+            println!("{:<12} {:>8} {:>10} {:>6} {:>6}", "strategy", "games", "avg shots", "min", "max");
+            for r in reports {
+                println!("{:<12} {:>8} {:>10.2} {:>6} {:>6}", r.strategy, r.games, r.average, r.min, r.max);
+            }
+        },
+    }
+    Ok(())
+}
 
-                        }
-                    },
-                    Err(e) => eprintln!("Error reading file: {}", e),
-                }
-            },
-            _ => panic!("Unknown function {}", functions.as_str()),
+// exit code: 0 ok, 1 errore del gioco o del file, 2 argomenti non validi (gestito da clap)
+pub fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
         }
     }
-
 }
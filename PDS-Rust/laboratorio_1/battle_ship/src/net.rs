@@ -0,0 +1,526 @@
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+use crate::ai::View;
+use crate::board::{format_ships, parse_ships, Board, Ship, ShotResult};
+
+
+/* partita a due giocatori su TCP */
+
+// Protocollo a righe, una riga per messaggio:
+//   HELLO <versione> <righe>x<colonne> <flotta> <touch|no-touch>   configurazione, deve coincidere
+//   READY                                                          navi posizionate
+//   FIRE <x> <y>                                                   colpo del giocatore di turno
+//   RESULT hit|miss|sunk <n>|already                               esito, lo invia chi ha ricevuto il colpo
+//   GAMEOVER                                                       chi ha perso tutte le navi, dopo RESULT
+//   REVEAL <navi>                                                  a fine partita, per verificare gli esiti
+//   ERROR <messaggio>                                              l'altro giocatore chiude la partita
+// Chi ospita la partita spara per primo; il turno passa all'avversario dopo ogni colpo, tranne
+// quando la cella era già stata colpita (come in Game::fire).
+
+pub const PROTOCOL_VERSION: u32 = 1;
+
+// tempo massimo di attesa di un messaggio: l'avversario può essere una persona che pensa al colpo,
+// ma un avversario che non risponde più non deve bloccare la partita per sempre
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Message {
+    Hello { version: u32, rows: usize, cols: usize, fleet: Vec<u8>, touching: bool },
+    Ready,
+    Fire(usize, usize),
+    Result(ShotResult),
+    GameOver,
+    Reveal(String),
+    Error(String),
+}
+
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Message::Hello { version, rows, cols, fleet, touching } => {
+                let fleet: Vec<String> = fleet.iter().map(|n| n.to_string()).collect();
+                let touching = if *touching { "touch" } else { "no-touch" };
+                write!(f, "HELLO {} {}x{} {} {}", version, rows, cols, fleet.join(","), touching)
+            }
+            Message::Ready => write!(f, "READY"),
+            Message::Fire(x, y) => write!(f, "FIRE {} {}", x, y),
+            Message::Result(result) => write!(f, "RESULT {}", result),
+            Message::GameOver => write!(f, "GAMEOVER"),
+            Message::Reveal(ships) => write!(f, "REVEAL {}", ships),
+            Message::Error(msg) => write!(f, "ERROR {}", msg),
+        }
+    }
+}
+
+impl Message {
+    pub fn parse(line: &str) -> Option<Message> {
+        let line = line.trim_end_matches(['\r', '\n']);
+        let (command, rest) = line.split_once(' ').unwrap_or((line, ""));
+        let mut args = rest.split_whitespace();
+        let message = match command {
+            "HELLO" => {
+                let version = args.next()?.parse().ok()?;
+                let (rows, cols) = args.next()?.split_once('x')?;
+                let fleet = args.next()?.split(',').map(|n| n.parse().ok()).collect::<Option<Vec<u8>>>()?;
+                let touching = match args.next()? {
+                    "touch" => true,
+                    "no-touch" => false,
+                    _ => return None,
+                };
+                Message::Hello { version, rows: rows.parse().ok()?, cols: cols.parse().ok()?, fleet, touching }
+            }
+            "READY" => Message::Ready,
+            "FIRE" => Message::Fire(args.next()?.parse().ok()?, args.next()?.parse().ok()?),
+            "RESULT" => return ShotResult::parse(rest).map(Message::Result),
+            "GAMEOVER" => Message::GameOver,
+            // la lista delle navi contiene spazi, viene letta dopo rispetto alla board
+            "REVEAL" => return Some(Message::Reveal(rest.to_string())),
+            "ERROR" => return Some(Message::Error(rest.to_string())),
+            _ => return None,
+        };
+        match args.next() {
+            Some(_) => None,
+            None => Some(message),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum NetError {
+    Io(io::Error),
+    Disconnected,
+    Timeout,          // nessun messaggio dall'avversario entro il tempo massimo
+    Abandoned,        // il giocatore locale ha lasciato la partita
+    Protocol(String), // messaggio non valido o inatteso
+    Mismatch(String), // configurazioni diverse
+    Remote(String),   // l'avversario ha inviato ERROR
+    Cheating(String), // gli esiti dichiarati dall'avversario non corrispondono alle sue navi
+}
+
+impl fmt::Display for NetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NetError::Io(e) => write!(f, "network error: {}", e),
+            NetError::Disconnected => write!(f, "the opponent disconnected"),
+            NetError::Timeout => write!(f, "the opponent did not answer in time"),
+            NetError::Abandoned => write!(f, "the game was abandoned"),
+            NetError::Protocol(msg) => write!(f, "protocol error: {}", msg),
+            NetError::Mismatch(msg) => write!(f, "different game settings: {}", msg),
+            NetError::Remote(msg) => write!(f, "the opponent closed the game: {}", msg),
+            NetError::Cheating(msg) => write!(f, "the opponent cheated: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for NetError {}
+
+impl From<io::Error> for NetError {
+    fn from(e: io::Error) -> Self {
+        match e.kind() {
+            io::ErrorKind::UnexpectedEof
+            | io::ErrorKind::ConnectionReset
+            | io::ErrorKind::ConnectionAborted
+            | io::ErrorKind::BrokenPipe => NetError::Disconnected,
+            // il timeout di lettura arriva come WouldBlock su Unix e come TimedOut su Windows
+            io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut => NetError::Timeout,
+            _ => NetError::Io(e),
+        }
+    }
+}
+
+// connessione con l'altro giocatore
+pub struct Peer {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+impl Peer {
+    pub fn new(stream: TcpStream) -> io::Result<Peer> {
+        stream.set_read_timeout(Some(DEFAULT_TIMEOUT))?;
+        Ok(Peer { reader: BufReader::new(stream.try_clone()?), writer: stream })
+    }
+
+    // tempo massimo di attesa di un messaggio, None = nessun limite
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) -> io::Result<()> {
+        self.writer.set_read_timeout(timeout)
+    }
+
+    // attende un avversario sull'indirizzo indicato (una sola connessione)
+    pub fn host<A: ToSocketAddrs>(addr: A) -> io::Result<Peer> {
+        Peer::accept(&TcpListener::bind(addr)?)
+    }
+
+    pub fn accept(listener: &TcpListener) -> io::Result<Peer> {
+        let (stream, _) = listener.accept()?;
+        Peer::new(stream)
+    }
+
+    pub fn join<A: ToSocketAddrs>(addr: A) -> io::Result<Peer> {
+        Peer::new(TcpStream::connect(addr)?)
+    }
+
+    pub fn send(&mut self, message: &Message) -> Result<(), NetError> {
+        writeln!(self.writer, "{}", message)?;
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    pub fn recv(&mut self) -> Result<Message, NetError> {
+        let mut line = String::new();
+        if self.reader.read_line(&mut line)? == 0 {
+            return Err(NetError::Disconnected);
+        }
+        match Message::parse(&line) {
+            Some(Message::Error(msg)) => Err(NetError::Remote(msg)),
+            Some(message) => Ok(message),
+            None => Err(NetError::Protocol(format!("invalid message '{}'", line.trim_end()))),
+        }
+    }
+
+    // segnala l'errore all'avversario (se è ancora connesso) e lo restituisce
+    fn fail(&mut self, error: NetError) -> NetError {
+        if !matches!(error, NetError::Disconnected | NetError::Remote(_)) {
+            self.send(&Message::Error(error.to_string())).ok();
+        }
+        error
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    pub won: bool,
+    pub shots_fired: usize,
+    pub shots_received: usize,
+}
+
+// gioca una partita completa sulla connessione. `board` contiene la flotta locale già posizionata,
+// `shoot` sceglie il prossimo colpo a partire da quanto si sa della board avversaria (None se il
+// giocatore abbandona la partita) e `out` riceve la cronaca della partita
+pub fn play(
+    peer: &mut Peer,
+    board: Board,
+    first: bool,
+    shoot: &mut dyn FnMut(&View) -> Option<(usize, usize)>,
+    out: &mut dyn Write,
+) -> Result<Outcome, NetError> {
+    match run(peer, board, first, shoot, out) {
+        Ok(outcome) => Ok(outcome),
+        Err(e) => Err(peer.fail(e)),
+    }
+}
+
+fn run(
+    peer: &mut Peer,
+    mut board: Board,
+    first: bool,
+    shoot: &mut dyn FnMut(&View) -> Option<(usize, usize)>,
+    out: &mut dyn Write,
+) -> Result<Outcome, NetError> {
+    if !board.is_complete() {
+        return Err(NetError::Protocol(String::from("the local fleet is not placed")));
+    }
+    let fleet = board.fleet();
+
+    // handshake: chi ospita propone la configurazione, l'altro giocatore deve avere la stessa
+    let hello = Message::Hello {
+        version: PROTOCOL_VERSION,
+        rows: board.rows(),
+        cols: board.cols(),
+        fleet: fleet.clone(),
+        touching: board.touching_allowed(),
+    };
+    if first {
+        peer.send(&hello)?;
+    }
+    let remote = peer.recv()?;
+    if remote != hello {
+        return Err(NetError::Mismatch(format!("local '{}', remote '{}'", hello, remote)));
+    }
+    if !first {
+        peer.send(&hello)?;
+    }
+    peer.send(&Message::Ready)?;
+    expect(peer.recv()?, Message::Ready)?;
+
+    let mut view = View::new(board.rows(), board.cols(), &fleet, board.touching_allowed());
+    let mut fired: Vec<(usize, usize, ShotResult)> = Vec::new();
+    let mut received = 0;
+    let mut my_turn = first;
+
+    let won = loop {
+        if my_turn {
+            let (x, y) = shoot(&view).ok_or(NetError::Abandoned)?;
+            peer.send(&Message::Fire(x, y))?;
+            let result = match peer.recv()? {
+                Message::Result(result) => result,
+                other => return Err(unexpected(&other, "RESULT")),
+            };
+            writeln!(out, "You fire at {},{}: {}", x, y, result)?;
+            view.record(x, y, result);
+            fired.push((x, y, result));
+            if view.all_sunk() {
+                // tutte le navi avversarie sono affondate, l'avversario deve ammettere la sconfitta
+                expect(peer.recv()?, Message::GameOver)?;
+                break true;
+            }
+            if !view.has_unknown_cells() {
+                // nessuna cella su cui sparare ma la flotta è ancora a galla: gli esiti erano falsi.
+                // La flotta locale viene comunque rivelata, così l'avversario la può verificare
+                peer.send(&Message::Reveal(format_ships(board.ships())))?;
+                return Err(NetError::Cheating(String::from("every cell was fired at but the fleet is not sunk")));
+            }
+            my_turn = result == ShotResult::AlreadyShot;
+        } else {
+            let (x, y) = match peer.recv()? {
+                Message::Fire(x, y) => (x, y),
+                Message::GameOver => return Err(NetError::Cheating(String::from("game over claimed out of turn"))),
+                other => return Err(unexpected(&other, "FIRE")),
+            };
+            let result = board.fire(x, y).map_err(|e| NetError::Protocol(format!("invalid shot {},{}: {}", x, y, e)))?;
+            peer.send(&Message::Result(result))?;
+            writeln!(out, "Opponent fires at {},{}: {}", x, y, result)?;
+            received += 1;
+            if board.is_defeated() {
+                peer.send(&Message::GameOver)?;
+                break false;
+            }
+            my_turn = result != ShotResult::AlreadyShot;
+        }
+    };
+
+    // entrambe le flotte vengono rivelate: ogni esito dichiarato dall'avversario viene verificato
+    peer.send(&Message::Reveal(format_ships(board.ships())))?;
+    let ships = match peer.recv()? {
+        Message::Reveal(ships) => ships,
+        other => return Err(unexpected(&other, "REVEAL")),
+    };
+    let template = Board::with_size(board.rows(), board.cols(), &fleet).allow_touching(board.touching_allowed());
+    let ships = parse_ships(&ships, &template).map_err(NetError::Protocol)?;
+    verify_claims(&template, &ships, &fired, won).map_err(NetError::Cheating)?;
+
+    Ok(Outcome { won, shots_fired: fired.len(), shots_received: received })
+}
+
+fn expect(message: Message, expected: Message) -> Result<(), NetError> {
+    if message == expected {
+        Ok(())
+    } else {
+        Err(unexpected(&message, &expected.to_string()))
+    }
+}
+
+fn unexpected(message: &Message, expected: &str) -> NetError {
+    NetError::Protocol(format!("expected {}, received '{}'", expected, message))
+}
+
+// ripete i colpi sulla flotta rivelata dall'avversario: la flotta deve rispettare le regole e ogni
+// esito deve essere quello dichiarato durante la partita
+pub fn verify_claims(template: &Board, ships: &[Ship], fired: &[(usize, usize, ShotResult)], won: bool) -> Result<(), String> {
+    let mut board = template.clone();
+    for ship in ships {
+        board.add_boat(ship.boat, (ship.x, ship.y), ship.length() as u8)
+            .map_err(|e| format!("invalid fleet: {}", e))?;
+    }
+    if !board.is_complete() {
+        return Err(String::from("the revealed fleet is incomplete"));
+    }
+    for &(x, y, claimed) in fired {
+        let actual = board.fire(x, y).map_err(|e| e.to_string())?;
+        if actual != claimed {
+            return Err(format!("shot at {},{} was {}, not {}", x, y, actual, claimed));
+        }
+    }
+    if board.is_defeated() != won {
+        return Err(String::from("the end of the game does not match the revealed fleet"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::{place_fleet, ProbabilityStrategy, Strategy};
+    use crate::board::Boat;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use std::thread;
+
+    fn fleet(seed: u64) -> Board {
+        let mut board = Board::with_size(8, 8, &[1, 1, 1]);
+        place_fleet(&mut board, &mut StdRng::seed_from_u64(seed)).unwrap();
+        board
+    }
+
+    #[test]
+    fn test_message_roundtrip() {
+        let messages = [
+            Message::Hello { version: 1, rows: 10, cols: 12, fleet: vec![4, 3, 2, 1], touching: false },
+            Message::Ready,
+            Message::Fire(3, 7),
+            Message::Result(ShotResult::Sunk(3)),
+            Message::Result(ShotResult::Miss),
+            Message::GameOver,
+            Message::Reveal(String::from("v,3,2,4; h,1,0,0")),
+            Message::Error(String::from("bye bye")),
+        ];
+        for message in messages {
+            assert_eq!(Message::parse(&format!("{}\n", message)), Some(message));
+        }
+        assert_eq!(Message::parse("FIRE 1"), None);
+        assert_eq!(Message::parse("FIRE 1 2 3"), None);
+        assert_eq!(Message::parse("SURRENDER"), None);
+    }
+
+    #[test]
+    fn test_game_over_localhost() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let guest = thread::spawn(move || {
+            let mut peer = Peer::join(addr).unwrap();
+            let mut strategy = ProbabilityStrategy::new(2);
+            play(&mut peer, fleet(2), false, &mut |view| Some(strategy.next_shot(view)), &mut io::sink())
+        });
+
+        let mut peer = Peer::accept(&listener).unwrap();
+        let mut strategy = ProbabilityStrategy::new(1);
+        let host = play(&mut peer, fleet(1), true, &mut |view| Some(strategy.next_shot(view)), &mut io::sink()).unwrap();
+        let guest = guest.join().unwrap().unwrap();
+
+        assert_ne!(host.won, guest.won);
+        assert_eq!(host.shots_fired, guest.shots_received);
+        assert_eq!(guest.shots_fired, host.shots_received);
+    }
+
+    #[test]
+    fn test_settings_mismatch() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let guest = thread::spawn(move || {
+            let mut peer = Peer::join(addr).unwrap();
+            let mut board = Board::with_size(8, 8, &[1]);
+            board.add_boat(Boat::Vertical(1), (0, 0), 1).unwrap();
+            play(&mut peer, board, false, &mut |_| Some((0, 0)), &mut io::sink())
+        });
+
+        let mut peer = Peer::accept(&listener).unwrap();
+        let host = play(&mut peer, fleet(1), true, &mut |_| Some((0, 0)), &mut io::sink());
+        assert!(matches!(guest.join().unwrap(), Err(NetError::Mismatch(_))));
+        assert!(matches!(host, Err(NetError::Remote(_))));
+    }
+
+    #[test]
+    fn test_disconnect() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        // l'ospite risponde all'handshake e se ne va
+        let guest = thread::spawn(move || {
+            let mut peer = Peer::join(addr).unwrap();
+            let hello = peer.recv().unwrap();
+            peer.send(&hello).unwrap();
+        });
+
+        let mut peer = Peer::accept(&listener).unwrap();
+        let host = play(&mut peer, fleet(1), true, &mut |_| Some((0, 0)), &mut io::sink());
+        guest.join().unwrap();
+        assert!(matches!(host, Err(NetError::Disconnected)));
+    }
+
+    #[test]
+    fn test_opponent_always_misses() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        // l'ospite risponde "miss" a ogni colpo e spara sulle celle libere di chi ospita
+        let guest = thread::spawn(move || {
+            let mut peer = Peer::join(addr).unwrap();
+            let hello = peer.recv().unwrap();
+            peer.send(&hello).unwrap();
+            peer.send(&Message::Ready).unwrap();
+            peer.recv().unwrap();
+            let mut targets = (0..3).flat_map(|x| (0..3).map(move |y| (x, y))).filter(|&c| c != (0, 0)).cycle();
+            loop {
+                match peer.recv() {
+                    Ok(Message::Fire(_, _)) => peer.send(&Message::Result(ShotResult::Miss)).unwrap(),
+                    Ok(Message::Result(_)) => continue,
+                    other => return other,
+                }
+                let (x, y) = targets.next().unwrap();
+                peer.send(&Message::Fire(x, y)).unwrap();
+            }
+        });
+
+        let mut board = Board::with_size(3, 3, &[1]);
+        board.add_boat(Boat::Vertical(1), (0, 0), 1).unwrap();
+        let mut peer = Peer::accept(&listener).unwrap();
+        let mut strategy = ProbabilityStrategy::new(1);
+        let host = play(&mut peer, board, true, &mut |view| Some(strategy.next_shot(view)), &mut io::sink());
+        assert!(matches!(host, Err(NetError::Cheating(_))), "{:?}", host);
+        assert!(matches!(guest.join().unwrap(), Ok(Message::Reveal(_))));
+    }
+
+    #[test]
+    fn test_silent_opponent_times_out() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        // l'ospite si collega e non risponde mai
+        let guest = thread::spawn(move || {
+            let mut peer = Peer::join(addr).unwrap();
+            peer.recv().unwrap();
+            peer.recv()
+        });
+
+        let mut peer = Peer::accept(&listener).unwrap();
+        peer.set_timeout(Some(Duration::from_millis(100))).unwrap();
+        let host = play(&mut peer, fleet(1), true, &mut |_| Some((0, 0)), &mut io::sink());
+        assert!(matches!(host, Err(NetError::Timeout)), "{:?}", host);
+        assert!(matches!(guest.join().unwrap(), Err(NetError::Remote(_))));
+    }
+
+    #[test]
+    fn test_abandoned_game() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let guest = thread::spawn(move || {
+            let mut peer = Peer::join(addr).unwrap();
+            play(&mut peer, fleet(2), false, &mut |_| Some((0, 0)), &mut io::sink())
+        });
+
+        // chi ospita se ne va invece di scegliere il primo colpo
+        let mut peer = Peer::accept(&listener).unwrap();
+        let host = play(&mut peer, fleet(1), true, &mut |_| None, &mut io::sink());
+        assert!(matches!(host, Err(NetError::Abandoned)));
+        assert!(matches!(guest.join().unwrap(), Err(NetError::Remote(_))));
+    }
+
+    #[test]
+    fn test_verify_claims() {
+        let template = Board::with_size(5, 5, &[1, 1]);
+        let ships = [
+            Ship { x: 0, y: 0, boat: Boat::Horizontal(2) },
+            Ship { x: 4, y: 4, boat: Boat::Vertical(1) },
+        ];
+        let honest = [(0, 0, ShotResult::Hit), (2, 2, ShotResult::Miss), (0, 1, ShotResult::Sunk(2))];
+        assert_eq!(verify_claims(&template, &ships, &honest, false), Ok(()));
+
+        let lie = [(0, 0, ShotResult::Miss)];
+        assert!(verify_claims(&template, &ships, &lie, false).is_err());
+
+        // l'avversario ha ammesso la sconfitta, ma una nave non è mai stata colpita
+        assert!(verify_claims(&template, &ships, &honest, true).is_err());
+
+        // le navi rivelate si toccano
+        let touching = [
+            Ship { x: 0, y: 0, boat: Boat::Horizontal(2) },
+            Ship { x: 1, y: 2, boat: Boat::Vertical(1) },
+        ];
+        assert!(verify_claims(&template, &touching, &[], false).is_err());
+    }
+}
@@ -0,0 +1,280 @@
+use std::collections::HashMap;
+
+// An alphabet is an ordered set of letters plus a folding rule which maps every input character
+// onto one of those letters (or leaves it alone, in that case it is simply ignored).
+// Implementing the trait is enough to plug a new alphabet into the pangram analysis.
+pub trait Alphabet {
+    fn name(&self) -> &str;
+
+    // letters in their canonical (folded) form, the order is the order used in the reports
+    fn letters(&self) -> &[char];
+
+    // maps a character onto its canonical form, the default is plain Unicode lowercase
+    fn fold(&self, c: char) -> char {
+        lowercase(c)
+    }
+
+    // position of the (folded) character inside `letters`, None if it does not belong to the alphabet
+    fn index_of(&self, c: char) -> Option<usize> {
+        let folded = self.fold(c);
+        self.letters().iter().position(|&l| l == folded)
+    }
+}
+
+// `char::to_lowercase` returns an iterator because some characters expand to more than one char
+// (e.g. 'İ' -> "i̇"), only the base letter is relevant for counting
+fn lowercase(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+// Alphabet described by data: a string of letters and a table of equivalent characters
+// (accented vowels, final sigma, ...) which are counted as the letter they fold to.
+#[derive(Debug, Clone)]
+pub struct LetterSet {
+    name: String,
+    letters: Vec<char>,
+    index: HashMap<char, usize>,
+    folds: HashMap<char, char>,
+}
+
+impl LetterSet {
+    pub fn new(name: &str, letters: &str) -> LetterSet {
+        let mut set = LetterSet {
+            name: name.to_string(),
+            letters: Vec::new(),
+            index: HashMap::new(),
+            folds: HashMap::new(),
+        };
+        for c in letters.chars().map(lowercase) {
+            if !set.index.contains_key(&c) {
+                set.index.insert(c, set.letters.len());
+                set.letters.push(c);
+            }
+        }
+        set
+    }
+
+    // every character of `from` is counted as the letter `to`
+    pub fn with_fold(mut self, from: &str, to: char) -> LetterSet {
+        for c in from.chars() {
+            self.folds.insert(lowercase(c), lowercase(to));
+        }
+        self
+    }
+
+    pub fn latin() -> LetterSet {
+        LetterSet::new("latin", "abcdefghijklmnopqrstuvwxyz")
+    }
+
+    // the Italian alphabet has 21 letters, j k w x y are only used in foreign words
+    pub fn italian() -> LetterSet {
+        LetterSet::new("italian", "abcdefghilmnopqrstuvz")
+            .with_fold("àá", 'a')
+            .with_fold("èé", 'e')
+            .with_fold("ìíî", 'i')
+            .with_fold("òó", 'o')
+            .with_fold("ùú", 'u')
+    }
+
+    pub fn german() -> LetterSet {
+        LetterSet::new("german", "abcdefghijklmnopqrstuvwxyzäöüß")
+    }
+
+    pub fn greek() -> LetterSet {
+        LetterSet::new("greek", "αβγδεζηθικλμνξοπρστυφχψω")
+            .with_fold("ά", 'α')
+            .with_fold("έ", 'ε')
+            .with_fold("ή", 'η')
+            .with_fold("ίϊΐ", 'ι')
+            .with_fold("ό", 'ο')
+            .with_fold("ς", 'σ')
+            .with_fold("ύϋΰ", 'υ')
+            .with_fold("ώ", 'ω')
+    }
+
+    pub fn cyrillic() -> LetterSet {
+        LetterSet::new("cyrillic", "абвгдеёжзийклмнопрстуфхцчшщъыьэюя")
+    }
+
+    pub fn by_name(name: &str) -> Option<LetterSet> {
+        match name.to_lowercase().as_str() {
+            "latin" | "english" => Some(LetterSet::latin()),
+            "italian" => Some(LetterSet::italian()),
+            "german" => Some(LetterSet::german()),
+            "greek" => Some(LetterSet::greek()),
+            "cyrillic" | "russian" => Some(LetterSet::cyrillic()),
+            _ => None,
+        }
+    }
+}
+
+impl Alphabet for LetterSet {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn letters(&self) -> &[char] {
+        &self.letters
+    }
+
+    fn fold(&self, c: char) -> char {
+        let c = lowercase(c);
+        *self.folds.get(&c).unwrap_or(&c)
+    }
+
+    fn index_of(&self, c: char) -> Option<usize> {
+        self.index.get(&self.fold(c)).copied()
+    }
+}
+
+// Result of the analysis of a text against an alphabet: occurrences of every letter and
+// position (in characters, starting from 0) of its first occurrence.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PangramReport {
+    letters: Vec<char>,
+    counts: Vec<u32>,
+    first_seen: Vec<Option<usize>>,
+}
+
+impl PangramReport {
+    pub fn is_pangram(&self) -> bool {
+        !self.letters.is_empty() && self.counts.iter().all(|&count| count > 0)
+    }
+
+    pub fn counts(&self) -> &[u32] {
+        &self.counts
+    }
+
+    pub fn missing(&self) -> Vec<char> {
+        self.letters.iter()
+            .zip(self.counts.iter())
+            .filter(|&(_, &count)| count == 0)
+            .map(|(&letter, _)| letter)
+            .collect()
+    }
+
+    pub fn count(&self, letter: char) -> Option<u32> {
+        let index = self.letters.iter().position(|&l| l == letter)?;
+        Some(self.counts[index])
+    }
+
+    pub fn first_occurrence(&self, letter: char) -> Option<usize> {
+        let index = self.letters.iter().position(|&l| l == letter)?;
+        self.first_seen[index]
+    }
+
+    // (letter, count, first occurrence) in alphabet order
+    pub fn iter(&self) -> impl Iterator<Item = (char, u32, Option<usize>)> + '_ {
+        self.letters.iter()
+            .zip(self.counts.iter())
+            .zip(self.first_seen.iter())
+            .map(|((&letter, &count), &first)| (letter, count, first))
+    }
+}
+
+pub fn stats_with<A: Alphabet + ?Sized>(text: &str, alphabet: &A) -> Vec<u32> {
+    analyze(text, alphabet).counts
+}
+
+pub fn analyze<A: Alphabet + ?Sized>(text: &str, alphabet: &A) -> PangramReport {
+    let size = alphabet.letters().len();
+    let mut counts = vec![0; size];
+    let mut first_seen = vec![None; size];
+
+    for (position, c) in text.chars().enumerate() {
+        if let Some(index) = alphabet.index_of(c) {
+            counts[index] += 1;
+            first_seen[index].get_or_insert(position);
+        }
+    }
+
+    PangramReport { letters: alphabet.letters().to_vec(), counts, first_seen }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latin_pangram() {
+        let report = analyze("The quick brown fox jumps over the lazy dog", &LetterSet::latin());
+        assert!(report.is_pangram());
+        assert!(report.missing().is_empty());
+        assert_eq!(report.first_occurrence('t'), Some(0));
+        assert_eq!(report.first_occurrence('q'), Some(4));
+        assert_eq!(report.count('o'), Some(4));
+    }
+
+    #[test]
+    fn test_latin_missing_letters() {
+        let report = analyze("The quick brown fox jumps over the laz* dog", &LetterSet::latin());
+        assert!(!report.is_pangram());
+        assert_eq!(report.missing(), vec!['y']);
+        assert_eq!(report.first_occurrence('y'), None);
+    }
+
+    #[test]
+    fn test_italian_pangram_with_accents() {
+        let alphabet = LetterSet::italian();
+        assert_eq!(alphabet.letters().len(), 21);
+
+        let report = analyze("Pranzo d'acqua fa volti sghembi", &alphabet);
+        assert!(report.is_pangram());
+
+        // accented vowels are counted as the plain vowel, foreign letters are ignored
+        let report = analyze("È già", &alphabet);
+        assert_eq!(report.count('e'), Some(1));
+        assert_eq!(report.count('a'), Some(1));
+        assert_eq!(report.count('j'), None);
+    }
+
+    #[test]
+    fn test_german_pangram() {
+        let report = analyze("Falsches Üben von Xylophonmusik quält jeden größeren Zwerg", &LetterSet::german());
+        assert!(report.is_pangram());
+        assert_eq!(report.first_occurrence('ß'), Some(47));
+    }
+
+    #[test]
+    fn test_greek_pangram_folds_final_sigma_and_tonos() {
+        let alphabet = LetterSet::greek();
+        let report = analyze("Ξεσκεπάζω την ψυχοφθόρα βδελυγμία", &alphabet);
+        assert!(report.is_pangram());
+
+        assert_eq!(alphabet.fold('Σ'), 'σ');
+        assert_eq!(alphabet.fold('ς'), 'σ');
+        assert_eq!(alphabet.fold('Ά'), 'α');
+    }
+
+    #[test]
+    fn test_cyrillic_pangram() {
+        let report = analyze("Съешь же ещё этих мягких французских булок, да выпей чаю", &LetterSet::cyrillic());
+        assert!(report.is_pangram());
+
+        let report = analyze("Съешь же ещё этих мягких французских булок", &LetterSet::cyrillic());
+        assert_eq!(report.missing(), vec!['в', 'д', 'й', 'п', 'ч', 'ы', 'ю']);
+    }
+
+    #[test]
+    fn test_custom_alphabet() {
+        let alphabet = LetterSet::new("abc", "ABC").with_fold("ä", 'a');
+        assert_eq!(alphabet.letters(), &['a', 'b', 'c']);
+
+        let report = analyze("xxÄ Bc", &alphabet);
+        assert!(report.is_pangram());
+        assert_eq!(report.first_occurrence('a'), Some(2));
+        assert_eq!(stats_with("cab cab", &alphabet), vec![2, 2, 2]);
+    }
+
+    #[test]
+    fn test_empty_alphabet_is_never_a_pangram() {
+        let report = analyze("anything", &LetterSet::new("empty", ""));
+        assert!(!report.is_pangram());
+    }
+
+    #[test]
+    fn test_by_name() {
+        assert_eq!(LetterSet::by_name("Greek").unwrap().name(), "greek");
+        assert!(LetterSet::by_name("klingon").is_none());
+    }
+}
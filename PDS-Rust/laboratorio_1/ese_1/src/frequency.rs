@@ -0,0 +1,501 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::str::FromStr;
+use std::sync::mpsc::sync_channel;
+use std::sync::Mutex;
+use std::thread;
+
+use crate::alphabet::Alphabet;
+
+// Streaming letter-frequency analysis: the input is read in chunks by the calling thread and
+// every chunk is sent to a pool of workers, each one keeps its own table of counts which are
+// merged once the input is over. Memory usage depends on chunk size and number of workers only.
+pub struct FrequencyAnalyzer<A> {
+    alphabet: A,
+    workers: usize,
+    chunk_size: usize,
+}
+
+impl<A: Alphabet + Sync> FrequencyAnalyzer<A> {
+    pub fn new(alphabet: A) -> FrequencyAnalyzer<A> {
+        let workers = thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+        FrequencyAnalyzer { alphabet, workers, chunk_size: 1 << 20 }
+    }
+
+    pub fn workers(mut self, workers: usize) -> FrequencyAnalyzer<A> {
+        self.workers = workers.max(1);
+        self
+    }
+
+    // size in bytes of the chunks sent to the workers, at least 4 so that a chunk can
+    // always hold a complete UTF-8 character
+    pub fn chunk_size(mut self, chunk_size: usize) -> FrequencyAnalyzer<A> {
+        self.chunk_size = chunk_size.max(4);
+        self
+    }
+
+    pub fn analyze_file(&self, path: &str) -> io::Result<Frequencies> {
+        self.analyze_reader(File::open(path)?)
+    }
+
+    pub fn analyze_stdin(&self) -> io::Result<Frequencies> {
+        self.analyze_reader(io::stdin().lock())
+    }
+
+    pub fn analyze_reader<R: Read>(&self, mut reader: R) -> io::Result<Frequencies> {
+        let size = self.alphabet.letters().len();
+        // bounded channel: the reader blocks when the workers are behind instead of
+        // loading the whole input in memory
+        let (tx, rx) = sync_channel::<Vec<u8>>(self.workers * 2);
+        let rx = Mutex::new(rx);
+
+        let (read_result, tables) = thread::scope(|s| {
+            let handles: Vec<_> = (0..self.workers)
+                .map(|_| {
+                    s.spawn(|| {
+                        let mut counts = vec![0u64; size];
+                        loop {
+                            // the lock is released as soon as the chunk has been received
+                            let chunk = match rx.lock().unwrap().recv() {
+                                Ok(chunk) => chunk,
+                                Err(_) => break,
+                            };
+                            count_into(&String::from_utf8_lossy(&chunk), &self.alphabet, &mut counts);
+                        }
+                        counts
+                    })
+                })
+                .collect();
+
+            let read_result = self.read_chunks(&mut reader, |chunk| {
+                // the workers never hang up before the channel is closed
+                tx.send(chunk).expect("frequency workers terminated");
+            });
+            drop(tx);
+
+            let tables: Vec<Vec<u64>> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+            (read_result, tables)
+        });
+        read_result?;
+
+        let mut counts = vec![0u64; size];
+        for table in tables {
+            for (total, count) in counts.iter_mut().zip(table) {
+                *total += count;
+            }
+        }
+
+        Ok(Frequencies::new(self.alphabet.letters().to_vec(), counts))
+    }
+
+    // reads the input in chunks which always end on a character boundary, the bytes of an
+    // incomplete UTF-8 sequence are carried over to the next chunk
+    fn read_chunks<R: Read, F: FnMut(Vec<u8>)>(&self, reader: &mut R, mut send: F) -> io::Result<()> {
+        let mut carry: Vec<u8> = Vec::new();
+        loop {
+            let mut chunk = Vec::with_capacity(self.chunk_size);
+            chunk.append(&mut carry);
+            let start = chunk.len();
+            chunk.resize(self.chunk_size.max(start + 4), 0);
+
+            let n = match reader.read(&mut chunk[start..]) {
+                Ok(n) => n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {
+                    carry = chunk[..start].to_vec();
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            chunk.truncate(start + n);
+
+            if n == 0 {
+                if !chunk.is_empty() {
+                    send(chunk);
+                }
+                return Ok(());
+            }
+
+            let boundary = char_boundary(&chunk);
+            carry = chunk.split_off(boundary);
+            if !chunk.is_empty() {
+                send(chunk);
+            }
+        }
+    }
+}
+
+// position where an incomplete UTF-8 sequence at the end of `bytes` starts (bytes.len() if complete)
+fn char_boundary(bytes: &[u8]) -> usize {
+    let len = bytes.len();
+    for back in 1..=len.min(4) {
+        let i = len - back;
+        let b = bytes[i];
+        if b & 0xC0 == 0x80 {
+            // continuation byte, keep looking for the lead byte
+            continue;
+        }
+        let expected = match b {
+            0x00..=0x7F => 1,
+            0xC0..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            0xF0..=0xF7 => 4,
+            _ => 1, // invalid lead byte, it will be replaced by from_utf8_lossy
+        };
+        return if back < expected { i } else { len };
+    }
+    len
+}
+
+fn count_into<A: Alphabet + ?Sized>(text: &str, alphabet: &A, counts: &mut [u64]) {
+    for c in text.chars() {
+        if let Some(index) = alphabet.index_of(c) {
+            counts[index] += 1;
+        }
+    }
+}
+
+// Letter counts of a whole input, percentages are computed on the letters of the alphabet only.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frequencies {
+    letters: Vec<char>,
+    counts: Vec<u64>,
+    total: u64,
+}
+
+impl Frequencies {
+    pub fn new(letters: Vec<char>, counts: Vec<u64>) -> Frequencies {
+        let total = counts.iter().sum();
+        Frequencies { letters, counts, total }
+    }
+
+    pub fn counts(&self) -> &[u64] {
+        &self.counts
+    }
+
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    pub fn count(&self, letter: char) -> Option<u64> {
+        let index = self.letters.iter().position(|&l| l == letter)?;
+        Some(self.counts[index])
+    }
+
+    pub fn percentage(&self, letter: char) -> Option<f64> {
+        let count = self.count(letter)?;
+        if self.total == 0 {
+            return Some(0.0);
+        }
+        Some(count as f64 * 100.0 / self.total as f64)
+    }
+
+    // sum of the absolute differences between observed and expected percentages,
+    // 0 means identical distributions and 200 completely disjoint ones
+    pub fn distance(&self, reference: &ReferenceFrequencies) -> f64 {
+        self.rows(Some(reference))
+            .iter()
+            .filter_map(|row| row.expected.map(|expected| (row.percentage - expected).abs()))
+            .sum()
+    }
+
+    fn rows(&self, reference: Option<&ReferenceFrequencies>) -> Vec<ReportRow> {
+        self.letters.iter()
+            .map(|&letter| ReportRow {
+                letter,
+                count: self.count(letter).unwrap_or(0),
+                percentage: self.percentage(letter).unwrap_or(0.0),
+                expected: reference.map(|r| r.percentage(letter).unwrap_or(0.0)),
+            })
+            .collect()
+    }
+
+    pub fn write_report<W: Write>(&self, out: &mut W, format: ReportFormat, reference: Option<&ReferenceFrequencies>) -> io::Result<()> {
+        let rows = self.rows(reference);
+        match format {
+            ReportFormat::Table => {
+                match reference {
+                    Some(r) => writeln!(out, "letter {:>12} {:>8} {:>8} {:>8}", "count", "%", r.name(), "delta")?,
+                    None => writeln!(out, "letter {:>12} {:>8}", "count", "%")?,
+                }
+                for row in &rows {
+                    write!(out, "{:<6} {:>12} {:>8.3}", row.letter, row.count, row.percentage)?;
+                    if let Some(expected) = row.expected {
+                        write!(out, " {:>8.3} {:>+8.3}", expected, row.percentage - expected)?;
+                    }
+                    writeln!(out)?;
+                }
+                writeln!(out, "total  {:>12}", self.total)?;
+                if let Some(r) = reference {
+                    writeln!(out, "distance from {}: {:.3}", r.name(), self.distance(r))?;
+                }
+            }
+            ReportFormat::Csv => {
+                match reference {
+                    Some(_) => writeln!(out, "letter,count,percentage,expected,delta")?,
+                    None => writeln!(out, "letter,count,percentage")?,
+                }
+                for row in &rows {
+                    write!(out, "{},{},{:.4}", row.letter, row.count, row.percentage)?;
+                    if let Some(expected) = row.expected {
+                        write!(out, ",{:.4},{:.4}", expected, row.percentage - expected)?;
+                    }
+                    writeln!(out)?;
+                }
+            }
+            ReportFormat::Json => {
+                write!(out, "{{\"total\":{},", self.total)?;
+                if let Some(r) = reference {
+                    write!(out, "\"reference\":{},\"distance\":{:.4},", json_string(r.name()), self.distance(r))?;
+                }
+                write!(out, "\"letters\":[")?;
+                for (i, row) in rows.iter().enumerate() {
+                    if i > 0 {
+                        write!(out, ",")?;
+                    }
+                    let letter = json_string(row.letter.encode_utf8(&mut [0; 4]));
+                    write!(out, "{{\"letter\":{},\"count\":{},\"percentage\":{:.4}", letter, row.count, row.percentage)?;
+                    if let Some(expected) = row.expected {
+                        write!(out, ",\"expected\":{:.4},\"delta\":{:.4}", expected, row.percentage - expected)?;
+                    }
+                    write!(out, "}}")?;
+                }
+                writeln!(out, "]}}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// JSON string literal: quotes, backslashes and control characters must be escaped
+fn json_string(s: &str) -> String {
+    let mut res = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => res.push_str("\\\""),
+            '\\' => res.push_str("\\\\"),
+            '\n' => res.push_str("\\n"),
+            '\r' => res.push_str("\\r"),
+            '\t' => res.push_str("\\t"),
+            c if c.is_control() => res.push_str(&format!("\\u{:04x}", c as u32)),
+            c => res.push(c),
+        }
+    }
+    res.push('"');
+    res
+}
+
+struct ReportRow {
+    letter: char,
+    count: u64,
+    percentage: f64,
+    expected: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReportFormat {
+    Table,
+    Csv,
+    Json,
+}
+
+impl FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "table" => Ok(ReportFormat::Table),
+            "csv" => Ok(ReportFormat::Csv),
+            "json" => Ok(ReportFormat::Json),
+            _ => Err(format!("unknown report format: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for ReportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReportFormat::Table => write!(f, "table"),
+            ReportFormat::Csv => write!(f, "csv"),
+            ReportFormat::Json => write!(f, "json"),
+        }
+    }
+}
+
+// Expected letter frequencies (percentages) of a language, taken from large reference corpora.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReferenceFrequencies {
+    name: String,
+    table: Vec<(char, f64)>,
+}
+
+impl ReferenceFrequencies {
+    // the table is normalized so that it sums to 100, published tables are rounded and often
+    // leave out accented letters
+    pub fn new(name: &str, table: &[(char, f64)]) -> ReferenceFrequencies {
+        let sum: f64 = table.iter().map(|&(_, p)| p).sum();
+        let table = table.iter()
+            .map(|&(letter, p)| (letter, if sum > 0.0 { p * 100.0 / sum } else { 0.0 }))
+            .collect();
+        ReferenceFrequencies { name: name.to_string(), table }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn percentage(&self, letter: char) -> Option<f64> {
+        self.table.iter().find(|&&(l, _)| l == letter).map(|&(_, p)| p)
+    }
+
+    pub fn english() -> ReferenceFrequencies {
+        ReferenceFrequencies::new("english", &[
+            ('a', 8.167), ('b', 1.492), ('c', 2.782), ('d', 4.253), ('e', 12.702), ('f', 2.228),
+            ('g', 2.015), ('h', 6.094), ('i', 6.966), ('j', 0.153), ('k', 0.772), ('l', 4.025),
+            ('m', 2.406), ('n', 6.749), ('o', 7.507), ('p', 1.929), ('q', 0.095), ('r', 5.987),
+            ('s', 6.327), ('t', 9.056), ('u', 2.758), ('v', 0.978), ('w', 2.360), ('x', 0.150),
+            ('y', 1.974), ('z', 0.074),
+        ])
+    }
+
+    pub fn italian() -> ReferenceFrequencies {
+        ReferenceFrequencies::new("italian", &[
+            ('a', 11.745), ('b', 0.927), ('c', 4.501), ('d', 3.736), ('e', 11.792), ('f', 1.153),
+            ('g', 1.644), ('h', 0.636), ('i', 10.143), ('j', 0.011), ('k', 0.009), ('l', 6.510),
+            ('m', 2.512), ('n', 6.883), ('o', 9.832), ('p', 3.056), ('q', 0.505), ('r', 6.367),
+            ('s', 4.981), ('t', 5.623), ('u', 3.011), ('v', 2.097), ('w', 0.033), ('x', 0.003),
+            ('y', 0.020), ('z', 1.181),
+        ])
+    }
+
+    pub fn german() -> ReferenceFrequencies {
+        ReferenceFrequencies::new("german", &[
+            ('a', 6.516), ('b', 1.886), ('c', 2.732), ('d', 5.076), ('e', 16.396), ('f', 1.656),
+            ('g', 3.009), ('h', 4.577), ('i', 6.550), ('j', 0.268), ('k', 1.417), ('l', 3.437),
+            ('m', 2.534), ('n', 9.776), ('o', 2.594), ('p', 0.670), ('q', 0.018), ('r', 7.003),
+            ('s', 7.270), ('t', 6.154), ('u', 4.166), ('v', 0.846), ('w', 1.921), ('x', 0.034),
+            ('y', 0.039), ('z', 1.134), ('ä', 0.578), ('ö', 0.443), ('ü', 0.995), ('ß', 0.307),
+        ])
+    }
+
+    pub fn by_name(name: &str) -> Option<ReferenceFrequencies> {
+        match name.to_lowercase().as_str() {
+            "english" => Some(ReferenceFrequencies::english()),
+            "italian" => Some(ReferenceFrequencies::italian()),
+            "german" => Some(ReferenceFrequencies::german()),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alphabet::{stats_with, LetterSet};
+    use std::io::Cursor;
+
+    const TEXT: &str = "The quick brown fox jumps over the lazy dog.\nΞεσκεπάζω την ψυχοφθόρα βδελυγμία\n";
+
+    fn sequential(text: &str, alphabet: &LetterSet) -> Vec<u64> {
+        stats_with(text, alphabet).into_iter().map(u64::from).collect()
+    }
+
+    #[test]
+    fn test_parallel_counts_match_sequential() {
+        let text = TEXT.repeat(100);
+        for workers in [1, 2, 4] {
+            let analyzer = FrequencyAnalyzer::new(LetterSet::latin()).workers(workers).chunk_size(64);
+            let frequencies = analyzer.analyze_reader(Cursor::new(text.as_bytes())).unwrap();
+            assert_eq!(frequencies.counts(), sequential(&text, &LetterSet::latin()).as_slice());
+        }
+    }
+
+    #[test]
+    fn test_chunks_never_split_a_character() {
+        // with 5 byte chunks almost every two-byte Greek letter crosses a chunk border
+        let text = TEXT.repeat(10);
+        let analyzer = FrequencyAnalyzer::new(LetterSet::greek()).workers(3).chunk_size(5);
+        let frequencies = analyzer.analyze_reader(Cursor::new(text.as_bytes())).unwrap();
+        assert_eq!(frequencies.counts(), sequential(&text, &LetterSet::greek()).as_slice());
+    }
+
+    #[test]
+    fn test_char_boundary() {
+        let bytes = "aé".as_bytes();
+        assert_eq!(char_boundary(bytes), 3);
+        assert_eq!(char_boundary(&bytes[..2]), 1);
+        assert_eq!(char_boundary("€".as_bytes()), 3);
+        assert_eq!(char_boundary(&"€".as_bytes()[..2]), 0);
+        assert_eq!(char_boundary(b""), 0);
+    }
+
+    #[test]
+    fn test_empty_input() {
+        let analyzer = FrequencyAnalyzer::new(LetterSet::latin()).workers(2);
+        let frequencies = analyzer.analyze_reader(Cursor::new(Vec::new())).unwrap();
+        assert_eq!(frequencies.total(), 0);
+        assert_eq!(frequencies.percentage('a'), Some(0.0));
+    }
+
+    #[test]
+    fn test_percentages_and_distance() {
+        let frequencies = Frequencies::new(vec!['a', 'b'], vec![3, 1]);
+        assert_eq!(frequencies.percentage('a'), Some(75.0));
+        assert_eq!(frequencies.percentage('c'), None);
+
+        let reference = ReferenceFrequencies::new("half", &[('a', 50.0), ('b', 50.0)]);
+        assert!((frequencies.distance(&reference) - 50.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_csv_report() {
+        let frequencies = Frequencies::new(vec!['a', 'b'], vec![3, 1]);
+        let mut out = Vec::new();
+        frequencies.write_report(&mut out, ReportFormat::Csv, None).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "letter,count,percentage\na,3,75.0000\nb,1,25.0000\n");
+    }
+
+    #[test]
+    fn test_json_report_with_reference() {
+        let frequencies = Frequencies::new(vec!['a', 'b'], vec![1, 1]);
+        let reference = ReferenceFrequencies::new("flat", &[('a', 50.0), ('b', 50.0)]);
+        let mut out = Vec::new();
+        frequencies.write_report(&mut out, ReportFormat::Json, Some(&reference)).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "{\"total\":2,\"reference\":\"flat\",\"distance\":0.0000,\"letters\":[\
+             {\"letter\":\"a\",\"count\":1,\"percentage\":50.0000,\"expected\":50.0000,\"delta\":0.0000},\
+             {\"letter\":\"b\",\"count\":1,\"percentage\":50.0000,\"expected\":50.0000,\"delta\":0.0000}]}\n"
+        );
+    }
+
+    #[test]
+    fn test_json_report_escapes_strings() {
+        let frequencies = Frequencies::new(vec!['"', '\\'], vec![1, 0]);
+        let reference = ReferenceFrequencies::new("say \"hi\"\t\\o/", &[('"', 100.0)]);
+        let mut out = Vec::new();
+        frequencies.write_report(&mut out, ReportFormat::Json, Some(&reference)).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "{\"total\":1,\"reference\":\"say \\\"hi\\\"\\t\\\\o/\",\"distance\":0.0000,\"letters\":[\
+             {\"letter\":\"\\\"\",\"count\":1,\"percentage\":100.0000,\"expected\":100.0000,\"delta\":0.0000},\
+             {\"letter\":\"\\\\\",\"count\":0,\"percentage\":0.0000,\"expected\":0.0000,\"delta\":0.0000}]}\n"
+        );
+        assert_eq!(json_string("\u{1}é"), "\"\\u0001é\"");
+    }
+
+    #[test]
+    fn test_reference_tables_sum_to_100() {
+        for name in ["english", "italian", "german"] {
+            let reference = ReferenceFrequencies::by_name(name).unwrap();
+            let sum: f64 = reference.table.iter().map(|&(_, p)| p).sum();
+            assert!((sum - 100.0).abs() < 1e-9, "{} sums to {}", name, sum);
+        }
+    }
+
+    #[test]
+    fn test_report_format_from_str() {
+        assert_eq!("CSV".parse::<ReportFormat>(), Ok(ReportFormat::Csv));
+        assert!("xml".parse::<ReportFormat>().is_err());
+    }
+}
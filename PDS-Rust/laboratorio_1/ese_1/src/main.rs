@@ -1,45 +1,29 @@
+pub mod alphabet;
+pub mod frequency;
+
 use std::fs::File;
 use std::io::{self, BufRead, BufReader};
 use std::env;
+use alphabet::{Alphabet, LetterSet, PangramReport};
+use frequency::{FrequencyAnalyzer, ReferenceFrequencies, ReportFormat};
 
-fn stats(text: &str) -> [u32; 26] {
-    let mut array = [0; 26];
-
-    for c in text.chars() {
-        if c.is_ascii_alphabetic() {
-            let index = (c.to_ascii_lowercase() as u32 - 'a' as u32) as usize;
-            array[index] += 1;
-        }
-    }
-
-    return array;
-}
-
-fn is_pangram(counts: &[u32]) -> bool {
-    if counts.len() != 26 {
-        return false;
-    }
-
-    // Using `&count` in the loop declaration (`for &count in ...`) applies pattern matching
-    // to directly unpack the value. Here, `count` is an `i32` rather than a reference,
-    // so there is no need for explicit dereferencing inside the loop.
-    // In contrast, `counts.iter()` returns an iterator over `&i32` (references to elements of `counts`).
-    // Thus, `count` is of type `&i32`, requiring explicit dereferencing with `*count` to access the value.
-
-    for &count in counts.iter() {
-        if count == 0 {
-            return false;
-        }
-    }
-    return true
+// the report tells whether the text is a pangram of the alphabet, how many times each letter
+// appears, which letters are missing and where each letter appears for the first time
+fn is_pangram<A: Alphabet + ?Sized>(text: &str, alphabet: &A) -> PangramReport {
+    alphabet::analyze(text, alphabet)
 }
 
 fn read_file(path: &str) -> Result<String, io::Error> {
     let file = File::open(path)?;
     let reader = BufReader::new(file);
 
+    // lines are joined back with '\n', otherwise the last word of a line and the first
+    // of the next one would be merged
     let mut content = String::new();
     for line in reader.lines() {
+        if !content.is_empty() {
+            content.push('\n');
+        }
         content.push_str(&line?);
     }
 
@@ -47,7 +31,7 @@ fn read_file(path: &str) -> Result<String, io::Error> {
 }
 
 // call this function from main
-// load here the contents of the file
+// load here the contents of the file, the optional second argument selects the alphabet
 pub fn run_pangram() {
     let args: Vec<String> = env::args().collect();
 
@@ -55,34 +39,119 @@ pub fn run_pangram() {
         let filename = &args[1];
         println!("Reading file: {}", filename);
 
-        let string;
-        match read_file(filename) {
+        let alphabet = match args.get(2) {
+            Some(name) => match LetterSet::by_name(name) {
+                Some(alphabet) => alphabet,
+                None => return eprintln!("Unknown alphabet: {}", name),
+            },
+            None => LetterSet::latin(),
+        };
+
+        let string = match read_file(filename) {
             Ok(content) => {
                 println!("{}", content);
-                string = content;
+                content
             },
             Err(e) => return eprintln!("Error reading file: {}", e),
-        }
+        };
 
-        let arraystat = stats(string.as_str());
+        let report = is_pangram(string.as_str(), &alphabet);
 
-        if is_pangram(&arraystat) {
-            println!("'{}' is a pangram", string);
-            arraystat.iter().enumerate().for_each(|(index, &x)| {
-                let char = (index as u8 + 'a' as u8) as char;
-                println!("{}: {}", char, x);
-            });
+        if report.is_pangram() {
+            println!("'{}' is a {} pangram", string, alphabet.name());
         } else {
-            println!("'{}' is not a pangram", string);
+            println!("'{}' is not a {} pangram, missing: {:?}", string, alphabet.name(), report.missing());
+        }
+        for (letter, count, first) in report.iter() {
+            match first {
+                Some(position) => println!("{}: {} (first at {})", letter, count, position),
+                None => println!("{}: {}", letter, count),
+            }
         }
-
-
     } else {
         eprintln!("No correct number of arguments passed in");
     }
 }
 
 
+// frequency report of a file (or of stdin when the file is missing or '-'):
+// freq [--alphabet name] [--format table|csv|json] [--reference lang] [--workers n] [--chunk-size bytes] [file]
+pub fn run_frequency(args: &[String]) {
+    let mut alphabet = LetterSet::latin();
+    let mut format = ReportFormat::Table;
+    let mut reference = None;
+    let mut workers = None;
+    let mut chunk_size = None;
+    let mut input = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if !arg.starts_with("--") {
+            input = Some(arg.clone());
+            continue;
+        }
+        let value = match iter.next() {
+            Some(value) => value,
+            None => return eprintln!("Missing value for {}", arg),
+        };
+        match arg.as_str() {
+            "--alphabet" => match LetterSet::by_name(value) {
+                Some(a) => alphabet = a,
+                None => return eprintln!("Unknown alphabet: {}", value),
+            },
+            "--format" => match value.parse() {
+                Ok(f) => format = f,
+                Err(e) => return eprintln!("{}", e),
+            },
+            "--reference" => match ReferenceFrequencies::by_name(value) {
+                Some(r) => reference = Some(r),
+                None => return eprintln!("Unknown reference language: {}", value),
+            },
+            "--workers" => match value.parse() {
+                Ok(n) => workers = Some(n),
+                Err(_) => return eprintln!("Invalid number of workers: {}", value),
+            },
+            "--chunk-size" => match value.parse() {
+                Ok(n) => chunk_size = Some(n),
+                Err(_) => return eprintln!("Invalid chunk size: {}", value),
+            },
+            _ => return eprintln!("Unknown option: {}", arg),
+        }
+    }
+
+    let mut analyzer = FrequencyAnalyzer::new(alphabet);
+    if let Some(n) = workers {
+        analyzer = analyzer.workers(n);
+    }
+    if let Some(n) = chunk_size {
+        analyzer = analyzer.chunk_size(n);
+    }
+
+    let result = match input.as_deref() {
+        None | Some("-") => analyzer.analyze_stdin(),
+        Some(path) => analyzer.analyze_file(path),
+    };
+    let frequencies = match result {
+        Ok(frequencies) => frequencies,
+        Err(e) => return eprintln!("Error reading input: {}", e),
+    };
+
+    if let Err(e) = frequencies.write_report(&mut io::stdout().lock(), format, reference.as_ref()) {
+        eprintln!("Error writing report: {}", e);
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+
+    match args.get(1).map(String::as_str) {
+        Some("freq") => run_frequency(&args[2..]),
+        Some(_) => run_pangram(),
+        None => println!("Running tests"),
+    }
+}
+
+
 // please note, code has been splittend in simple functions in order to make testing easier
 
 #[cfg(test)] // this is a test module
@@ -93,52 +162,58 @@ mod tests
 
     #[test]
     fn test_all_ones() {
-        let counts = [1; 26];
-        assert!(is_pangram(&counts));
+        let report = is_pangram("abcdefghijklmnopqrstuvwxyz", &LetterSet::latin());
+        assert!(report.is_pangram());
     }
 
     #[test]
     fn test_some_zeros() {
-        let mut counts = [0; 26];
-        counts[0] = 0;
-        counts[1] = 0;
-        assert!(!is_pangram(&counts));
+        let report = is_pangram("cdefghijklmnopqrstuvwxyz", &LetterSet::latin());
+        assert!(!report.is_pangram());
+        assert_eq!(report.missing(), vec!['a', 'b']);
     }
 
     #[test]
     fn test_increasing_counts() {
-        let mut counts = [0; 26];
-        for i in 0..26 {
-            counts[i] = i as u32 + 1;
+        let mut text = String::new();
+        for (i, letter) in ('a'..='z').enumerate() {
+            text.extend(std::iter::repeat_n(letter, i + 1));
         }
-        assert!(is_pangram(&counts));
+        let report = is_pangram(&text, &LetterSet::latin());
+        assert!(report.is_pangram());
+        assert_eq!(report.count('z'), Some(26));
+        assert_eq!(report.first_occurrence('c'), Some(3));
     }
 
     #[test]
-    fn test_wrong_size()  {
-        let counts = [1; 25];
-        assert!(!is_pangram(&counts));
+    fn test_wrong_alphabet()  {
+        let report = is_pangram("The quick brown fox jumps over the lazy dog", &LetterSet::greek());
+        assert!(!report.is_pangram());
+        assert_eq!(report.missing().len(), 24);
     }
 
     #[test]
     fn test_stats_on_full_alphabet() {
-        let counts = stats("abcdefghijklmnopqrstuvwxyz");
-        for c in counts {
+        let report = is_pangram("abcdefghijklmnopqrstuvwxyz", &LetterSet::latin());
+        let counts = report.counts();
+        for &c in counts {
             assert!(c == 1);
         }
     }
 
     #[test]
     fn test_stats_on_empty_string() {
-        let counts = stats("");
-        for c in counts {
+        let report = is_pangram("", &LetterSet::latin());
+        let counts = report.counts();
+        for &c in counts {
             assert!(c == 0);
         }
     }
 
     #[test]
     fn test_stats_missing_char() {
-        let counts = stats("abcdefghijklmnopqrstuvwxy");
+        let report = is_pangram("abcdefghijklmnopqrstuvwxy", &LetterSet::latin());
+        let counts = report.counts();
         for c in counts.iter().take(25) {
             assert!(*c == 1);
         }
@@ -149,8 +224,9 @@ mod tests
     #[test]
     fn test_stats_on_full_tring() {
         let contents = "The quick brown fox jumps over the lazy dog";
-        let counts = stats(contents);
-        for c in counts {
+        let report = is_pangram(contents, &LetterSet::latin());
+        let counts = report.counts();
+        for &c in counts {
             assert!(c > 0);
         }
     }
@@ -158,8 +234,9 @@ mod tests
     #[test]
     fn test_stats_with_punctuation() {
         let contents = "The quick brown fox jumps over the lazy dog!";
-        let counts = stats(contents);
-        for c in counts {
+        let report = is_pangram(contents, &LetterSet::latin());
+        let counts = report.counts();
+        for &c in counts {
             assert!(c > 0);
         }
     }
@@ -167,7 +244,8 @@ mod tests
     #[test]
     fn test_missing_char_on_full_string() {
         let contents = "The quick brown fox jumps over the laz* dog";
-        let counts = stats(contents);
+        let report = is_pangram(contents, &LetterSet::latin());
+        let counts = report.counts();
         println!("{:?}", counts);
         for (i, c) in counts.iter().enumerate() {
             if i == 24 {
@@ -181,12 +259,12 @@ mod tests
 
     #[test]
     fn test_is_pangram() {
-        let counts = stats("The quick brown fox jumps over the lazy dog");
-        assert!(is_pangram(&counts));
-    }
-}
+        let report = is_pangram("The quick brown fox jumps over the lazy dog", &LetterSet::latin());
+        assert!(report.is_pangram());
+        assert_eq!(report.first_occurrence('t'), Some(0));
+        assert_eq!(report.first_occurrence('g'), Some(42));
 
-fn main() {
-    println!("Running tests");
+        let report = is_pangram("Ξεσκεπάζω την ψυχοφθόρα βδελυγμία", &LetterSet::greek());
+        assert!(report.is_pangram());
+    }
 }
-
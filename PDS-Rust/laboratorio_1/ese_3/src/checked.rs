@@ -0,0 +1,268 @@
+use std::fmt;
+use std::error;
+use std::num::IntErrorKind;
+use std::str::FromStr;
+
+use num::traits::{CheckedRem, NumCast, PrimInt, SaturatingMul, WrappingAdd, WrappingMul, WrappingSub};
+
+// Checked arithmetic over every primitive integer type. Each operation takes a policy that
+// decides what happens when the exact result does not fit in the type: the error is reported,
+// the result is clamped to the bounds of the type, or it wraps around (two's complement).
+// A division by zero has no meaningful result and is always an error.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArithError {
+    Overflow,       // the exact result is above the maximum of the type
+    Underflow,      // the exact result is below the minimum of the type
+    DivisionByZero,
+    InvalidNumber,  // the input is not a number at all
+}
+
+impl fmt::Display for ArithError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArithError::Overflow => write!(f, "arithmetic overflow"),
+            ArithError::Underflow => write!(f, "arithmetic underflow"),
+            ArithError::DivisionByZero => write!(f, "division by zero"),
+            ArithError::InvalidNumber => write!(f, "invalid number"),
+        }
+    }
+}
+
+impl error::Error for ArithError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Policy {
+    #[default]
+    Error,
+    Saturate,
+    Wrap,
+}
+
+// every primitive integer (i8..i128, isize, u8..u128, usize) implements these
+pub trait Int: PrimInt + CheckedRem + SaturatingMul + WrappingAdd + WrappingSub + WrappingMul + fmt::Debug {}
+
+impl<T: PrimInt + CheckedRem + SaturatingMul + WrappingAdd + WrappingSub + WrappingMul + fmt::Debug> Int for T {}
+
+fn is_negative<T: Int>(n: T) -> bool {
+    n < T::zero()
+}
+
+// applies the policy to an out-of-range result: `err` tells in which direction it went out
+fn resolve<T: Int>(err: ArithError, policy: Policy, wrapped: impl FnOnce() -> T) -> Result<T, ArithError> {
+    match (policy, err) {
+        (Policy::Error, _) => Err(err),
+        (Policy::Saturate, ArithError::Overflow) => Ok(T::max_value()),
+        (Policy::Saturate, ArithError::Underflow) => Ok(T::min_value()),
+        (Policy::Saturate, _) => Err(err),
+        (Policy::Wrap, _) => Ok(wrapped()),
+    }
+}
+
+pub fn add<T: Int>(a: T, b: T, policy: Policy) -> Result<T, ArithError> {
+    match a.checked_add(&b) {
+        Some(res) => Ok(res),
+        None if is_negative(b) => resolve(ArithError::Underflow, policy, || a.wrapping_add(&b)),
+        None => resolve(ArithError::Overflow, policy, || a.wrapping_add(&b)),
+    }
+}
+
+pub fn sub<T: Int>(a: T, b: T, policy: Policy) -> Result<T, ArithError> {
+    match a.checked_sub(&b) {
+        Some(res) => Ok(res),
+        // a - b with b > 0 can only go below the minimum
+        None if b > T::zero() => resolve(ArithError::Underflow, policy, || a.wrapping_sub(&b)),
+        None => resolve(ArithError::Overflow, policy, || a.wrapping_sub(&b)),
+    }
+}
+
+pub fn mul<T: Int>(a: T, b: T, policy: Policy) -> Result<T, ArithError> {
+    match a.checked_mul(&b) {
+        Some(res) => Ok(res),
+        // the exact product is negative when the signs differ
+        None if is_negative(a) != is_negative(b) => resolve(ArithError::Underflow, policy, || a.wrapping_mul(&b)),
+        None => resolve(ArithError::Overflow, policy, || a.wrapping_mul(&b)),
+    }
+}
+
+pub fn div<T: Int>(a: T, b: T, policy: Policy) -> Result<T, ArithError> {
+    if b.is_zero() {
+        return Err(ArithError::DivisionByZero);
+    }
+    match a.checked_div(&b) {
+        Some(res) => Ok(res),
+        // only MIN / -1 fails: the result is MAX + 1, which wraps around to MIN
+        None => resolve(ArithError::Overflow, policy, T::min_value),
+    }
+}
+
+pub fn rem<T: Int>(a: T, b: T, policy: Policy) -> Result<T, ArithError> {
+    if b.is_zero() {
+        return Err(ArithError::DivisionByZero);
+    }
+    match a.checked_rem(&b) {
+        Some(res) => Ok(res),
+        // MIN % -1: the remainder is 0, but computing it overflows (MIN / -1)
+        None => match policy {
+            Policy::Error => Err(ArithError::Overflow),
+            Policy::Saturate | Policy::Wrap => Ok(T::zero()),
+        },
+    }
+}
+
+pub fn pow<T: Int>(base: T, exp: u32, policy: Policy) -> Result<T, ArithError> {
+    // exponentiation by squaring: O(log exp) products even for a huge exponent
+    if let Some(res) = num::checked_pow(base, exp as usize) {
+        return Ok(res);
+    }
+
+    // a negative base with an odd exponent gives a negative result
+    let err = if is_negative(base) && exp % 2 == 1 { ArithError::Underflow } else { ArithError::Overflow };
+    resolve(err, policy, || wrapping_pow(base, exp))
+}
+
+// exponentiation by squaring, every product wraps around
+fn wrapping_pow<T: Int>(mut base: T, mut exp: u32) -> T {
+    let mut res = T::one();
+    while exp > 0 {
+        if exp & 1 == 1 {
+            res = res.wrapping_mul(&base);
+        }
+        base = base.wrapping_mul(&base);
+        exp >>= 1;
+    }
+    res
+}
+
+// conversion between integer types, e.g. i32 -> u32 fails with Underflow for negative values
+pub fn cast<T: Int, U: Int>(value: T, policy: Policy) -> Result<U, ArithError> {
+    if let Some(res) = <U as NumCast>::from(value) {
+        return Ok(res);
+    }
+    let err = if is_negative(value) { ArithError::Underflow } else { ArithError::Overflow };
+    // wrapping keeps the low bits of the value, as the `as` operator does. The value is first
+    // sign-extended to 128 bits, so that a narrow type can give the bits of a wider one
+    resolve(err, policy, || {
+        let wide = match value.to_i128() {
+            Some(n) => n as u128,
+            None => value.to_u128().unwrap(),
+        };
+        let bits = U::zero().count_zeros() as usize;
+        let mut res = U::zero();
+        for i in (0..bits).rev() {
+            let bit = (wide >> i) & 1;
+            res = (res << 1) | if bit == 0 { U::zero() } else { U::one() };
+        }
+        res
+    })
+}
+
+// parses untrusted input: out of range values are resolved with the policy
+pub fn parse<T: Int + FromStr<Err = std::num::ParseIntError>>(s: &str, policy: Policy) -> Result<T, ArithError> {
+    match s.trim().parse::<T>() {
+        Ok(n) => Ok(n),
+        Err(e) => {
+            let err = match e.kind() {
+                IntErrorKind::PosOverflow => ArithError::Overflow,
+                IntErrorKind::NegOverflow => ArithError::Underflow,
+                _ => return Err(ArithError::InvalidNumber),
+            };
+            // a number with too many digits has no sensible wrapped value
+            let policy = if policy == Policy::Wrap { Policy::Error } else { policy };
+            resolve(err, policy, T::zero)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::Policy::{Error, Saturate, Wrap};
+
+    #[test]
+    fn test_add_sub() {
+        assert_eq!(add(2i32, 3, Error), Ok(5));
+        assert_eq!(add(i32::MAX, 1, Error), Err(ArithError::Overflow));
+        assert_eq!(add(i32::MIN, -1, Error), Err(ArithError::Underflow));
+        assert_eq!(add(250u8, 10, Saturate), Ok(255));
+        assert_eq!(add(250u8, 10, Wrap), Ok(4));
+        assert_eq!(add(-100i8, -100, Saturate), Ok(-128));
+
+        assert_eq!(sub(3u32, 5, Error), Err(ArithError::Underflow));
+        assert_eq!(sub(3u32, 5, Saturate), Ok(0));
+        assert_eq!(sub(3u32, 5, Wrap), Ok(u32::MAX - 1));
+        assert_eq!(sub(i64::MAX, -1, Error), Err(ArithError::Overflow));
+    }
+
+    #[test]
+    fn test_mul() {
+        assert_eq!(mul(6u16, 7, Error), Ok(42));
+        assert_eq!(mul(2i32.pow(30) - 1, 5, Error), Err(ArithError::Overflow));
+        assert_eq!(mul(i32::MAX, -2, Error), Err(ArithError::Underflow));
+        assert_eq!(mul(i32::MAX, -2, Saturate), Ok(i32::MIN));
+        assert_eq!(mul(-128i8, -1, Saturate), Ok(127));
+        assert_eq!(mul(16u8, 17, Wrap), Ok(16));
+    }
+
+    #[test]
+    fn test_div_rem() {
+        assert_eq!(div(7i32, 2, Error), Ok(3));
+        assert_eq!(div(7u64, 0, Saturate), Err(ArithError::DivisionByZero));
+        assert_eq!(div(i8::MIN, -1, Error), Err(ArithError::Overflow));
+        assert_eq!(div(i8::MIN, -1, Saturate), Ok(i8::MAX));
+        assert_eq!(div(i8::MIN, -1, Wrap), Ok(i8::MIN));
+
+        assert_eq!(rem(-7i32, 2, Error), Ok(-1));
+        assert_eq!(rem(7usize, 0, Wrap), Err(ArithError::DivisionByZero));
+        assert_eq!(rem(i16::MIN, -1, Error), Err(ArithError::Overflow));
+        assert_eq!(rem(i16::MIN, -1, Wrap), Ok(0));
+    }
+
+    #[test]
+    fn test_pow() {
+        assert_eq!(pow(3i64, 4, Error), Ok(81));
+        assert_eq!(pow(5u8, 0, Error), Ok(1));
+        assert_eq!(pow(2u8, 8, Error), Err(ArithError::Overflow));
+        assert_eq!(pow(-2i8, 7, Error), Ok(-128));
+        assert_eq!(pow(-3i8, 5, Error), Err(ArithError::Underflow));
+        assert_eq!(pow(-3i8, 5, Saturate), Ok(i8::MIN));
+        assert_eq!(pow(3u8, 6, Wrap), Ok(3u8.wrapping_pow(6)));
+        assert_eq!(pow(-3i128, 99, Wrap), Ok((-3i128).wrapping_pow(99)));
+
+        // huge exponents take a handful of products
+        assert_eq!(pow(1u64, u32::MAX, Error), Ok(1));
+        assert_eq!(pow(-1i32, u32::MAX, Error), Ok(-1));
+        assert_eq!(pow(0u8, u32::MAX, Error), Ok(0));
+        assert_eq!(pow(2u64, u32::MAX, Error), Err(ArithError::Overflow));
+        assert_eq!(pow(3u32, u32::MAX, Wrap), Ok(3u32.wrapping_pow(u32::MAX)));
+    }
+
+    #[test]
+    fn test_cast() {
+        assert_eq!(cast::<i32, u32>(42, Error), Ok(42));
+        assert_eq!(cast::<i32, u32>(-1, Error), Err(ArithError::Underflow));
+        assert_eq!(cast::<i32, u8>(300, Saturate), Ok(255));
+        assert_eq!(cast::<i32, u8>(300, Wrap), Ok(300i32 as u8));
+        assert_eq!(cast::<i32, i8>(-200, Wrap), Ok(-200i32 as i8));
+        assert_eq!(cast::<u64, i16>(u64::MAX, Saturate), Ok(i16::MAX));
+
+        // narrow to wide: negative values are sign-extended
+        assert_eq!(cast::<i8, u64>(-1, Wrap), Ok(-1i8 as u64));
+        assert_eq!(cast::<i8, u128>(-128, Wrap), Ok(-128i8 as u128));
+        assert_eq!(cast::<i16, usize>(-300, Wrap), Ok(-300i16 as usize));
+        assert_eq!(cast::<i32, u16>(-70000, Wrap), Ok(-70000i32 as u16));
+        assert_eq!(cast::<i128, u8>(i128::MIN + 5, Wrap), Ok((i128::MIN + 5) as u8));
+        assert_eq!(cast::<u128, i8>(u128::MAX, Wrap), Ok(u128::MAX as i8));
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse::<u8>(" 200 ", Error), Ok(200));
+        assert_eq!(parse::<u8>("256", Error), Err(ArithError::Overflow));
+        assert_eq!(parse::<u8>("256", Saturate), Ok(255));
+        assert_eq!(parse::<i8>("-129", Saturate), Ok(-128));
+        assert_eq!(parse::<u32>("-1", Error), Err(ArithError::InvalidNumber));
+        assert_eq!(parse::<i32>("12a", Saturate), Err(ArithError::InvalidNumber));
+        assert_eq!(parse::<i64>("99999999999999999999", Wrap), Err(ArithError::Overflow));
+    }
+}
@@ -0,0 +1,198 @@
+use std::io::{self, BufRead, Read, Write};
+
+// xxd-like hex viewer: every row shows the offset, the bytes in hex (grouped) and their
+// printable ASCII representation, e.g.
+// 00000000: 4865 6c6c 6f20 776f 726c 640a            Hello world.
+#[derive(Debug, Clone)]
+pub struct HexDumpOptions {
+    pub cols: usize,         // bytes per row
+    pub group: usize,        // bytes per group, 0 means no separation between groups
+    pub start: u64,          // first byte to show
+    pub length: Option<u64>, // number of bytes to show, None = up to the end
+    pub ascii: bool,         // show the ASCII column
+    pub uppercase: bool,     // hex digits in uppercase
+}
+
+impl Default for HexDumpOptions {
+    fn default() -> Self {
+        HexDumpOptions { cols: 16, group: 2, start: 0, length: None, ascii: true, uppercase: false }
+    }
+}
+
+// dumps a reader already positioned at `opts.start` (offsets shown start from there)
+pub fn dump<R: Read, W: Write>(reader: R, out: &mut W, opts: &HexDumpOptions) -> io::Result<()> {
+    let cols = opts.cols.max(1);
+    let mut reader: Box<dyn Read> = match opts.length {
+        Some(length) => Box::new(reader.take(length)),
+        None => Box::new(reader),
+    };
+
+    let mut row = vec![0u8; cols];
+    let mut offset = opts.start;
+    loop {
+        let n = read_row(&mut reader, &mut row)?;
+        if n == 0 {
+            break;
+        }
+        write_row(out, offset, &row[..n], opts)?;
+        offset += n as u64;
+    }
+    out.flush()
+}
+
+// fills the row, a short read is accepted only at the end of the input
+fn read_row<R: Read>(reader: &mut R, row: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < row.len() {
+        match reader.read(&mut row[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+fn write_row<W: Write>(out: &mut W, offset: u64, bytes: &[u8], opts: &HexDumpOptions) -> io::Result<()> {
+    let cols = opts.cols.max(1);
+    let mut line = String::with_capacity(10 + cols * 4);
+    line.push_str(&format!("{:08x}:", offset));
+
+    for i in 0..cols {
+        if opts.group == 0 {
+            if i == 0 {
+                line.push(' ');
+            }
+        } else if i % opts.group == 0 {
+            line.push(' ');
+        }
+        match bytes.get(i) {
+            Some(b) if opts.uppercase => line.push_str(&format!("{:02X}", b)),
+            Some(b) => line.push_str(&format!("{:02x}", b)),
+            // padding keeps the ASCII column aligned on the last row
+            None => line.push_str("  "),
+        }
+    }
+
+    if opts.ascii {
+        line.push_str("  ");
+        line.extend(bytes.iter().map(|&b| if b.is_ascii_graphic() || b == b' ' { b as char } else { '.' }));
+    }
+
+    writeln!(out, "{}", line)
+}
+
+// Rebuilds the binary data from a dump produced by `dump` (or by xxd). The hex column ends at
+// the first double space, so the ASCII column is never parsed. Bytes are written at the offset
+// of their row, relative to the first row: gaps are filled with zeros.
+pub fn reverse<R: BufRead, W: Write>(reader: R, out: &mut W) -> io::Result<u64> {
+    let mut base: Option<u64> = None;
+    let mut written: u64 = 0;
+
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let invalid = |msg: &str| io::Error::new(io::ErrorKind::InvalidData, format!("line {}: {}", i + 1, msg));
+
+        let (offset, data) = line.split_once(':').ok_or_else(|| invalid("missing offset"))?;
+        let offset = u64::from_str_radix(offset.trim(), 16).map_err(|_| invalid("invalid offset"))?;
+        let base = *base.get_or_insert(offset);
+        let position = offset.checked_sub(base).ok_or_else(|| invalid("offset before the first row"))?;
+        if position < written {
+            return Err(invalid("overlapping rows"));
+        }
+
+        // zero fill, as xxd -r does when rows are missing
+        io::copy(&mut io::repeat(0).take(position - written), out)?;
+        written = position;
+
+        let data = data.strip_prefix(' ').unwrap_or(data);
+        let hex = match data.find("  ") {
+            Some(end) => &data[..end],
+            None => data,
+        };
+        let digits: Vec<u8> = hex.bytes().filter(|b| !b.is_ascii_whitespace()).collect();
+        if !digits.len().is_multiple_of(2) {
+            return Err(invalid("odd number of hex digits"));
+        }
+
+        let mut bytes = Vec::with_capacity(digits.len() / 2);
+        for pair in digits.chunks(2) {
+            let pair = std::str::from_utf8(pair).map_err(|_| invalid("invalid hex digit"))?;
+            bytes.push(u8::from_str_radix(pair, 16).map_err(|_| invalid("invalid hex digit"))?);
+        }
+        out.write_all(&bytes)?;
+        written += bytes.len() as u64;
+    }
+
+    out.flush()?;
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dump_to_string(data: &[u8], opts: &HexDumpOptions) -> String {
+        let mut out = Vec::new();
+        dump(data, &mut out, opts).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_default_format() {
+        let out = dump_to_string(b"Hello world\n", &HexDumpOptions::default());
+        assert_eq!(out, "00000000: 4865 6c6c 6f20 776f 726c 640a            Hello world.\n");
+    }
+
+    #[test]
+    fn test_multiple_rows_and_grouping() {
+        let data: Vec<u8> = (0u8..20).collect();
+        let opts = HexDumpOptions { cols: 8, group: 4, ascii: false, uppercase: true, ..Default::default() };
+        let out = dump_to_string(&data, &opts);
+        assert_eq!(out, "00000000: 00010203 04050607\n\
+                         00000008: 08090A0B 0C0D0E0F\n\
+                         00000010: 10111213         \n");
+    }
+
+    #[test]
+    fn test_no_grouping() {
+        let opts = HexDumpOptions { cols: 4, group: 0, ..Default::default() };
+        assert_eq!(dump_to_string(b"abcd", &opts), "00000000: 61626364  abcd\n");
+    }
+
+    #[test]
+    fn test_reverse_roundtrip() {
+        let data: Vec<u8> = (0..1000u32).map(|i| (i * 7 % 256) as u8).collect();
+        for opts in [
+            HexDumpOptions::default(),
+            HexDumpOptions { cols: 7, group: 3, uppercase: true, ..Default::default() },
+            HexDumpOptions { cols: 32, group: 0, ascii: false, ..Default::default() },
+        ] {
+            let dumped = dump_to_string(&data, &opts);
+            let mut rebuilt = Vec::new();
+            assert_eq!(reverse(dumped.as_bytes(), &mut rebuilt).unwrap(), 1000);
+            assert_eq!(rebuilt, data);
+        }
+    }
+
+    #[test]
+    fn test_reverse_ignores_hex_looking_ascii_and_fills_gaps() {
+        let dump = "00000000: 6162 6364  abcd\n00000008: 6566  ef\n";
+        let mut rebuilt = Vec::new();
+        reverse(dump.as_bytes(), &mut rebuilt).unwrap();
+        assert_eq!(rebuilt, b"abcd\0\0\0\0ef");
+    }
+
+    #[test]
+    fn test_reverse_errors() {
+        let mut out = Vec::new();
+        assert!(reverse("no offset here".as_bytes(), &mut out).is_err());
+        assert!(reverse("00000000: 616".as_bytes(), &mut out).is_err());
+        assert!(reverse("00000000: zz".as_bytes(), &mut out).is_err());
+        assert!(reverse("00000010: 61\n00000000: 62\n".as_bytes(), &mut out).is_err());
+    }
+}
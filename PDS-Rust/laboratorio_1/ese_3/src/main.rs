@@ -1,7 +1,14 @@
-use std::fs::{File};
-use std::io::{self, BufRead, BufReader, Read, Write};
+#![allow(clippy::ptr_arg, clippy::needless_borrow, clippy::needless_return, clippy::inherent_to_string)]
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom, Write};
 use std::time::SystemTime;
-use hex;
+
+pub mod checked;
+pub mod hexdump;
+
+use checked::{ArithError, Policy};
+use hexdump::HexDumpOptions;
 
 fn read_file_content(filename: &str) -> io::Result<String> {
     let mut file = File::open(filename)?;
@@ -10,13 +17,15 @@ fn read_file_content(filename: &str) -> io::Result<String> {
     Ok(contents)
 }
 
-fn read_file_content_2(filename: &str) -> io::Result<Vec<u8>> {
+// streaming reader over the bytes of the file from `start` on: the data is read in chunks by
+// whoever consumes it, the file is never loaded in memory
+fn read_file_content_2(filename: &str, start: u64) -> io::Result<BufReader<File>> {
     let mut f = File::open(filename)?;
-    let mut data = vec![];
-    f.read_to_end(&mut data)?;
-    Ok(data)
+    f.seek(SeekFrom::Start(start))?;
+    Ok(BufReader::new(f))
 }
 
+#[allow(dead_code)] // exercise, not called by main
 fn read_file_lines(filename: &str) -> io::Result<Vec<String>> {
     let file = File::open(filename)?;
     let reader = BufReader::new(file);
@@ -32,6 +41,7 @@ fn write_to_file(filename: &str, content: &str) -> io::Result<()> {
 
 // ---
 
+#[allow(dead_code)] // exercise, not called by main
 fn uno_a(input: &String){
     let mut f_content: String = String::new();
     match read_file_content(&input) {
@@ -49,20 +59,59 @@ fn uno_a(input: &String){
     }
 }
 
-fn uno_b(input: &String){
-    match read_file_content(&input) {
-        Ok(content) => {
-            println!("File content: {}", hex::encode(content));
-        },
-        Err(e) => panic!("Error reading file: {}", e),
+fn uno_b(input: &String, opts: &HexDumpOptions){
+    let stdout = io::stdout();
+    let result = read_file_content_2(&input, opts.start)
+        .and_then(|reader| hexdump::dump(reader, &mut stdout.lock(), opts));
+    if let Err(e) = result {
+        eprintln!("Error reading file: {}", e);
     }
+}
 
-    match read_file_content_2(&input) {
-        Ok(content) => {
-            print!("{}", hex::encode(content));
-        },
-        Err(e) => eprintln!("Error reading file: {}", e),
+// hexdump [-c cols] [-g group] [-s start] [-l length] [-u] [-a] file
+// hexdump -r dump [output]
+fn run_hexdump(args: &[String]) -> Result<(), String> {
+    let mut opts = HexDumpOptions::default();
+    let mut reverse = false;
+    let mut files: Vec<&String> = Vec::new();
+
+    let mut it = args.iter();
+    while let Some(arg) = it.next() {
+        let mut value = |name: &str| it.next().ok_or(format!("missing value for {}", name));
+        match arg.as_str() {
+            "-c" => opts.cols = parse_number(value("-c")?)? as usize,
+            "-g" => opts.group = parse_number(value("-g")?)? as usize,
+            "-s" => opts.start = parse_number(value("-s")?)?,
+            "-l" => opts.length = Some(parse_number(value("-l")?)?),
+            "-u" => opts.uppercase = true,
+            "-a" => opts.ascii = false,
+            "-r" => reverse = true,
+            _ => files.push(arg),
+        }
+    }
+
+    if reverse {
+        let input = files.first().ok_or("missing dump file")?;
+        let reader = BufReader::new(File::open(input).map_err(|e| e.to_string())?);
+        let result = match files.get(1) {
+            Some(output) => hexdump::reverse(reader, &mut File::create(output).map_err(|e| e.to_string())?),
+            None => hexdump::reverse(reader, &mut io::stdout().lock()),
+        };
+        return result.map(|_| ()).map_err(|e| e.to_string());
     }
+
+    let input = files.first().ok_or("missing input file")?;
+    uno_b(input, &opts);
+    Ok(())
+}
+
+// decimal or hexadecimal (0x...) number
+fn parse_number(s: &str) -> Result<u64, String> {
+    let parsed = match s.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => s.parse(),
+    };
+    parsed.map_err(|_| format!("invalid number: {}", s))
 }
 
 enum Error {
@@ -84,6 +133,7 @@ fn enum_handle_errors(err: Error) {
     }
 }
 
+#[allow(dead_code)] // exercise, not called by main
 fn due(){
     let simple_error = Error::Simple(SystemTime::now());
     let complex_error = Error::Complex(SystemTime::now(), String::from("Something went wrong"));
@@ -92,36 +142,19 @@ fn due(){
     enum_handle_errors(complex_error);
 }
 
-enum MulErr {Overflow, NegativeNumber}
-
-fn mul(a: i32, b: i32) -> Result<u32, MulErr> {
-
-    // checked_mul is used to safely perform the multiplication. If an overflow occurs, it returns None
-    match a.checked_mul(b) {
-        // Some optional element, two possible value: <T> and None
-        Some(mul) => {
-            if mul < 0 {
-                Err(MulErr::NegativeNumber)
-            } else {
-                Ok(mul as u32)
-            }
-        }
-        None => Err(MulErr::Overflow), // Overflow detected
-    }
-}
-
 pub fn tre(){
     let a = 2_i32.pow(30) - 1; // 2^31 - 1 ! overflow già qui
     let b = 5;
-    match mul(a, b){
+    // the product must also fit in an u32
+    match checked::mul(a, b, Policy::Error).and_then(|mul| checked::cast::<i32, u32>(mul, Policy::Error)) {
         Ok(mul) => {
-            println!("{} * {} = {}", 5, 5, mul);
+            println!("{} * {} = {}", a, b, mul);
         },
-        Err(MulErr::NegativeNumber) => {
+        Err(ArithError::Underflow) => {
             println!("Negative number");
         }
-        Err(MulErr::Overflow) => {
-            println!("Overflow");
+        Err(e) => {
+            println!("{}", e);
         }
     }
 }
@@ -162,10 +195,39 @@ impl Node {
 
 
 fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("hexdump") {
+        if let Err(e) = run_hexdump(&args[2..]) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
     let mut node = Node::new("nodo");
     node.size(10).count(5);
     println!("{}", node.to_string());
 
     node.grow().inc();
     println!("{}", node.to_string());
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dump_range_from_file() {
+        let path = std::env::temp_dir().join("ese_3_hexdump_range.bin");
+        let data: Vec<u8> = (0u8..=255).collect();
+        std::fs::write(&path, &data).unwrap();
+
+        let opts = HexDumpOptions { cols: 4, start: 0x41, length: Some(6), ..Default::default() };
+        let reader = read_file_content_2(path.to_str().unwrap(), opts.start).unwrap();
+        let mut out = Vec::new();
+        hexdump::dump(reader, &mut out, &opts).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(String::from_utf8(out).unwrap(), "00000041: 4142 4344  ABCD\n00000045: 4546       EF\n");
+    }
+}
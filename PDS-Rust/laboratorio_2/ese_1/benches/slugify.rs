@@ -0,0 +1,115 @@
+// Confronto tra lo slugify originale (Regex compilata ad ogni chiamata, tabelle Vec<char>
+// ricostruite per ogni carattere) e la versione con tabelle precalcolate.
+// Eseguire con: cargo bench
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use regex::Regex;
+use ese_1::my_functions::{slugify, slugify_all, slugify_cow, slugify_into};
+
+fn conv(c: char) -> char {
+    const SUBS_I : &str =
+        "àáâäæãåāăąçćčđďèéêëēėęěğǵḧîïíīįìıİłḿñńǹňôöòóœøōõőṕŕřßśšşșťțûüùúūǘůűųẃẍÿýžźż";
+    const SUBS_O: &str =
+        "aaaaaaaaaacccddeeeeeeeegghiiiiiiiilmnnnnoooooooooprrsssssttuuuuuuuuuwxyyzzz";
+
+    let subs_i: Vec<char> = SUBS_I.chars().collect();
+    let subs_o: Vec<char> = SUBS_O.chars().collect();
+
+    if let Some(index) = subs_i.iter().position(|&i| i == c) {
+        return subs_o[index];
+    }
+
+    let re = Regex::new(r"[a-z]").unwrap();
+    if re.is_match(&c.to_string()) { c } else { '-' }
+}
+
+fn original_slugify(s: &str) -> String {
+    if s.is_empty() {
+        return String::from("-");
+    }
+
+    let mut normalized = String::new();
+    for c in s.chars() {
+        normalized.push(conv(c.to_ascii_lowercase()));
+    }
+
+    let re = Regex::new(r"[a-z0-9]").unwrap();
+    let filtered: String = normalized.chars()
+        .map(|c| if re.is_match(&c.to_string()) { c } else { '-' })
+        .collect();
+
+    let mut previusly: char = filtered.chars().next().unwrap();
+    let mut res = String::from(previusly);
+    for c in filtered.chars().skip(1) {
+        if c == '-' && c == previusly {
+            continue;
+        }
+        res.push(c);
+        previusly = c;
+    }
+
+    if res.ends_with('-') {
+        res.pop();
+    }
+    if res.is_empty() {
+        return String::from("-");
+    }
+    res
+}
+
+fn titles(n: usize) -> Vec<String> {
+    let samples = [
+        "Hello World!", "Perché l'università è così cara?", "Größere Übungen für Anfänger",
+        "Crème brûlée: la ricetta originale", "10 things you didn't know about Rust",
+        "already-a-slug", "Ωmega & Щука", "  spazi   multipli  ",
+    ];
+    (0..n).map(|i| format!("{} {}", samples[i % samples.len()], i)).collect()
+}
+
+fn run<F: FnMut()>(name: &str, iterations: u32, mut f: F) -> Duration {
+    // warm up
+    f();
+    let start = Instant::now();
+    for _ in 0..iterations {
+        f();
+    }
+    let elapsed = start.elapsed() / iterations;
+    println!("{:<28} {:>12.3?} per batch", name, elapsed);
+    elapsed
+}
+
+fn main() {
+    let input = titles(2_000);
+    let iterations = 10;
+
+    println!("slugify of {} titles, average over {} runs", input.len(), iterations);
+
+    let original = run("original (regex + conv)", iterations, || {
+        for s in &input {
+            black_box(original_slugify(black_box(s)));
+        }
+    });
+    let new = run("slugify", iterations, || {
+        for s in &input {
+            black_box(slugify(black_box(s)));
+        }
+    });
+    run("slugify_into (reused buf)", iterations, || {
+        let mut buf = String::with_capacity(128);
+        for s in &input {
+            buf.clear();
+            slugify_into(black_box(s), &mut buf);
+            black_box(&buf);
+        }
+    });
+    run("slugify_cow", iterations, || {
+        for s in &input {
+            black_box(slugify_cow(black_box(s)));
+        }
+    });
+    run("slugify_all", iterations, || {
+        black_box(slugify_all(black_box(&input)));
+    });
+
+    println!("speedup over the original: {:.1}x", original.as_secs_f64() / new.as_secs_f64());
+}
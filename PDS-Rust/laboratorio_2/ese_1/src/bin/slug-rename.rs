@@ -0,0 +1,100 @@
+// Rinomina tutti i file e le directory di un albero con il loro slug.
+//
+// uso: slug-rename [--dry-run] [--max-depth N] [--manifest FILE] <directory>
+//      slug-rename --undo FILE [--dry-run]
+use std::env;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use ese_1::rename::{read_manifest, rename_tree, undo, write_manifest, RenameOptions};
+
+const USAGE: &str = "usage: slug-rename [--dry-run] [--max-depth N] [--manifest FILE] <directory>\n       slug-rename --undo FILE [--dry-run]";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let mut options = RenameOptions::default();
+    let mut manifest: Option<PathBuf> = None;
+    let mut undo_manifest: Option<PathBuf> = None;
+    let mut root: Option<PathBuf> = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--dry-run" | "-n" => options.dry_run = true,
+            "--max-depth" => match iter.next().and_then(|v| v.parse().ok()) {
+                Some(depth) => options.max_depth = Some(depth),
+                None => return usage_error("--max-depth requires a number"),
+            },
+            "--manifest" => match iter.next() {
+                Some(path) => manifest = Some(PathBuf::from(path)),
+                None => return usage_error("--manifest requires a file"),
+            },
+            "--undo" => match iter.next() {
+                Some(path) => undo_manifest = Some(PathBuf::from(path)),
+                None => return usage_error("--undo requires a file"),
+            },
+            "--help" | "-h" => {
+                println!("{}", USAGE);
+                return ExitCode::SUCCESS;
+            }
+            other if other.starts_with('-') => return usage_error(&format!("unknown option {}", other)),
+            other => root = Some(PathBuf::from(other)),
+        }
+    }
+
+    if let Some(path) = undo_manifest {
+        let renames = match read_manifest(&path) {
+            Ok(renames) => renames,
+            Err(e) => return error(&format!("cannot read manifest {}: {}", path.display(), e)),
+        };
+        for rename in renames.iter().rev() {
+            println!("{} -> {}", rename.to.display(), rename.from.display());
+        }
+        if let Err(e) = undo(&renames, options.dry_run) {
+            return error(&e.to_string());
+        }
+        return ExitCode::SUCCESS;
+    }
+
+    let root = match root {
+        Some(root) => root,
+        None => return usage_error("missing directory"),
+    };
+
+    // after a failure the renames already done are still printed and saved in the manifest,
+    // otherwise they could not be undone
+    let (renames, failure) = match rename_tree(&root, &options) {
+        Ok(renames) => (renames, None),
+        Err(e) => (e.done, Some(e.source)),
+    };
+    for rename in &renames {
+        println!("{} -> {}", rename.from.display(), rename.to.display());
+    }
+    if options.dry_run {
+        println!("dry run: {} entries would be renamed", renames.len());
+    }
+
+    if let Some(path) = manifest {
+        if options.dry_run {
+            println!("dry run: manifest {} not written", path.display());
+        } else if let Err(e) = write_manifest(&path, &renames) {
+            return error(&format!("cannot write manifest {}: {}", path.display(), e));
+        }
+    }
+
+    if let Some(e) = failure {
+        return error(&format!("error renaming {}: {} ({} entries renamed)", root.display(), e, renames.len()));
+    }
+
+    ExitCode::SUCCESS
+}
+
+fn usage_error(msg: &str) -> ExitCode {
+    eprintln!("{}\n{}", msg, USAGE);
+    ExitCode::from(2)
+}
+
+fn error(msg: &str) -> ExitCode {
+    eprintln!("{}", msg);
+    ExitCode::FAILURE
+}
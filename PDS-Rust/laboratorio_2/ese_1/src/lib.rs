@@ -1,3 +1,6 @@
+// the header below is a note for the reader, not the documentation of the tratto module
+#![allow(clippy::empty_line_after_doc_comments)]
+
 /// Rust Module and Import Best Practices
 ///
 /// Key Principles:
@@ -21,92 +24,166 @@
 /// - Missing dependency declarations
 
 pub mod tratto;
-use tratto::*;
+pub mod transliteration;
+pub mod options;
+pub mod registry;
+pub mod rename;
 
 pub mod my_functions {
-    use regex::Regex;
-
-    fn conv(c: char) -> char {
-        /// Converte char nell'equivalente non accentato
-        const SUBS_I : &str =
-            "àáâäæãåāăąçćčđďèéêëēėęěğǵḧîïíīįìıİłḿñńǹňôöòóœøōõőṕŕřßśšşșťțûüùúūǘůűųẃẍÿýžźż";
-        const SUBS_O: &str =
-            "aaaaaaaaaacccddeeeeeeeegghiiiiiiiilmnnnnoooooooooprrsssssttuuuuuuuuuwxyyzzz";
-
-        // make str indexable by transforming them into string
-        let subs_i: Vec<char> = SUBS_I.chars().collect();
-        let subs_o: Vec<char> = SUBS_O.chars().collect();
-
-        let mut index_j = subs_o.len();  // Initialize index_j with the length of subs_o
-        for (index, &i) in subs_i.iter().enumerate() {  // Iterate over characters in subs_i
-            if i == c {
-                index_j = index;  // Store the index when a match is found
-                break;
+    use std::borrow::Cow;
+    use std::sync::OnceLock;
+    use crate::options::SlugOptions;
+    use crate::transliteration::Transliterator;
+
+    /// Tabella costruita a tempo di compilazione per i caratteri ASCII: il carattere dello
+    /// slug (già minuscolo) oppure 0 se il carattere diventa un separatore
+    const ASCII_SLUG: [u8; 128] = ascii_table();
+
+    const fn ascii_table() -> [u8; 128] {
+        let mut table = [0u8; 128];
+        let mut i = 0;
+        while i < 128 {
+            let c = i as u8;
+            table[i] = if c.is_ascii_lowercase() || c.is_ascii_digit() {
+                c
+            } else if c.is_ascii_uppercase() {
+                c.to_ascii_lowercase()
+            } else {
+                0
+            };
+            i += 1;
+        }
+        table
+    }
+
+    /// Motore con le tabelle predefinite, costruito una sola volta al primo utilizzo
+    fn default_transliterator() -> &'static Transliterator {
+        static DEFAULT: OnceLock<Transliterator> = OnceLock::new();
+        DEFAULT.get_or_init(Transliterator::new)
+    }
+
+    /// Scrive lo slug direttamente nella stringa di destinazione: i separatori vengono
+    /// rimandati finché non arriva un carattere valido, così ne resta uno solo tra due
+    /// parole e nessuno in fondo (quello iniziale invece resta, come nella versione originale)
+    struct SlugWriter<'a> {
+        out: &'a mut String,
+        start: usize,
+        pending_separator: bool,
+    }
+
+    impl SlugWriter<'_> {
+        fn push_valid(&mut self, c: char) {
+            if self.pending_separator {
+                self.out.push('-');
+                self.pending_separator = false;
             }
+            self.out.push(c);
         }
 
-        if index_j < subs_o.len() {
-            // .chars().next() converts the string into an iterator of characters and retrieves the first character.
-            // .unwrap() is used to get the value, assuming the string is non-empty.
-            // If the string is empty, it will cause a panic.
-            return subs_o[index_j];
+        /// carattere già traslitterato: minuscolo se possibile, altrimenti separatore
+        fn push_plain(&mut self, c: char) {
+            if c.is_ascii() {
+                match ASCII_SLUG[c as usize] {
+                    0 => self.pending_separator = true,
+                    b => self.push_valid(b as char),
+                }
+                return;
+            }
+            for l in c.to_lowercase() {
+                if l.is_ascii_lowercase() || l.is_ascii_digit() {
+                    self.push_valid(l);
+                } else {
+                    self.pending_separator = true;
+                }
+            }
         }
 
+        fn push(&mut self, c: char, transliterator: &Transliterator) {
+            if c.is_ascii() && !transliterator.has_ascii_rules() {
+                self.push_plain(c);
+                return;
+            }
+            match transliterator.rule_ignore_case(c) {
+                Some(replacement) => replacement.chars().for_each(|r| self.push_plain(r)),
+                None => self.push_plain(c),
+            }
+        }
 
-        // redundant control requested by the exercise
-        // in fact, the code already handles it in the flow of the slugify function
-        let re = Regex::new(r"[a-z]").unwrap();
-        if re.is_match(&c.to_string()) {
-            return c
-        } else {
-            return '-'
+        fn finish(self) {
+            // check if res is empty
+            if self.out.len() == self.start {
+                self.out.push('-');
+            }
         }
     }
 
-
+    /// trasforma la stringa -> slugify, traslitterando con le tabelle predefinite
     pub fn slugify(s: &str) -> String {
-        /// trasforma la stringa -> slugify
+        slugify_with(s, default_transliterator())
+    }
 
-        // ensure to elaborate not empty str
-        if s.is_empty()  {
-            return String::from("-");
-        }
+    /// trasforma la stringa -> slugify, traslitterando con il motore indicato
+    /// (es. `Transliterator::for_language("de")` per avere `ä` -> `ae`)
+    pub fn slugify_with(s: &str, transliterator: &Transliterator) -> String {
+        let mut res = String::with_capacity(s.len());
+        slugify_into_with(s, &mut res, transliterator);
+        res
+    }
 
-        // removal of accented characters
-        let mut normilized = String::new();
+    /// aggiunge lo slug di `s` in coda a `out`: riutilizzando la stessa stringa non
+    /// viene fatta nessuna allocazione una volta raggiunta la capacità necessaria
+    pub fn slugify_into(s: &str, out: &mut String) {
+        slugify_into_with(s, out, default_transliterator())
+    }
+
+    pub fn slugify_into_with(s: &str, out: &mut String, transliterator: &Transliterator) {
+        let start = out.len();
+        let mut writer = SlugWriter { out, start, pending_separator: false };
         for c in s.chars() {
-            let tmp = c.to_ascii_lowercase();
-            normilized.push(conv(tmp));
+            writer.push(c, transliterator);
         }
+        writer.finish();
+    }
 
-        // regex filtering
-        let re = Regex::new(r"[a-z0-9]").unwrap();
-        let filtered: String = normilized.chars()
-            .map(|c| if re.is_match(&c.to_string()) { c } else { '-' })
-            .collect();
-
-        // only one '-' between two characters
-        let mut previusly: char = filtered.chars().next().unwrap();
-        let mut res = String::from(previusly);
-        for c in filtered.chars().skip(1) {
-            if c == '-' && c == previusly {
-                continue;
-            }
-            res.push(c);
-            previusly = c;
+    /// verifica, senza allocare, che la stringa sia già uno slug (cioè `slugify(s) == s`)
+    pub fn is_slug(s: &str) -> bool {
+        if s == "-" {
+            return true;
         }
+        let bytes = s.as_bytes();
+        !bytes.is_empty()
+            && bytes.iter().all(|&b| b == b'-' || b.is_ascii_lowercase() || b.is_ascii_digit())
+            && !bytes.windows(2).any(|w| w == b"--")
+            && !s.ends_with('-')
+    }
 
-
-        // ensure last character is not '-'
-        if res.ends_with('-') {
-            res.pop();
+    /// come `slugify`, ma se la stringa è già uno slug viene restituita senza copiarla
+    pub fn slugify_cow(s: &str) -> Cow<'_, str> {
+        if is_slug(s) {
+            Cow::Borrowed(s)
+        } else {
+            Cow::Owned(slugify(s))
         }
+    }
 
-        // check if res is empty
-        if res.is_empty()  {
-            return String::from("-");
+    /// slugify di una sequenza di stringhe, il motore di traslitterazione è cercato una volta sola
+    pub fn slugify_all<I, S>(inputs: I) -> Vec<String>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let transliterator = default_transliterator();
+        let inputs = inputs.into_iter();
+        let mut res = Vec::with_capacity(inputs.size_hint().0);
+        for s in inputs {
+            res.push(slugify_with(s.as_ref(), transliterator));
         }
-
         res
     }
-}
\ No newline at end of file
+
+    /// trasforma la stringa -> slugify secondo le opzioni indicate
+    pub fn slugify_with_options(s: &str, options: &SlugOptions) -> String {
+        let transliterator = options.engine().unwrap_or_else(|| default_transliterator());
+        options.build(&transliterator.transliterate(s))
+    }
+}
@@ -0,0 +1,209 @@
+use std::collections::HashSet;
+use crate::transliteration::Transliterator;
+
+/// Stop word inglesi più comuni, utilizzabili con `SlugOptions::stop_words`
+pub const STOP_WORDS_EN: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "by", "for", "from", "in", "is", "it",
+    "of", "on", "or", "the", "to", "with",
+];
+
+const DEFAULT_ALLOWED: &str = "abcdefghijklmnopqrstuvwxyz0123456789";
+
+/// Opzioni per la generazione degli slug, costruite con il pattern builder:
+///
+/// ```
+/// use ese_1::options::SlugOptions;
+/// let opts = SlugOptions::new().separator('_').max_length(20);
+/// ```
+///
+/// I valori predefiniti sono quelli di `slugify`: separatore `-`, tutto minuscolo,
+/// solo caratteri `[a-z0-9]`, nessun limite di lunghezza. L'unica differenza nel risultato è il
+/// separatore iniziale: `slugify(" hello")` restituisce `-hello`, con le opzioni si ottiene `hello`.
+#[derive(Debug, Clone)]
+pub struct SlugOptions {
+    separator: char,
+    max_length: Option<usize>,
+    stop_words: HashSet<String>,
+    preserve_case: bool,
+    allowed: HashSet<char>,
+    reserved: HashSet<String>,
+    reserved_suffix: String,
+    transliterator: Option<Transliterator>,
+}
+
+impl Default for SlugOptions {
+    fn default() -> Self {
+        SlugOptions::new()
+    }
+}
+
+impl SlugOptions {
+    pub fn new() -> SlugOptions {
+        SlugOptions {
+            separator: '-',
+            max_length: None,
+            stop_words: HashSet::new(),
+            preserve_case: false,
+            allowed: DEFAULT_ALLOWED.chars().collect(),
+            reserved: HashSet::new(),
+            reserved_suffix: String::from("1"),
+            transliterator: None,
+        }
+    }
+
+    /// Carattere usato per separare le parole
+    pub fn separator(mut self, separator: char) -> SlugOptions {
+        self.separator = separator;
+        self
+    }
+
+    /// Lunghezza massima in caratteri, lo slug viene troncato all'ultima parola intera
+    /// (una singola parola troppo lunga viene invece tagliata)
+    pub fn max_length(mut self, max_length: usize) -> SlugOptions {
+        self.max_length = Some(max_length.max(1));
+        self
+    }
+
+    /// Parole da eliminare dallo slug (il confronto ignora maiuscole e minuscole)
+    pub fn stop_words(mut self, words: &[&str]) -> SlugOptions {
+        self.stop_words.extend(words.iter().map(|w| w.to_lowercase()));
+        self
+    }
+
+    /// Mantiene le maiuscole invece di convertire tutto in minuscolo
+    pub fn preserve_case(mut self, preserve_case: bool) -> SlugOptions {
+        self.preserve_case = preserve_case;
+        self
+    }
+
+    /// Insieme dei caratteri ammessi nello slug (sostituisce `[a-z0-9]`), gli altri diventano
+    /// separatori. Con `preserve_case` sono ammesse anche le maiuscole delle lettere indicate.
+    pub fn allowed_chars(mut self, chars: &str) -> SlugOptions {
+        self.allowed = chars.chars().collect();
+        self
+    }
+
+    /// Parole riservate: uno slug uguale a una di queste riceve il suffisso
+    /// (es. `admin` -> `admin-1`). Il confronto è fatto parola per parola, quindi `admin panel`
+    /// riserva anche `admin_panel` quando il separatore è `_`
+    pub fn reserved_words(mut self, words: &[&str]) -> SlugOptions {
+        self.reserved.extend(words.iter().map(|w| w.to_lowercase()));
+        self
+    }
+
+    /// Suffisso aggiunto (dopo il separatore) agli slug riservati, il predefinito è `1`.
+    /// Se `max_length` non lascia spazio per slug e suffisso resta solo il suffisso, troncato
+    pub fn reserved_suffix(mut self, suffix: &str) -> SlugOptions {
+        self.reserved_suffix = suffix.to_string();
+        self
+    }
+
+    /// Motore di traslitterazione da usare al posto di quello predefinito
+    pub fn transliterator(mut self, transliterator: Transliterator) -> SlugOptions {
+        self.transliterator = Some(transliterator);
+        self
+    }
+
+    pub(crate) fn separator_char(&self) -> char {
+        self.separator
+    }
+
+    pub(crate) fn engine(&self) -> Option<&Transliterator> {
+        self.transliterator.as_ref()
+    }
+
+    fn is_allowed(&self, c: char) -> bool {
+        if c == self.separator {
+            return false;
+        }
+        self.allowed.contains(&c)
+            || (self.preserve_case && c.to_lowercase().all(|l| self.allowed.contains(&l)))
+    }
+
+    /// Applica le opzioni a un testo già traslitterato
+    pub(crate) fn build(&self, transliterated: &str) -> String {
+        let text = if self.preserve_case { transliterated.to_string() } else { transliterated.to_lowercase() };
+
+        // words are the runs of allowed characters
+        let words: Vec<&str> = text
+            .split(|c: char| !self.is_allowed(c))
+            .filter(|w| !w.is_empty())
+            .collect();
+
+        // if every word is a stop word the original words are kept, an empty slug is useless
+        let filtered: Vec<&str> = words.iter()
+            .copied()
+            .filter(|w| !self.stop_words.contains(&w.to_lowercase()))
+            .collect();
+        let words = if filtered.is_empty() { words } else { filtered };
+
+        if words.is_empty() {
+            return self.separator.to_string();
+        }
+
+        // truncate first, so that a slug cut down to a reserved word is caught as well
+        let mut slug = self.join_truncated(&words, self.max_length);
+
+        if self.is_reserved(&slug) {
+            let suffix_len = self.reserved_suffix.chars().count() + 1;
+            match self.max_length {
+                // no room left for the slug: only the suffix is kept, cut to the limit
+                Some(max) if max <= suffix_len => {
+                    return self.reserved_suffix.chars().take(max).collect();
+                }
+                // leave room for the suffix
+                Some(max) if slug.chars().count() + suffix_len > max => {
+                    slug = self.join_truncated(&words, Some(max - suffix_len));
+                }
+                _ => {}
+            }
+            slug.push(self.separator);
+            slug.push_str(&self.reserved_suffix);
+        }
+
+        slug
+    }
+
+    /// Slug troncato all'ultima parola intera in modo che, con `max_length`, restino `extra`
+    /// caratteri per un suffisso. Almeno un carattere dello slug resta comunque
+    pub(crate) fn truncate_for_suffix(&self, slug: &str, extra: usize) -> String {
+        match self.max_length {
+            Some(max) if slug.chars().count() + extra > max => {
+                let words: Vec<&str> = slug.split(self.separator).collect();
+                self.join_truncated(&words, Some(max.saturating_sub(extra).max(1)))
+            }
+            _ => slug.to_string(),
+        }
+    }
+
+    // reserved words are compared word by word, whatever separator they were written with
+    fn is_reserved(&self, slug: &str) -> bool {
+        let slug = slug.to_lowercase();
+        self.reserved.iter().any(|reserved| {
+            let words = reserved.split(|c: char| !self.is_allowed(c)).filter(|w| !w.is_empty());
+            words.eq(slug.split(self.separator))
+        })
+    }
+
+    fn join_truncated(&self, words: &[&str], max_length: Option<usize>) -> String {
+        let mut slug = String::new();
+        let mut len = 0;
+        for (i, word) in words.iter().enumerate() {
+            let word_len = word.chars().count();
+            let needed = if i == 0 { word_len } else { word_len + 1 };
+            if let Some(max) = max_length && len + needed > max {
+                if i == 0 {
+                    // the first word alone is too long: hard cut
+                    slug.extend(word.chars().take(max));
+                }
+                break;
+            }
+            if i > 0 {
+                slug.push(self.separator);
+            }
+            slug.push_str(word);
+            len += needed;
+        }
+        slug
+    }
+}
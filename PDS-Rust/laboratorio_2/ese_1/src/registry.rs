@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::sync::Mutex;
+use std::{error, fmt, io};
+
+use crate::my_functions::slugify_with_options;
+use crate::options::SlugOptions;
+
+/// Slug di base per le stringhe che non contengono nessun carattere valido: lo slug vuoto
+/// (`-`) con un suffisso diventerebbe `--2`, che non è uno slug
+const EMPTY_BASE: &str = "item";
+
+/// Errore nel caricamento di un registro salvato su file
+#[derive(Debug)]
+pub enum RegistryError {
+    Io(io::Error),
+    /// riga (a partire da 1) non valida
+    Parse { line: usize, content: String },
+}
+
+impl fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegistryError::Io(e) => write!(f, "cannot access slug registry: {}", e),
+            RegistryError::Parse { line, content } => write!(f, "invalid registry entry at line {}: '{}'", line, content),
+        }
+    }
+}
+
+impl error::Error for RegistryError {}
+
+impl From<io::Error> for RegistryError {
+    fn from(e: io::Error) -> Self {
+        RegistryError::Io(e)
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// slug -> stringa originale che lo ha richiesto
+    slugs: HashMap<String, String>,
+    /// slug di base -> primo suffisso da provare, evita di ripartire da 2 ad ogni collisione
+    next_suffix: HashMap<String, usize>,
+}
+
+/// Registro di slug univoci: se lo slug di una stringa è già assegnato viene aggiunto un
+/// suffisso numerico (`hello-world`, `hello-world-2`, `hello-world-3`, ...). Con `max_length`
+/// lo slug di base viene accorciato all'ultima parola intera per far posto al suffisso.
+///
+/// Lo stato è protetto da un `Mutex`, quindi il registro può essere condiviso tra thread
+/// con un `Arc<SlugRegistry>` e tutti i metodi richiedono solo `&self`.
+#[derive(Debug, Default)]
+pub struct SlugRegistry {
+    state: Mutex<State>,
+    options: SlugOptions,
+}
+
+impl SlugRegistry {
+    pub fn new() -> SlugRegistry {
+        SlugRegistry::with_options(SlugOptions::new())
+    }
+
+    pub fn with_options(options: SlugOptions) -> SlugRegistry {
+        SlugRegistry { state: Mutex::new(State::default()), options }
+    }
+
+    fn base(&self, original: &str) -> String {
+        let base = slugify_with_options(original, &self.options);
+        if base.chars().all(|c| c == self.options.separator_char()) {
+            return EMPTY_BASE.to_string();
+        }
+        base
+    }
+
+    /// Restituisce uno slug univoco per la stringa e lo riserva
+    pub fn register(&self, original: &str) -> String {
+        let base = self.base(original);
+        let mut state = self.state.lock().unwrap();
+
+        if !state.slugs.contains_key(&base) {
+            state.slugs.insert(base.clone(), original.to_string());
+            return base;
+        }
+
+        let mut n = state.next_suffix.get(&base).copied().unwrap_or(2);
+        let separator = self.options.separator_char();
+        loop {
+            // the suffix must fit in max_length as well: the base is cut to make room for it
+            let suffix = format!("{}{}", separator, n);
+            let candidate = self.options.truncate_for_suffix(&base, suffix.chars().count()) + &suffix;
+            if !state.slugs.contains_key(&candidate) {
+                state.slugs.insert(candidate.clone(), original.to_string());
+                state.next_suffix.insert(base, n + 1);
+                return candidate;
+            }
+            n += 1;
+        }
+    }
+
+    /// Libera lo slug restituendo la stringa originale che lo aveva richiesto
+    pub fn release(&self, slug: &str) -> Option<String> {
+        let mut state = self.state.lock().unwrap();
+        let original = state.slugs.remove(slug)?;
+        // the freed suffix may be reused, the search restarts from 2
+        let base = self.base(&original);
+        state.next_suffix.remove(&base);
+        Some(original)
+    }
+
+    /// Stringa originale che ha richiesto lo slug
+    pub fn lookup(&self, slug: &str) -> Option<String> {
+        self.state.lock().unwrap().slugs.get(slug).cloned()
+    }
+
+    pub fn contains(&self, slug: &str) -> bool {
+        self.state.lock().unwrap().slugs.contains_key(slug)
+    }
+
+    pub fn len(&self) -> usize {
+        self.state.lock().unwrap().slugs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Salva gli slug riservati, una riga per slug: `slug<TAB>originale`.
+    /// Tab, a capo e backslash dell'originale sono salvati come sequenze di escape.
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        let state = self.state.lock().unwrap();
+        let mut entries: Vec<(&String, &String)> = state.slugs.iter().collect();
+        entries.sort();
+
+        let mut out = BufWriter::new(File::create(path)?);
+        for (slug, original) in entries {
+            writeln!(out, "{}\t{}", slug, escape(original))?;
+        }
+        out.flush()
+    }
+
+    /// Carica un registro salvato con `save`, le opzioni sono usate per i nuovi slug
+    pub fn load(path: &str, options: SlugOptions) -> Result<SlugRegistry, RegistryError> {
+        let registry = SlugRegistry::with_options(options);
+        {
+            let mut state = registry.state.lock().unwrap();
+            let reader = BufReader::new(File::open(path)?);
+            for (i, line) in reader.lines().enumerate() {
+                let line = line?;
+                if line.is_empty() {
+                    continue;
+                }
+                let entry = line.split_once('\t').and_then(|(slug, original)| {
+                    Some((slug.to_string(), unescape(original)?))
+                });
+                match entry {
+                    Some((slug, original)) if !slug.is_empty() => {
+                        state.slugs.insert(slug, original);
+                    }
+                    _ => return Err(RegistryError::Parse { line: i + 1, content: line }),
+                }
+            }
+        }
+        Ok(registry)
+    }
+}
+
+fn escape(s: &str) -> String {
+    let mut res = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\\' => res.push_str("\\\\"),
+            '\t' => res.push_str("\\t"),
+            '\n' => res.push_str("\\n"),
+            '\r' => res.push_str("\\r"),
+            _ => res.push(c),
+        }
+    }
+    res
+}
+
+fn unescape(s: &str) -> Option<String> {
+    let mut res = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            res.push(c);
+            continue;
+        }
+        match chars.next()? {
+            '\\' => res.push('\\'),
+            't' => res.push('\t'),
+            'n' => res.push('\n'),
+            'r' => res.push('\r'),
+            _ => return None,
+        }
+    }
+    Some(res)
+}
@@ -0,0 +1,246 @@
+use std::collections::HashSet;
+use std::ffi::OsString;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::{error, fmt};
+
+use walkdir::WalkDir;
+
+use crate::tratto::MySlug;
+
+/// Opzioni della rinomina di un albero di directory
+#[derive(Debug, Clone, Default)]
+pub struct RenameOptions {
+    /// se vero le rinomine sono solo calcolate e restituite, il disco non viene toccato
+    pub dry_run: bool,
+    /// profondità massima (1 = solo il contenuto diretto della radice), None = nessun limite
+    pub max_depth: Option<usize>,
+}
+
+/// Una rinomina eseguita (o prevista, in dry-run)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rename {
+    pub from: PathBuf,
+    pub to: PathBuf,
+}
+
+/// Errore durante `rename_tree`: le rinomine eseguite prima dell'errore sono in `done`, così si
+/// possono ancora salvare nel manifest e annullare
+#[derive(Debug)]
+pub struct RenameError {
+    pub done: Vec<Rename>,
+    pub source: io::Error,
+}
+
+impl fmt::Display for RenameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({} entries renamed before the error)", self.source, self.done.len())
+    }
+}
+
+impl error::Error for RenameError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// Errore durante `undo`: ogni rinomina che non è stata annullata, con il suo errore
+#[derive(Debug)]
+pub struct UndoError {
+    pub failures: Vec<(Rename, io::Error)>,
+}
+
+impl fmt::Display for UndoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (rename, e)) in self.failures.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "cannot restore {}: {}", rename.from.display(), e)?;
+        }
+        Ok(())
+    }
+}
+
+impl error::Error for UndoError {}
+
+/// Rinomina ogni file e directory sotto `root` (esclusa la radice) con il suo slug.
+///
+/// Il contenuto di una directory è rinominato prima della directory stessa, così i percorsi
+/// raccolti all'inizio restano validi. In caso di collisione con un file esistente (o con una
+/// rinomina precedente della stessa esecuzione) viene aggiunto `-2`, `-3`, ... prima
+/// dell'estensione. Le rinomine sono restituite nell'ordine in cui sono state eseguite, anche
+/// quando una di esse fallisce (vedi `RenameError`).
+pub fn rename_tree(root: &Path, options: &RenameOptions) -> Result<Vec<Rename>, RenameError> {
+    let mut walker = WalkDir::new(root)
+        .min_depth(1)
+        .contents_first(true)
+        .sort_by_file_name();
+    if let Some(depth) = options.max_depth {
+        walker = walker.max_depth(depth);
+    }
+
+    // the whole tree is read before renaming anything: a directory that changes while it is
+    // being listed may return the same entry twice
+    let entries = walker
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| RenameError { done: Vec::new(), source: e.into() })?;
+
+    let mut claimed: HashSet<PathBuf> = HashSet::new();
+    let mut renames = Vec::new();
+    for entry in entries {
+        let name = entry.file_name();
+        let slug = name.to_slug();
+        if name.to_str() == Some(slug.as_str()) {
+            continue;
+        }
+
+        let parent = entry.path().parent().unwrap_or(root);
+        let to = free_name(parent, &slug, &claimed);
+        if !options.dry_run && let Err(e) = fs::rename(entry.path(), &to) {
+            return Err(RenameError { done: renames, source: e });
+        }
+        claimed.insert(to.clone());
+        renames.push(Rename { from: entry.path().to_path_buf(), to });
+    }
+
+    Ok(renames)
+}
+
+/// Primo nome libero nella directory: `slug`, `slug-2`, `slug-3`, ... (prima dell'estensione)
+fn free_name(parent: &Path, slug: &str, claimed: &HashSet<PathBuf>) -> PathBuf {
+    let (stem, extension) = match slug.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => (stem, Some(ext)),
+        _ => (slug, None),
+    };
+
+    let mut n = 1;
+    loop {
+        let name = match (n, extension) {
+            (1, _) => slug.to_string(),
+            (_, Some(ext)) => format!("{}-{}.{}", stem, n, ext),
+            (_, None) => format!("{}-{}", stem, n),
+        };
+        let candidate = parent.join(name);
+        if !claimed.contains(&candidate) && fs::symlink_metadata(&candidate).is_err() {
+            return candidate;
+        }
+        n += 1;
+    }
+}
+
+/// Salva le rinomine nel manifest, una per riga: `origine<TAB>destinazione`
+pub fn write_manifest(path: &Path, renames: &[Rename]) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    for rename in renames {
+        writeln!(out, "{}\t{}", encode_path(&rename.from), encode_path(&rename.to))?;
+    }
+    out.flush()
+}
+
+pub fn read_manifest(path: &Path) -> io::Result<Vec<Rename>> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut renames = Vec::new();
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.is_empty() {
+            continue;
+        }
+        let rename = line.split_once('\t').and_then(|(from, to)| {
+            Some(Rename { from: decode_path(from)?, to: decode_path(to)? })
+        });
+        match rename {
+            Some(rename) => renames.push(rename),
+            None => return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid manifest entry at line {}", i + 1),
+            )),
+        }
+    }
+    Ok(renames)
+}
+
+/// Annulla le rinomine del manifest, in ordine inverso. Un errore non interrompe l'operazione:
+/// vengono provate tutte le rinomine e gli errori sono restituiti insieme alla fine
+pub fn undo(renames: &[Rename], dry_run: bool) -> Result<(), UndoError> {
+    let mut failures = Vec::new();
+    for rename in renames.iter().rev() {
+        if fs::symlink_metadata(&rename.from).is_ok() {
+            let e = io::Error::new(io::ErrorKind::AlreadyExists, "path already exists");
+            failures.push((rename.clone(), e));
+            continue;
+        }
+        if !dry_run && let Err(e) = fs::rename(&rename.to, &rename.from) {
+            failures.push((rename.clone(), e));
+        }
+    }
+    if failures.is_empty() { Ok(()) } else { Err(UndoError { failures }) }
+}
+
+/// Percorso come testo su una riga: tab, a capo e backslash diventano sequenze di escape e,
+/// su Unix, i byte non UTF-8 sono scritti come `\xNN` così il nome originale non va perso
+fn encode_path(path: &Path) -> String {
+    let mut res = String::new();
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+        for chunk in path.as_os_str().as_bytes().utf8_chunks() {
+            escape_into(chunk.valid(), &mut res);
+            for b in chunk.invalid() {
+                res.push_str(&format!("\\x{:02x}", b));
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    escape_into(&path.to_string_lossy(), &mut res);
+    res
+}
+
+fn escape_into(s: &str, res: &mut String) {
+    for c in s.chars() {
+        match c {
+            '\\' => res.push_str("\\\\"),
+            '\t' => res.push_str("\\t"),
+            '\n' => res.push_str("\\n"),
+            '\r' => res.push_str("\\r"),
+            _ => res.push(c),
+        }
+    }
+}
+
+fn decode_path(s: &str) -> Option<PathBuf> {
+    let mut bytes: Vec<u8> = Vec::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            let mut buf = [0u8; 4];
+            bytes.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+            continue;
+        }
+        match chars.next()? {
+            '\\' => bytes.push(b'\\'),
+            't' => bytes.push(b'\t'),
+            'n' => bytes.push(b'\n'),
+            'r' => bytes.push(b'\r'),
+            'x' => {
+                let hex: String = chars.by_ref().take(2).collect();
+                bytes.push(u8::from_str_radix(&hex, 16).ok()?);
+            }
+            _ => return None,
+        }
+    }
+    Some(PathBuf::from(os_string_from_bytes(bytes)?))
+}
+
+#[cfg(unix)]
+fn os_string_from_bytes(bytes: Vec<u8>) -> Option<OsString> {
+    use std::os::unix::ffi::OsStringExt;
+    Some(OsString::from_vec(bytes))
+}
+
+#[cfg(not(unix))]
+fn os_string_from_bytes(bytes: Vec<u8>) -> Option<OsString> {
+    String::from_utf8(bytes).ok().map(OsString::from)
+}
@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+use std::{error, fmt, fs, io};
+
+/// Tabelle predefinite, nello stesso formato dei file caricabili dall'utente
+const LATIN: &str = include_str!("tables/latin.txt");
+const GREEK: &str = include_str!("tables/greek.txt");
+const CYRILLIC: &str = include_str!("tables/cyrillic.txt");
+
+/// Regole specifiche per lingua, applicate sopra le tabelle predefinite
+const GERMAN: &str = include_str!("tables/de.txt");
+const DANISH: &str = include_str!("tables/da.txt");
+
+/// Errore nel caricamento di una tabella di traslitterazione
+#[derive(Debug)]
+pub enum TableError {
+    Io(io::Error),
+    /// riga (a partire da 1) che non contiene una regola valida
+    Parse { line: usize, content: String },
+    UnknownLanguage(String),
+}
+
+impl fmt::Display for TableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TableError::Io(e) => write!(f, "cannot read transliteration table: {}", e),
+            TableError::Parse { line, content } => write!(f, "invalid rule at line {}: '{}'", line, content),
+            TableError::UnknownLanguage(lang) => write!(f, "no transliteration rules for language '{}'", lang),
+        }
+    }
+}
+
+impl error::Error for TableError {}
+
+impl From<io::Error> for TableError {
+    fn from(e: io::Error) -> Self {
+        TableError::Io(e)
+    }
+}
+
+/// Motore di traslitterazione guidato da tabelle: ogni carattere viene sostituito da una stringa
+/// (anche vuota o di più caratteri, es. `ß` -> `ss`, `щ` -> `shch`).
+///
+/// Le tabelle sono file di testo con una regola per riga: il carattere, uno spazio e la
+/// sostituzione. Le righe vuote e quelle che iniziano con `#` sono ignorate.
+/// Le regole aggiunte dopo sovrascrivono quelle precedenti, così le regole di una lingua
+/// (es. tedesco `ä` -> `ae`) hanno la precedenza su quelle generiche.
+#[derive(Debug, Clone)]
+pub struct Transliterator {
+    rules: HashMap<char, String>,
+    /// true se almeno una regola riguarda un carattere ASCII: finché è false i caratteri
+    /// ASCII possono essere copiati senza consultare la tabella
+    ascii_rules: bool,
+}
+
+impl Default for Transliterator {
+    fn default() -> Self {
+        Transliterator::new()
+    }
+}
+
+impl Transliterator {
+    /// Motore senza regole: ogni carattere resta invariato
+    pub fn empty() -> Transliterator {
+        Transliterator { rules: HashMap::new(), ascii_rules: false }
+    }
+
+    /// Motore con le tabelle predefinite: latino esteso, greco e cirillico. Le scritture CJK non
+    /// hanno una tabella (la lettura dipende dalla lingua): servono regole caricate dall'utente
+    pub fn new() -> Transliterator {
+        let mut t = Transliterator::empty();
+        for table in [LATIN, GREEK, CYRILLIC] {
+            // le tabelle predefinite sono verificate dai test, un errore qui è un bug
+            t.add_table(table).expect("invalid built-in transliteration table");
+        }
+        t
+    }
+
+    /// Motore con le tabelle predefinite più le regole della lingua indicata (codice ISO 639-1)
+    pub fn for_language(lang: &str) -> Result<Transliterator, TableError> {
+        let overlay = match lang.to_lowercase().as_str() {
+            "de" => GERMAN,
+            "da" | "no" | "nb" | "nn" => DANISH,
+            "en" | "it" | "fr" | "es" | "el" | "ru" | "uk" => "",
+            _ => return Err(TableError::UnknownLanguage(lang.to_string())),
+        };
+        let mut t = Transliterator::new();
+        t.add_table(overlay)?;
+        Ok(t)
+    }
+
+    /// Aggiunge una singola regola, sovrascrivendo quella eventualmente presente
+    pub fn with_rule(mut self, c: char, replacement: &str) -> Transliterator {
+        self.insert(c, replacement);
+        self
+    }
+
+    fn insert(&mut self, c: char, replacement: &str) {
+        self.ascii_rules |= c.is_ascii();
+        self.rules.insert(c, replacement.to_string());
+    }
+
+    /// Aggiunge le regole contenute nel file indicato
+    pub fn with_table_file(mut self, path: &str) -> Result<Transliterator, TableError> {
+        let content = fs::read_to_string(path)?;
+        self.add_table(&content)?;
+        Ok(self)
+    }
+
+    /// Aggiunge le regole di una tabella in formato testuale
+    pub fn add_table(&mut self, table: &str) -> Result<(), TableError> {
+        for (i, line) in table.lines().enumerate() {
+            let line = line.trim_end_matches('\r');
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut chars = line.chars();
+            let c = chars.next().unwrap();
+            let rest = chars.as_str();
+            // il carattere deve essere seguito da uno spazio (o dalla fine della riga se la
+            // sostituzione è vuota)
+            if !(rest.is_empty() || rest.starts_with(' ') || rest.starts_with('\t')) {
+                return Err(TableError::Parse { line: i + 1, content: line.to_string() });
+            }
+            self.insert(c, rest.trim());
+        }
+        Ok(())
+    }
+
+    /// Sostituzione prevista per il carattere, se esiste una regola
+    pub fn rule(&self, c: char) -> Option<&str> {
+        self.rules.get(&c).map(|s| s.as_str())
+    }
+
+    /// Regola per il carattere o, se manca, per la sua forma minuscola: la maiuscola della
+    /// sostituzione non interessa a chi converte poi tutto in minuscolo (es. gli slug)
+    pub(crate) fn rule_ignore_case(&self, c: char) -> Option<&str> {
+        if let Some(replacement) = self.rules.get(&c) {
+            return Some(replacement);
+        }
+        let mut lower = c.to_lowercase();
+        match (lower.next(), lower.next()) {
+            (Some(l), None) if l != c => self.rule(l),
+            _ => None,
+        }
+    }
+
+    pub fn has_ascii_rules(&self) -> bool {
+        self.ascii_rules
+    }
+
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Traslittera la stringa. Le maiuscole sono cercate anche nella forma minuscola e la
+    /// sostituzione mantiene l'iniziale maiuscola (`Щ` -> `Shch`); i caratteri senza regola
+    /// restano invariati.
+    pub fn transliterate(&self, s: &str) -> String {
+        let mut res = String::with_capacity(s.len());
+        self.transliterate_into(s, &mut res);
+        res
+    }
+
+    /// Come `transliterate`, ma aggiunge il risultato in coda a `res` senza allocare una nuova stringa
+    pub fn transliterate_into(&self, s: &str, res: &mut String) {
+        for c in s.chars() {
+            if c.is_ascii() && !self.ascii_rules {
+                res.push(c);
+                continue;
+            }
+
+            if let Some(replacement) = self.rules.get(&c) {
+                res.push_str(replacement);
+                continue;
+            }
+
+            let mut lower = c.to_lowercase();
+            match (lower.next(), lower.next()) {
+                (Some(l), None) if l != c => match self.rules.get(&l) {
+                    Some(replacement) => {
+                        let mut chars = replacement.chars();
+                        if let Some(first) = chars.next() {
+                            res.extend(first.to_uppercase());
+                            res.push_str(chars.as_str());
+                        }
+                    }
+                    None => res.push(c),
+                },
+                _ => res.push(c),
+            }
+        }
+    }
+}
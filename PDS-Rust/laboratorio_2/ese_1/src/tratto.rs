@@ -1,5 +1,7 @@
-use regex::Regex;
-use crate::my_functions::slugify;
+use std::ffi::OsStr;
+use std::path::{Component, Path, PathBuf};
+use crate::my_functions::{is_slug, slugify, slugify_with_options};
+use crate::options::SlugOptions;
 
 /// Tratto per la gestione degli slug
 pub trait MySlug {
@@ -14,9 +16,15 @@ pub trait MySlug {
     /// # Returns
     /// Una nuova stringa trasformata in formato slug
     fn to_slug(&self) -> String;
+
+    /// Verifica se la stringa corrente è già uno slug valido secondo le opzioni indicate
+    fn is_slug_with(&self, options: &SlugOptions) -> bool;
+
+    /// Converte la stringa corrente in uno slug secondo le opzioni indicate
+    fn to_slug_with(&self, options: &SlugOptions) -> String;
 }
 
-/// IMPLEMENTAZIONE SPECIFICA
+// IMPLEMENTAZIONE SPECIFICA
 /*
 /// Implementazione del tratto per il tipo String
 /// NOTA: tutte le stringe implementeranno di default questa funzione!
@@ -68,13 +76,107 @@ impl MySlug for &str {
 impl<T> MySlug for T
 where T: std::ops::Deref<Target = str> {
     fn is_slug(&self) -> bool {
+        is_slug(self.as_ref())
+    }
+
+    fn to_slug(&self) -> String {
+        slugify(self.as_ref())
+    }
+
+    fn is_slug_with(&self, options: &SlugOptions) -> bool {
         let input = self.as_ref();
-        let output = slugify(input);
+        input == slugify_with_options(input, options)
+    }
+
+    fn to_slug_with(&self, options: &SlugOptions) -> String {
+        slugify_with_options(self.as_ref(), options)
+    }
+}
 
-        input == output
+/// IMPLEMENTAZIONE PER NOMI DI FILE:
+/// Un `OsStr` è trattato come nome di file: viene trasformato in slug solo il nome, mentre
+/// l'estensione è mantenuta (in minuscolo) e il punto iniziale dei file nascosti è conservato,
+/// es. `My Photo.JPG` -> `my-photo.jpg`, `.My Config` -> `.my-config`.
+///
+/// I nomi non UTF-8 sono convertiti con `to_string_lossy`: i byte non validi diventano
+/// U+FFFD e quindi un separatore, come ogni altro carattere non traslitterabile.
+impl MySlug for OsStr {
+    fn is_slug(&self) -> bool {
+        self.to_str().is_some_and(|name| name == self.to_slug())
     }
 
     fn to_slug(&self) -> String {
-        slugify(self.as_ref())
+        file_name_slug(self, slugify)
+    }
+
+    fn is_slug_with(&self, options: &SlugOptions) -> bool {
+        self.to_str().is_some_and(|name| name == self.to_slug_with(options))
+    }
+
+    fn to_slug_with(&self, options: &SlugOptions) -> String {
+        file_name_slug(self, |s| slugify_with_options(s, options))
+    }
+}
+
+/// Un `Path` viene trasformato componente per componente (ogni componente come nome di file),
+/// radice, `.` e `..` restano invariati: `Foto Vacanze/Mare 1.PNG` -> `foto-vacanze/mare-1.png`
+impl MySlug for Path {
+    fn is_slug(&self) -> bool {
+        path_components_are(self, |name| name.is_slug())
+    }
+
+    fn to_slug(&self) -> String {
+        path_slug(self, |name| name.to_slug())
+    }
+
+    fn is_slug_with(&self, options: &SlugOptions) -> bool {
+        path_components_are(self, |name| name.is_slug_with(options))
+    }
+
+    fn to_slug_with(&self, options: &SlugOptions) -> String {
+        path_slug(self, |name| name.to_slug_with(options))
+    }
+}
+
+fn file_name_slug<F: Fn(&str) -> String>(name: &OsStr, slug: F) -> String {
+    let name = name.to_string_lossy();
+    let (prefix, rest) = match name.strip_prefix('.') {
+        Some(rest) if !rest.is_empty() => (".", rest),
+        _ => ("", name.as_ref()),
+    };
+
+    // it is an extension only if it is made of letters and digits ("Hello. World" has none)
+    let (stem, extension) = match rest.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() && !ext.is_empty()
+            && ext.chars().all(|c| c.is_alphanumeric()) => (stem, Some(ext)),
+        _ => (rest, None),
+    };
+
+    let mut res = format!("{}{}", prefix, slug(stem));
+    if let Some(ext) = extension {
+        let ext = slug(ext);
+        if ext != "-" {
+            res.push('.');
+            res.push_str(&ext);
+        }
     }
-}
\ No newline at end of file
+    res
+}
+
+fn path_slug<F: Fn(&OsStr) -> String>(path: &Path, slug: F) -> String {
+    let mut res = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(name) => res.push(slug(name)),
+            other => res.push(other.as_os_str()),
+        }
+    }
+    res.to_string_lossy().into_owned()
+}
+
+fn path_components_are<F: Fn(&OsStr) -> bool>(path: &Path, check: F) -> bool {
+    path.components().all(|component| match component {
+        Component::Normal(name) => check(name),
+        _ => true,
+    })
+}
@@ -0,0 +1,146 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::thread;
+use ese_1::options::SlugOptions;
+use ese_1::registry::{RegistryError, SlugRegistry};
+
+#[test]
+pub fn test_collisions_get_a_suffix() {
+    let registry = SlugRegistry::new();
+    assert_eq!(registry.register("Hello World!"), "hello-world");
+    assert_eq!(registry.register("hello world"), "hello-world-2");
+    assert_eq!(registry.register("HELLO  WORLD"), "hello-world-3");
+    assert_eq!(registry.register("something else"), "something-else");
+    assert_eq!(registry.len(), 4);
+}
+
+#[test]
+pub fn test_suffix_does_not_collide_with_existing_slug() {
+    let registry = SlugRegistry::new();
+    assert_eq!(registry.register("page 2"), "page-2");
+    assert_eq!(registry.register("page"), "page");
+    assert_eq!(registry.register("Page"), "page-3");
+}
+
+#[test]
+pub fn test_lookup_and_release() {
+    let registry = SlugRegistry::new();
+    let first = registry.register("Hello World!");
+    let second = registry.register("hello world");
+
+    assert_eq!(registry.lookup(&first).as_deref(), Some("Hello World!"));
+    assert_eq!(registry.lookup(&second).as_deref(), Some("hello world"));
+    assert_eq!(registry.lookup("missing"), None);
+
+    assert_eq!(registry.release(&second).as_deref(), Some("hello world"));
+    assert!(!registry.contains(&second));
+    assert_eq!(registry.release(&second), None);
+
+    // a released slug can be handed out again
+    assert_eq!(registry.register("Hello, world"), "hello-world-2");
+}
+
+#[test]
+pub fn test_uses_options_separator() {
+    let registry = SlugRegistry::with_options(SlugOptions::new().separator('_'));
+    assert_eq!(registry.register("a b"), "a_b");
+    assert_eq!(registry.register("a-b"), "a_b_2");
+}
+
+#[test]
+pub fn test_suffix_fits_in_max_length() {
+    let registry = SlugRegistry::with_options(SlugOptions::new().max_length(11));
+    assert_eq!(registry.register("hello world"), "hello-world");
+    // hello-world-2 would be 13 characters: the base is cut at the last whole word
+    assert_eq!(registry.register("Hello World"), "hello-2");
+    assert_eq!(registry.register("hello, world"), "hello-3");
+    assert_eq!(registry.register("hello"), "hello");
+    assert_eq!(registry.register("hello!"), "hello-4");
+
+    // a single long word is cut
+    let registry = SlugRegistry::with_options(SlugOptions::new().max_length(7));
+    assert_eq!(registry.register("abcdefghij"), "abcdefg");
+    for i in 2..=10 {
+        let slug = registry.register("abcdefghij");
+        assert!(slug.chars().count() <= 7, "{}", slug);
+        assert!(slug.ends_with(&format!("-{}", i)));
+    }
+    assert_eq!(registry.lookup("abcd-10").as_deref(), Some("abcdefghij"));
+}
+
+#[test]
+pub fn test_input_without_valid_chars() {
+    let registry = SlugRegistry::new();
+    assert_eq!(registry.register(""), "item");
+    assert_eq!(registry.register("!@#"), "item-2");
+    assert_eq!(registry.register("東京"), "item-3");
+    assert_eq!(registry.release("item-2").as_deref(), Some("!@#"));
+    assert_eq!(registry.register("???"), "item-2");
+
+    let registry = SlugRegistry::with_options(SlugOptions::new().separator('_'));
+    assert_eq!(registry.register("--"), "item");
+    assert_eq!(registry.register("__"), "item_2");
+}
+
+#[test]
+pub fn test_shared_between_threads() {
+    let registry = Arc::new(SlugRegistry::new());
+    let mut handles = vec![];
+    for _ in 0..8 {
+        let registry = Arc::clone(&registry);
+        handles.push(thread::spawn(move || {
+            (0..100)
+                .map(|i| registry.register(if i % 2 == 0 { "Same Title" } else { "same title!" }))
+                .collect::<Vec<_>>()
+        }));
+    }
+
+    let slugs: Vec<String> = handles.into_iter()
+        .flat_map(|h| h.join().unwrap())
+        .collect();
+    let unique: HashSet<&String> = slugs.iter().collect();
+
+    assert_eq!(slugs.len(), 800);
+    assert_eq!(unique.len(), 800);
+    assert!(registry.contains("same-title"));
+    assert!(registry.contains("same-title-800"));
+}
+
+#[test]
+pub fn test_save_and_load() {
+    let path = std::env::temp_dir().join("ese_1_registry_roundtrip.txt");
+    let path = path.to_str().unwrap();
+
+    let registry = SlugRegistry::new();
+    registry.register("Hello World!");
+    registry.register("Hello World");
+    let escaped = registry.register("tab\tnew line\nbackslash \\");
+    registry.save(path).unwrap();
+
+    let loaded = SlugRegistry::load(path, SlugOptions::new()).unwrap();
+    std::fs::remove_file(path).unwrap();
+
+    assert_eq!(loaded.len(), 3);
+    assert_eq!(loaded.lookup("hello-world").as_deref(), Some("Hello World!"));
+    assert_eq!(loaded.lookup("hello-world-2").as_deref(), Some("Hello World"));
+    assert_eq!(loaded.lookup(&escaped).as_deref(), Some("tab\tnew line\nbackslash \\"));
+    assert_eq!(loaded.register("hello world"), "hello-world-3");
+}
+
+#[test]
+pub fn test_load_errors() {
+    let path = std::env::temp_dir().join("ese_1_registry_invalid.txt");
+    std::fs::write(&path, "ok\tOk\nno-tab-here\n").unwrap();
+    let result = SlugRegistry::load(path.to_str().unwrap(), SlugOptions::new());
+    std::fs::remove_file(&path).unwrap();
+
+    match result {
+        Err(RegistryError::Parse { line, content }) => {
+            assert_eq!(line, 2);
+            assert_eq!(content, "no-tab-here");
+        }
+        _ => panic!("expected a parse error"),
+    }
+
+    assert!(matches!(SlugRegistry::load("/non/existing/registry.txt", SlugOptions::new()), Err(RegistryError::Io(_))));
+}
@@ -0,0 +1,186 @@
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+use ese_1::options::SlugOptions;
+use ese_1::rename::{read_manifest, rename_tree, undo, write_manifest, Rename, RenameOptions};
+use ese_1::tratto::MySlug;
+
+fn temp_tree(name: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("ese_1_rename_{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("Foto Vacanze/Estate 2024")).unwrap();
+    fs::write(root.join("Foto Vacanze/Mare 1.JPG"), "1").unwrap();
+    fs::write(root.join("Foto Vacanze/mare-1.jpg"), "2").unwrap();
+    fs::write(root.join("Foto Vacanze/Estate 2024/Spiaggia è bella.png"), "3").unwrap();
+    fs::write(root.join("già-slug.txt"), "4").unwrap();
+    fs::write(root.join("ok.txt"), "5").unwrap();
+    root
+}
+
+fn list(root: &Path) -> Vec<String> {
+    let mut res: Vec<String> = walk(root);
+    res.sort();
+    res
+}
+
+fn walk(dir: &Path) -> Vec<String> {
+    let mut res = vec![];
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        res.push(path.to_string_lossy().into_owned());
+        if path.is_dir() {
+            res.extend(walk(&path));
+        }
+    }
+    res
+}
+
+#[test]
+pub fn test_os_str_slug_keeps_extension() {
+    assert_eq!(OsStr::new("My Photo.JPG").to_slug(), "my-photo.jpg");
+    assert_eq!(OsStr::new("archive.tar.gz").to_slug(), "archive-tar.gz");
+    assert_eq!(OsStr::new(".My Config").to_slug(), ".my-config");
+    assert_eq!(OsStr::new("Hello. World").to_slug(), "hello-world");
+    assert_eq!(OsStr::new("Straße.txt").to_slug(), "strasse.txt");
+    assert!(OsStr::new("my-photo.jpg").is_slug());
+    assert!(!OsStr::new("My Photo.jpg").is_slug());
+
+    let opts = SlugOptions::new().separator('_');
+    assert_eq!(OsStr::new("My Photo.JPG").to_slug_with(&opts), "my_photo.jpg");
+}
+
+#[test]
+pub fn test_path_slug() {
+    assert_eq!(Path::new("Foto Vacanze/Mare 1.PNG").to_slug(), "foto-vacanze/mare-1.png");
+    assert_eq!(Path::new("/tmp/../Ciao Mondo").to_slug(), "/tmp/../ciao-mondo");
+    assert!(Path::new("/tmp/foto-vacanze/mare.png").is_slug());
+    assert!(!PathBuf::from("/tmp/Foto").is_slug());
+}
+
+#[cfg(unix)]
+#[test]
+pub fn test_non_utf8_name() {
+    use std::os::unix::ffi::OsStrExt;
+    let name = OsStr::from_bytes(b"caf\xe9 menu.txt");
+    assert_eq!(name.to_slug(), "caf-menu.txt");
+    assert!(!name.is_slug());
+}
+
+#[test]
+pub fn test_dry_run_does_not_touch_the_disk() {
+    let root = temp_tree("dry_run");
+    let before = list(&root);
+
+    let renames = rename_tree(&root, &RenameOptions { dry_run: true, max_depth: None }).unwrap();
+    assert_eq!(list(&root), before);
+
+    let targets: Vec<PathBuf> = renames.iter().map(|r| r.to.clone()).collect();
+    assert!(targets.contains(&root.join("Foto Vacanze/mare-1-2.jpg")));
+    assert!(targets.contains(&root.join("Foto Vacanze/Estate 2024/spiaggia-e-bella.png")));
+    assert!(targets.contains(&root.join("foto-vacanze")));
+    assert!(targets.contains(&root.join("gia-slug.txt")));
+    assert_eq!(renames.len(), 5);
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+pub fn test_rename_with_collisions_and_undo() {
+    let root = temp_tree("undo");
+    let before = list(&root);
+
+    let renames = rename_tree(&root, &RenameOptions::default()).unwrap();
+    let manifest = root.with_extension("manifest");
+    write_manifest(&manifest, &renames).unwrap();
+
+    // the existing slug wins, the renamed file gets a suffix
+    assert_eq!(fs::read_to_string(root.join("foto-vacanze/mare-1.jpg")).unwrap(), "2");
+    assert_eq!(fs::read_to_string(root.join("foto-vacanze/mare-1-2.jpg")).unwrap(), "1");
+    assert_eq!(fs::read_to_string(root.join("foto-vacanze/estate-2024/spiaggia-e-bella.png")).unwrap(), "3");
+    assert!(root.join("ok.txt").exists());
+
+    // a second run has nothing to do
+    assert!(rename_tree(&root, &RenameOptions::default()).unwrap().is_empty());
+
+    let loaded = read_manifest(&manifest).unwrap();
+    assert_eq!(loaded, renames);
+    undo(&loaded, false).unwrap();
+    assert_eq!(list(&root), before);
+
+    fs::remove_file(&manifest).unwrap();
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+pub fn test_failure_keeps_the_renames_already_done() {
+    let root = std::env::temp_dir().join(format!("ese_1_rename_failure_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+    fs::write(root.join("A b.txt"), "1").unwrap();
+    // 254 bytes, the slug "shchshch..." (508 bytes) is too long for the file system
+    let long = "щ".repeat(127);
+    fs::write(root.join(&long), "2").unwrap();
+
+    let e = rename_tree(&root, &RenameOptions::default()).unwrap_err();
+    assert_eq!(e.done, vec![Rename { from: root.join("A b.txt"), to: root.join("a-b.txt") }]);
+    assert!(root.join("a-b.txt").exists());
+
+    undo(&e.done, false).unwrap();
+    assert!(root.join("A b.txt").exists());
+    assert!(root.join(&long).exists());
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+pub fn test_undo_reports_every_failure() {
+    let root = temp_tree("undo_failures");
+    let renames = rename_tree(&root, &RenameOptions::default()).unwrap();
+
+    // two entries can no longer be restored: one target is gone, one source is taken again
+    fs::remove_file(root.join("gia-slug.txt")).unwrap();
+    fs::write(root.join("foto-vacanze/estate-2024/Spiaggia è bella.png"), "x").unwrap();
+
+    let e = undo(&renames, false).unwrap_err();
+    assert_eq!(e.failures.len(), 2);
+    assert_eq!(e.to_string().lines().count(), 2);
+    // the other entries were restored anyway
+    assert!(root.join("Foto Vacanze/Mare 1.JPG").exists());
+    assert!(root.join("Foto Vacanze/Estate 2024/spiaggia-e-bella.png").exists());
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+pub fn test_max_depth() {
+    let root = temp_tree("depth");
+    let renames = rename_tree(&root, &RenameOptions { dry_run: false, max_depth: Some(1) }).unwrap();
+
+    assert_eq!(renames.len(), 2);
+    assert!(root.join("foto-vacanze/Mare 1.JPG").exists());
+    assert!(root.join("gia-slug.txt").exists());
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+pub fn test_manifest_keeps_non_utf8_names() {
+    use std::os::unix::ffi::OsStrExt;
+    let root = std::env::temp_dir().join(format!("ese_1_rename_bytes_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+    let original = root.join(OsStr::from_bytes(b"caf\xe9\tmenu.txt"));
+    fs::write(&original, "x").unwrap();
+
+    let renames = rename_tree(&root, &RenameOptions::default()).unwrap();
+    assert_eq!(renames[0].to, root.join("caf-menu.txt"));
+
+    let manifest = root.with_extension("manifest");
+    write_manifest(&manifest, &renames).unwrap();
+    undo(&read_manifest(&manifest).unwrap(), false).unwrap();
+    assert!(original.exists());
+
+    fs::remove_file(&manifest).unwrap();
+    fs::remove_dir_all(&root).unwrap();
+}
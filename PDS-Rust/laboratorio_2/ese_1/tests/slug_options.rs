@@ -0,0 +1,108 @@
+use ese_1::my_functions::{slugify, slugify_with_options};
+use ese_1::options::{SlugOptions, STOP_WORDS_EN};
+use ese_1::tratto::MySlug;
+use ese_1::transliteration::Transliterator;
+
+#[test]
+pub fn test_default_options_match_slugify() {
+    let opts = SlugOptions::new();
+    for input in ["hello world", "Hello WORLD", "héllo wôrld", "hello    world", "hello world!!!", "Straße 42"] {
+        assert_eq!(slugify_with_options(input, &opts), slugify(input));
+    }
+    assert_eq!(slugify_with_options("", &opts), "-");
+    assert_eq!(slugify_with_options("!@#$", &opts), "-");
+
+    // the only difference: the options never keep a leading separator
+    assert_eq!(slugify_with_options(" hello", &opts), "hello");
+    assert_eq!(slugify(" hello"), "-hello");
+}
+
+#[test]
+pub fn test_separator() {
+    let opts = SlugOptions::new().separator('_');
+    assert_eq!("Hello big World".to_slug_with(&opts), "hello_big_world");
+    // the separator found in the input is a word boundary too
+    assert_eq!("snake_case-name".to_slug_with(&opts), "snake_case_name");
+}
+
+#[test]
+pub fn test_max_length_cuts_at_word_boundary() {
+    let opts = SlugOptions::new().max_length(12);
+    assert_eq!("the quick brown fox".to_slug_with(&opts), "the-quick");
+    assert_eq!("exactly twelve".to_slug_with(&SlugOptions::new().max_length(14)), "exactly-twelve");
+    // a single word longer than the limit is cut
+    assert_eq!("supercalifragilistic".to_slug_with(&SlugOptions::new().max_length(5)), "super");
+}
+
+#[test]
+pub fn test_stop_words() {
+    let opts = SlugOptions::new().stop_words(STOP_WORDS_EN);
+    assert_eq!("The Lord of the Rings".to_slug_with(&opts), "lord-rings");
+    // a title made only of stop words keeps them
+    assert_eq!("To be or not to be".to_slug_with(&SlugOptions::new().stop_words(&["to", "be", "or"])), "not");
+    assert_eq!("To be".to_slug_with(&opts), "to-be");
+}
+
+#[test]
+pub fn test_preserve_case() {
+    let opts = SlugOptions::new().preserve_case(true);
+    assert_eq!("Hello World".to_slug_with(&opts), "Hello-World");
+    assert_eq!("Ärger in München".to_slug_with(&opts), "Arger-in-Munchen");
+}
+
+#[test]
+pub fn test_allowed_chars() {
+    let opts = SlugOptions::new().allowed_chars("abcdefghijklmnopqrstuvwxyz0123456789.");
+    assert_eq!("Report v1.2 final".to_slug_with(&opts), "report-v1.2-final");
+
+    let letters_only = SlugOptions::new().allowed_chars("abcdefghijklmnopqrstuvwxyz");
+    assert_eq!("room 101 booking".to_slug_with(&letters_only), "room-booking");
+}
+
+#[test]
+pub fn test_reserved_words() {
+    let opts = SlugOptions::new().reserved_words(&["admin", "new"]);
+    assert_eq!("Admin".to_slug_with(&opts), "admin-1");
+    assert_eq!("admin panel".to_slug_with(&opts), "admin-panel");
+
+    let opts = SlugOptions::new().reserved_words(&["new"]).reserved_suffix("page").max_length(8);
+    assert_eq!("New".to_slug_with(&opts), "new-page");
+
+    // the slug is checked after the truncation, and the suffix still fits in the limit
+    let opts = SlugOptions::new().reserved_words(&["admin"]).max_length(7);
+    assert_eq!("admin panel".to_slug_with(&opts), "admin-1");
+    let opts = SlugOptions::new().reserved_words(&["admin"]).max_length(5);
+    assert_eq!("admin panel".to_slug_with(&opts), "adm-1");
+
+    // a limit smaller than the suffix keeps only the suffix
+    let opts = SlugOptions::new().reserved_words(&["new"]).reserved_suffix("page").max_length(3);
+    assert_eq!("New".to_slug_with(&opts), "pag");
+    let opts = SlugOptions::new().reserved_words(&["ok"]).max_length(2);
+    assert_eq!("OK".to_slug_with(&opts), "1");
+    assert_eq!("new".to_slug_with(&opts), "ne");
+
+    // the words are compared, not the separator they were written with
+    let opts = SlugOptions::new().separator('_').reserved_words(&["admin panel", "log-in"]);
+    assert_eq!("Admin Panel".to_slug_with(&opts), "admin_panel_1");
+    assert_eq!("log in".to_slug_with(&opts), "log_in_1");
+    assert_eq!("login".to_slug_with(&opts), "login");
+}
+
+#[test]
+pub fn test_transliterator_option() {
+    let opts = SlugOptions::new().transliterator(Transliterator::for_language("de").unwrap());
+    assert_eq!("Grüße aus Köln".to_slug_with(&opts), "gruesse-aus-koeln");
+}
+
+#[test]
+pub fn test_is_slug_with() {
+    let opts = SlugOptions::new().separator('_').reserved_words(&["admin"]);
+    assert!("hello_world".is_slug_with(&opts));
+    assert!(!"hello-world".is_slug_with(&opts));
+    assert!(!"admin".is_slug_with(&opts));
+    assert!("admin_1".is_slug_with(&opts));
+
+    let owned = String::from("Hello_World");
+    assert!(!owned.is_slug_with(&opts));
+    assert!(owned.is_slug_with(&SlugOptions::new().separator('_').preserve_case(true)));
+}
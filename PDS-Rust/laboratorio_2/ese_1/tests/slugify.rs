@@ -0,0 +1,90 @@
+use std::borrow::Cow;
+use ese_1::my_functions::{is_slug, slugify, slugify_all, slugify_cow, slugify_into, slugify_with};
+use ese_1::transliteration::Transliterator;
+
+const INPUTS: &[&str] = &[
+    "", "-", "--", "a", "-a", "a-", "!!a!!", "hello world", "Hello WORLD", "héllo wôrld",
+    "hello    world", "hello!@#$world", "!@#$%^&*", "hello world ", "hello world!!!",
+    "Straße", "ÆSIR", "Щука", "Ωmega", "東京 tower", "KELVIN \u{212A}", "İstanbul", "ΣΊΣΥΦΟΣ",
+    "tab\tand\nnewline", "123 456", "already-a-slug", "ﬁle ﬂow",
+];
+
+// the straightforward pipeline: transliterate, lowercase, filter, collapse
+fn reference(s: &str, transliterator: &Transliterator) -> String {
+    let normalized = transliterator.transliterate(s).to_lowercase();
+    let mut res = String::new();
+    for c in normalized.chars() {
+        let c = if c.is_ascii_lowercase() || c.is_ascii_digit() { c } else { '-' };
+        if c == '-' && res.ends_with('-') {
+            continue;
+        }
+        res.push(c);
+    }
+    if res.ends_with('-') {
+        res.pop();
+    }
+    if res.is_empty() { String::from("-") } else { res }
+}
+
+#[test]
+pub fn test_matches_reference_pipeline() {
+    let t = Transliterator::new();
+    for input in INPUTS {
+        assert_eq!(slugify(input), reference(input, &t), "input: {:?}", input);
+    }
+}
+
+#[test]
+pub fn test_matches_reference_with_ascii_rules() {
+    let t = Transliterator::new().with_rule('&', "and").with_rule('@', " at ");
+    for input in INPUTS.iter().chain(&["r&d", "me@home", "A&B"]) {
+        assert_eq!(slugify_with(input, &t), reference(input, &t), "input: {:?}", input);
+    }
+    assert_eq!(slugify_with("me@home & co", &t), "me-at-home-and-co");
+}
+
+#[test]
+pub fn test_is_slug_agrees_with_slugify() {
+    for input in INPUTS {
+        let slug = slugify(input);
+        assert!(is_slug(&slug), "slug: {:?}", slug);
+        assert_eq!(is_slug(input), slugify(input) == *input, "input: {:?}", input);
+    }
+}
+
+#[test]
+pub fn test_slugify_into_appends_and_reuses_buffer() {
+    let mut out = String::with_capacity(64);
+    slugify_into("Hello World", &mut out);
+    assert_eq!(out, "hello-world");
+
+    out.push('/');
+    slugify_into("!!!", &mut out);
+    assert_eq!(out, "hello-world/-");
+
+    let capacity = out.capacity();
+    out.clear();
+    slugify_into("Straße", &mut out);
+    assert_eq!(out, "strasse");
+    assert_eq!(out.capacity(), capacity);
+}
+
+#[test]
+pub fn test_slugify_cow() {
+    match slugify_cow("already-a-slug") {
+        Cow::Borrowed(s) => assert_eq!(s, "already-a-slug"),
+        Cow::Owned(_) => panic!("a slug must not be copied"),
+    }
+    match slugify_cow("Not A Slug") {
+        Cow::Owned(s) => assert_eq!(s, "not-a-slug"),
+        Cow::Borrowed(_) => panic!("expected a new string"),
+    }
+}
+
+#[test]
+pub fn test_slugify_all() {
+    let titles = vec![String::from("Hello World!"), String::from("Straße")];
+    assert_eq!(slugify_all(&titles), vec!["hello-world", "strasse"]);
+    assert_eq!(slugify_all(["a b", "c"].iter()), vec!["a-b", "c"]);
+    assert!(slugify_all(Vec::<&str>::new()).is_empty());
+}
@@ -0,0 +1,103 @@
+use std::fs;
+use ese_1::my_functions::{slugify, slugify_with};
+use ese_1::transliteration::{TableError, Transliterator};
+
+#[test]
+pub fn test_single_char_rules() {
+    let t = Transliterator::new();
+    assert_eq!(t.transliterate("àéüųẃ"), "aeuuw");
+    assert_eq!(t.transliterate("plain ascii 123"), "plain ascii 123");
+}
+
+#[test]
+pub fn test_multi_char_rules() {
+    let t = Transliterator::new();
+    assert_eq!(t.transliterate("ß"), "ss");
+    assert_eq!(t.transliterate("æ"), "ae");
+    assert_eq!(t.transliterate("щ"), "shch");
+    assert_eq!(t.transliterate("ﬁ"), "fi");
+}
+
+#[test]
+pub fn test_greek_and_cyrillic() {
+    let t = Transliterator::new();
+    assert_eq!(t.transliterate("Αθήνα"), "Athina");
+    assert_eq!(t.transliterate("ψυχή"), "psychi");
+    assert_eq!(t.transliterate("Москва"), "Moskva");
+    assert_eq!(t.transliterate("Щука"), "Shchuka");
+    // hard and soft signs have no transliteration
+    assert_eq!(t.transliterate("объём"), "obyom");
+}
+
+#[test]
+pub fn test_unknown_chars_are_kept() {
+    let t = Transliterator::new();
+    assert_eq!(t.transliterate("東京"), "東京");
+}
+
+#[test]
+pub fn test_language_rules() {
+    let default = Transliterator::new();
+    let german = Transliterator::for_language("de").unwrap();
+    assert_eq!(default.transliterate("Müller"), "Muller");
+    assert_eq!(german.transliterate("Müller"), "Mueller");
+    assert_eq!(german.transliterate("Ärger"), "Aerger");
+
+    let danish = Transliterator::for_language("da").unwrap();
+    assert_eq!(danish.transliterate("København"), "Koebenhavn");
+
+    assert!(matches!(Transliterator::for_language("xx"), Err(TableError::UnknownLanguage(_))));
+}
+
+#[test]
+pub fn test_user_table_from_file() {
+    let path = std::env::temp_dir().join("ese_1_transliteration_cjk.txt");
+    fs::write(&path, "# custom table\n東 dong\n京 jing\n\nß sz\n").unwrap();
+
+    let t = Transliterator::new().with_table_file(path.to_str().unwrap()).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(t.transliterate("東京"), "dongjing");
+    // user rules override the built-in ones
+    assert_eq!(t.transliterate("ß"), "sz");
+}
+
+#[test]
+pub fn test_invalid_table() {
+    let mut t = Transliterator::empty();
+    match t.add_table("# ok\nä ae\näae\n") {
+        Err(TableError::Parse { line, content }) => {
+            assert_eq!(line, 3);
+            assert_eq!(content, "äae");
+        }
+        _ => panic!("expected a parse error"),
+    }
+
+    let missing = Transliterator::new().with_table_file("/non/existing/table.txt");
+    assert!(matches!(missing, Err(TableError::Io(_))));
+}
+
+#[test]
+pub fn test_with_rule() {
+    let t = Transliterator::empty().with_rule('&', "and");
+    assert_eq!(t.len(), 1);
+    assert_eq!(t.rule('&'), Some("and"));
+    assert_eq!(t.transliterate("r&d"), "randd");
+}
+
+#[test]
+pub fn test_slugify_uses_engine() {
+    assert_eq!(slugify("héllo wôrld"), "hello-world");
+    assert_eq!(slugify("Straße"), "strasse");
+    assert_eq!(slugify("Ωmega Щука"), "omega-shchuka");
+    assert_eq!(slugify("ÉCOLE"), "ecole");
+    assert_eq!(slugify("東京"), "-");
+    assert_eq!(slugify("hello!@#$world"), "hello-world");
+    assert_eq!(slugify(""), "-");
+}
+
+#[test]
+pub fn test_slugify_with_language() {
+    let german = Transliterator::for_language("de").unwrap();
+    assert_eq!(slugify_with("Größe über alles", &german), "groesse-ueber-alles");
+}
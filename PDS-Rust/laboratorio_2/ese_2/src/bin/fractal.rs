@@ -0,0 +1,125 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+use ese_2::fractal::{self, Fractal, Palette, RenderConfig, Viewport};
+use ese_2::solution::ComplexNumber;
+
+#[derive(Parser)]
+#[command(name = "fractal", about = "Disegna l'insieme di Mandelbrot o un insieme di Julia in un file PPM/PGM")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Insieme di Mandelbrot: z <- z^2 + c con z che parte da 0 e c il punto del pixel
+    Mandelbrot {
+        #[command(flatten)]
+        options: RenderOptions,
+    },
+    /// Insieme di Julia: z <- z^2 + c con z che parte dal punto del pixel e c fisso
+    Julia {
+        /// Parametro c, es. -0.8+0.156i
+        #[arg(short, long, allow_hyphen_values = true, default_value = "-0.8+0.156i")]
+        c: ComplexNumber,
+        #[command(flatten)]
+        options: RenderOptions,
+    },
+}
+
+#[derive(Args)]
+struct RenderOptions {
+    /// Dimensione dell'immagine in pixel, larghezza x altezza
+    #[arg(long, value_name = "WxH", default_value = "800x600", value_parser = parse_size)]
+    size: (usize, usize),
+    /// Centro dell'immagine nel piano complesso (default: -0.5 per Mandelbrot, 0 per Julia)
+    #[arg(long, allow_hyphen_values = true)]
+    center: Option<ComplexNumber>,
+    /// Larghezza della porzione di piano visualizzata
+    #[arg(long, default_value_t = 3.5)]
+    view_width: f64,
+    /// Numero massimo di iterazioni per pixel
+    #[arg(short, long, default_value_t = 256)]
+    iterations: u32,
+    #[arg(long, value_enum, default_value_t = PaletteArg::Fire)]
+    palette: PaletteArg,
+    /// Lato delle tile in pixel
+    #[arg(long, default_value_t = 64)]
+    tile: usize,
+    /// Numero di thread (default: uno per core)
+    #[arg(long)]
+    threads: Option<usize>,
+    /// File di output: con estensione .pgm viene scritta un'immagine in scala di grigi
+    #[arg(short, long, default_value = "fractal.ppm")]
+    output: PathBuf,
+}
+
+#[derive(Copy, Clone, ValueEnum)]
+enum PaletteArg {
+    Grayscale,
+    Fire,
+    Ocean,
+    Rainbow,
+}
+
+impl From<PaletteArg> for Palette {
+    fn from(p: PaletteArg) -> Palette {
+        match p {
+            PaletteArg::Grayscale => Palette::Grayscale,
+            PaletteArg::Fire => Palette::Fire,
+            PaletteArg::Ocean => Palette::Ocean,
+            PaletteArg::Rainbow => Palette::Rainbow,
+        }
+    }
+}
+
+fn parse_size(s: &str) -> Result<(usize, usize), String> {
+    let invalid = || format!("Dimensione non valida: {} (atteso larghezza x altezza, es. 800x600)", s);
+    let (width, height) = s.split_once('x').ok_or_else(invalid)?;
+    let width: usize = width.trim().parse().map_err(|_| invalid())?;
+    let height: usize = height.trim().parse().map_err(|_| invalid())?;
+    if width == 0 || height == 0 {
+        return Err(invalid());
+    }
+    Ok((width, height))
+}
+
+fn run(cli: Cli) -> Result<(), String> {
+    let (fractal, options) = match cli.command {
+        Command::Mandelbrot { options } => (Fractal::Mandelbrot, options),
+        Command::Julia { c, options } => (Fractal::Julia(c), options),
+    };
+    if !(options.view_width > 0.0 && options.view_width.is_finite()) {
+        return Err(format!("view width must be a positive number, got {}", options.view_width));
+    }
+
+    let (width, height) = options.size;
+    let mut config = RenderConfig::new(fractal, width, height);
+    config.viewport = Viewport { center: options.center.unwrap_or(config.viewport.center), width: options.view_width };
+    config.max_iterations = options.iterations;
+    config.tile_size = options.tile;
+    if let Some(threads) = options.threads {
+        config.threads = threads;
+    }
+
+    let image = fractal::render(&config);
+    let grayscale = options.output.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("pgm"));
+    let bytes = if grayscale { image.to_pgm() } else { image.to_ppm(options.palette.into()) };
+    fs::write(&options.output, bytes).map_err(|e| format!("cannot write {}: {}", options.output.display(), e))?;
+    println!("{}x{} image written to {}", width, height, options.output.display());
+    Ok(())
+}
+
+pub fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,270 @@
+// TRASFORMATA DI FOURIER DISCRETA
+// X[k] = sum_j x[j] e^(-2 pi i jk / n)         (trasformata diretta)
+// x[j] = 1/n sum_k X[k] e^(2 pi i jk / n)      (inversa, già normalizzata: ifft(fft(x)) == x)
+//
+// radix2 è la FFT di Cooley-Tukey iterativa e richiede una lunghezza potenza di due; bluestein
+// riscrive la DFT di lunghezza qualunque come una convoluzione, calcolata con radix2 su una lunghezza
+// potenza di due. fft e ifft scelgono da sole l'algoritmo. Tutte le funzioni lavorano sul posto su
+// &mut [ComplexNumber<T>], per ComplexArray<N> ci sono i metodi fft e ifft.
+use std::{error, fmt};
+
+use num::Float;
+use num::traits::FloatConst;
+
+use crate::solution::{ComplexArray, ComplexNumber};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Direction {
+    Forward,
+    Inverse,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum FftError {
+    NotPowerOfTwo(usize),
+}
+
+impl fmt::Display for FftError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FftError::NotPowerOfTwo(n) => write!(f, "Radix-2 FFT needs a power of two length, got {}", n),
+        }
+    }
+}
+
+impl error::Error for FftError {}
+
+// e^(sign 2 pi i k / n) per k in 0..n/2, il segno è - per la diretta
+fn twiddles<T: Float + FloatConst>(n: usize, direction: Direction) -> Vec<ComplexNumber<T>> {
+    let step = sign::<T>(direction) * (T::PI() + T::PI()) / from_usize(n);
+    (0..n / 2).map(|k| ComplexNumber::from_polar(T::one(), step * from_usize(k))).collect()
+}
+
+fn sign<T: Float>(direction: Direction) -> T {
+    match direction {
+        Direction::Forward => -T::one(),
+        Direction::Inverse => T::one(),
+    }
+}
+
+fn from_usize<T: Float>(n: usize) -> T {
+    T::from(n).unwrap()
+}
+
+fn scale<T: Float>(data: &mut [ComplexNumber<T>]) {
+    let factor = T::one() / from_usize(data.len());
+    data.iter_mut().for_each(|z| *z *= factor);
+}
+
+// Cooley-Tukey: riordina gli elementi per indice a bit invertiti, poi combina a coppie blocchi di
+// lunghezza 2, 4, ..., n (butterfly). I fattori di rotazione sono calcolati una volta sola per la
+// lunghezza n: al passo len servono quelli con indice multiplo di n / len
+pub fn radix2<T: Float + FloatConst>(data: &mut [ComplexNumber<T>], direction: Direction) -> Result<(), FftError> {
+    let n = data.len();
+    if !n.is_power_of_two() {
+        return Err(FftError::NotPowerOfTwo(n));
+    }
+    if n == 1 {
+        return Ok(());
+    }
+
+    let bits = n.trailing_zeros();
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS - bits);
+        if i < j {
+            data.swap(i, j);
+        }
+    }
+
+    let w = twiddles::<T>(n, direction);
+    let mut len = 2;
+    while len <= n {
+        let half = len / 2;
+        let stride = n / len;
+        for block in data.chunks_exact_mut(len) {
+            let (lo, hi) = block.split_at_mut(half);
+            for (k, (a, b)) in lo.iter_mut().zip(hi.iter_mut()).enumerate() {
+                let t = *b * w[k * stride];
+                *b = *a - t;
+                *a += t;
+            }
+        }
+        len *= 2;
+    }
+
+    if direction == Direction::Inverse {
+        scale(data);
+    }
+    Ok(())
+}
+
+// Bluestein (chirp-z): con jk = (j^2 + k^2 - (k - j)^2) / 2 la DFT diventa
+//   X[k] = c[k] sum_j (x[j] c[j]) conj(c[k - j]),   c[k] = e^(-pi i k^2 / n)
+// cioè una convoluzione, che si calcola con tre FFT radix-2 di lunghezza m >= 2n - 1
+pub fn bluestein<T: Float + FloatConst>(data: &mut [ComplexNumber<T>], direction: Direction) {
+    let n = data.len();
+    if n <= 1 {
+        return;
+    }
+
+    // k^2 mod 2n: l'angolo resta piccolo e non perde precisione per k grandi
+    let chirp: Vec<ComplexNumber<T>> = (0..n)
+        .map(|k| {
+            let k2 = (k as u128 * k as u128 % (2 * n as u128)) as usize;
+            ComplexNumber::from_polar(T::one(), sign::<T>(direction) * T::PI() * from_usize(k2) / from_usize(n))
+        })
+        .collect();
+
+    let m = (2 * n - 1).next_power_of_two();
+    let mut a = vec![ComplexNumber::from_real(T::zero()); m];
+    for (a, (x, c)) in a.iter_mut().zip(data.iter().zip(&chirp)) {
+        *a = *x * *c;
+    }
+    // conj(c) con indici negativi, che nella convoluzione ciclica di lunghezza m stanno in fondo
+    let mut b = vec![ComplexNumber::from_real(T::zero()); m];
+    b[0] = chirp[0].conj();
+    for k in 1..n {
+        b[k] = chirp[k].conj();
+        b[m - k] = chirp[k].conj();
+    }
+
+    cyclic_convolve(&mut a, &mut b);
+    for (x, (y, c)) in data.iter_mut().zip(a.iter().zip(&chirp)) {
+        *x = *y * *c;
+    }
+
+    if direction == Direction::Inverse {
+        scale(data);
+    }
+}
+
+// convoluzione ciclica di due vettori della stessa lunghezza potenza di due, il risultato va in a
+fn cyclic_convolve<T: Float + FloatConst>(a: &mut [ComplexNumber<T>], b: &mut [ComplexNumber<T>]) {
+    radix2(a, Direction::Forward).unwrap();
+    radix2(b, Direction::Forward).unwrap();
+    for (x, y) in a.iter_mut().zip(b.iter()) {
+        *x *= *y;
+    }
+    radix2(a, Direction::Inverse).unwrap();
+}
+
+pub fn transform<T: Float + FloatConst>(data: &mut [ComplexNumber<T>], direction: Direction) {
+    if data.len().is_power_of_two() {
+        radix2(data, direction).unwrap();
+    } else {
+        bluestein(data, direction);
+    }
+}
+
+pub fn fft<T: Float + FloatConst>(data: &mut [ComplexNumber<T>]) {
+    transform(data, Direction::Forward);
+}
+
+pub fn ifft<T: Float + FloatConst>(data: &mut [ComplexNumber<T>]) {
+    transform(data, Direction::Inverse);
+}
+
+// DFT calcolata dalla definizione in O(n^2), come riferimento per i test
+pub fn dft<T: Float + FloatConst>(input: &[ComplexNumber<T>], direction: Direction) -> Vec<ComplexNumber<T>> {
+    let n = input.len();
+    let mut out: Vec<ComplexNumber<T>> = (0..n)
+        .map(|k| {
+            input.iter().enumerate().fold(ComplexNumber::from_real(T::zero()), |acc, (j, x)| {
+                let angle = sign::<T>(direction) * (T::PI() + T::PI()) * from_usize(j * k % n) / from_usize(n);
+                acc + *x * ComplexNumber::from_polar(T::one(), angle)
+            })
+        })
+        .collect();
+    if direction == Direction::Inverse && n > 0 {
+        scale(&mut out);
+    }
+    out
+}
+
+
+// SEGNALI REALI
+// La trasformata di un segnale reale è hermitiana, X[n - k] = conj(X[k]): bastano i primi n/2 + 1
+// valori. Per n pari i campioni vengono impacchettati in n/2 complessi z[j] = x[2j] + i x[2j + 1]
+// e si fa una FFT di metà lunghezza; le trasformate dei campioni pari e dispari si separano con
+//   E[k] = (Z[k] + conj(Z[n/2 - k])) / 2,   O[k] = (Z[k] - conj(Z[n/2 - k])) / 2i
+// e X[k] = E[k] + e^(-2 pi i k / n) O[k]. Per n dispari si usa la FFT complessa.
+pub fn rfft<T: Float + FloatConst>(input: &[T]) -> Vec<ComplexNumber<T>> {
+    let n = input.len();
+    if n == 0 {
+        return Vec::new();
+    }
+    if n % 2 == 1 {
+        let mut data: Vec<ComplexNumber<T>> = input.iter().map(|x| ComplexNumber::from_real(*x)).collect();
+        fft(&mut data);
+        data.truncate(n / 2 + 1);
+        return data;
+    }
+
+    let half = n / 2;
+    let mut z: Vec<ComplexNumber<T>> = input.chunks_exact(2).map(|p| ComplexNumber::new(p[0], p[1])).collect();
+    fft(&mut z);
+
+    let two = T::one() + T::one();
+    let step = -(T::PI() + T::PI()) / from_usize(n);
+    (0..=half)
+        .map(|k| {
+            let zk = z[k % half];
+            let zr = z[(half - k) % half].conj();
+            let even = (zk + zr) / two;
+            // (zk - zr) / 2i = -i (zk - zr) / 2
+            let d = zk - zr;
+            let odd = ComplexNumber::new(d.imag(), -d.real()) / two;
+            even + ComplexNumber::from_polar(T::one(), step * from_usize(k)) * odd
+        })
+        .collect()
+}
+
+// inversa di rfft: n è la lunghezza del segnale (da n/2 + 1 valori non si distingue n pari da dispari).
+// Lo spettro viene completato per simmetria e trasformato con la FFT complessa
+pub fn irfft<T: Float + FloatConst>(spectrum: &[ComplexNumber<T>], n: usize) -> Vec<T> {
+    assert_eq!(spectrum.len(), n / 2 + 1, "a real signal of length {} has {} spectrum values", n, n / 2 + 1);
+    let mut data: Vec<ComplexNumber<T>> = (0..n)
+        .map(|k| if k < spectrum.len() { spectrum[k] } else { spectrum[n - k].conj() })
+        .collect();
+    ifft(&mut data);
+    data.iter().map(|z| z.real()).collect()
+}
+
+
+// CONVOLUZIONE
+// Convoluzione lineare (a * b)[k] = sum_j a[j] b[k - j], lunga a.len() + b.len() - 1: le sequenze
+// vengono allungate con zeri alla prima potenza di due sufficiente, così la convoluzione ciclica
+// calcolata con la FFT coincide con quella lineare. O(m log m) invece di O(len(a) len(b))
+pub fn convolve<T: Float + FloatConst>(a: &[ComplexNumber<T>], b: &[ComplexNumber<T>]) -> Vec<ComplexNumber<T>> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let len = a.len() + b.len() - 1;
+    let m = len.next_power_of_two();
+    let pad = |v: &[ComplexNumber<T>]| {
+        let mut p = v.to_vec();
+        p.resize(m, ComplexNumber::from_real(T::zero()));
+        p
+    };
+    let (mut fa, mut fb) = (pad(a), pad(b));
+    cyclic_convolve(&mut fa, &mut fb);
+    fa.truncate(len);
+    fa
+}
+
+// convoluzione di segnali reali, es. un filtro FIR applicato a dei campioni
+pub fn convolve_real<T: Float + FloatConst>(a: &[T], b: &[T]) -> Vec<T> {
+    let lift = |v: &[T]| v.iter().map(|x| ComplexNumber::from_real(*x)).collect::<Vec<_>>();
+    convolve(&lift(a), &lift(b)).iter().map(|z| z.real()).collect()
+}
+
+
+impl<const N: usize, T: Float + FloatConst> ComplexArray<N, T> {
+    pub fn fft(&mut self) {
+        fft(&mut self.0);
+    }
+
+    pub fn ifft(&mut self) {
+        ifft(&mut self.0);
+    }
+}
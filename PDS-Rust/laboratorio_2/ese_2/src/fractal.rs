@@ -0,0 +1,252 @@
+// FRATTALI: MANDELBROT E JULIA
+// Per ogni pixel si itera z <- z^2 + c e si conta dopo quanti passi |z| supera il raggio di fuga.
+//   Mandelbrot: c è il punto del pixel, z parte da 0
+//   Julia:      c è fisso, z parte dal punto del pixel
+// Il conteggio intero produce bande di colore; la versione "smooth" aggiunge la parte frazionaria
+//   mu = n + 1 - log2(ln |z_n|)
+// che varia con continuità da un pixel all'altro.
+//
+// L'immagine viene divisa in tile quadrate, calcolate in parallelo da un pool di thread; ogni pixel
+// dipende solo dalle proprie coordinate, perciò il risultato non dipende da quanti thread ci sono né
+// dall'ordine in cui finiscono. L'output è PPM (P6, a colori) o PGM (P5, in scala di grigi): un
+// header testuale seguito dai byte dei pixel, senza bisogno di librerie per le immagini.
+use std::sync::{Arc, Mutex, mpsc};
+use std::thread::{self, JoinHandle};
+
+use crate::solution::ComplexNumber;
+
+// con un raggio grande la correzione smooth è più precisa
+const ESCAPE_RADIUS: f64 = 256.0;
+
+#[derive(Debug, Copy, Clone)]
+pub enum Fractal {
+    Mandelbrot,
+    Julia(ComplexNumber),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Palette {
+    Grayscale,
+    Fire,
+    Ocean,
+    Rainbow,
+}
+
+// porzione del piano complesso da disegnare: center è il centro dell'immagine, width la larghezza
+// in unità del piano; l'altezza segue dalle proporzioni dell'immagine (pixel quadrati)
+#[derive(Debug, Copy, Clone)]
+pub struct Viewport {
+    pub center: ComplexNumber,
+    pub width: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct RenderConfig {
+    pub fractal: Fractal,
+    pub viewport: Viewport,
+    pub width: usize,
+    pub height: usize,
+    pub max_iterations: u32,
+    pub tile_size: usize,
+    pub threads: usize,
+}
+
+impl RenderConfig {
+    // l'insieme di Mandelbrot intero, 1 thread per core
+    pub fn new(fractal: Fractal, width: usize, height: usize) -> RenderConfig {
+        let viewport = match fractal {
+            Fractal::Mandelbrot => Viewport { center: ComplexNumber::new(-0.5, 0.0), width: 3.5 },
+            Fractal::Julia(_) => Viewport { center: ComplexNumber::new(0.0, 0.0), width: 3.5 },
+        };
+        RenderConfig {
+            fractal,
+            viewport,
+            width,
+            height,
+            max_iterations: 256,
+            tile_size: 64,
+            threads: thread::available_parallelism().map_or(1, |n| n.get()),
+        }
+    }
+
+    // centro del pixel (x, y); le y crescono verso il basso, la parte immaginaria verso l'alto
+    pub fn point(&self, x: usize, y: usize) -> ComplexNumber {
+        let pixel = self.viewport.width / self.width as f64;
+        let re = (x as f64 + 0.5 - self.width as f64 / 2.0) * pixel;
+        let im = (self.height as f64 / 2.0 - y as f64 - 0.5) * pixel;
+        self.viewport.center + ComplexNumber::new(re, im)
+    }
+}
+
+// numero di iterazioni (smooth) prima della fuga, None se il punto non fugge entro max_iterations
+pub fn escape_time(mut z: ComplexNumber, c: ComplexNumber, max_iterations: u32) -> Option<f64> {
+    for n in 0..max_iterations {
+        if z.norm() > ESCAPE_RADIUS * ESCAPE_RADIUS {
+            let smooth = n as f64 + 1.0 - z.modulus().ln().log2();
+            return Some(smooth.max(0.0));
+        }
+        z = z * z + c;
+    }
+    None
+}
+
+
+// POOL DI THREAD
+// un canale per i lavori, condiviso dai worker dietro un Mutex perché il Receiver non si può clonare.
+// Quando il pool viene distrutto il Sender si chiude, i worker escono dal ciclo e vengono attesi
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+struct ThreadPool {
+    sender: Option<mpsc::Sender<Job>>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl ThreadPool {
+    fn new(threads: usize) -> ThreadPool {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let workers = (0..threads.max(1))
+            .map(|_| {
+                let receiver = Arc::clone(&receiver);
+                thread::spawn(move || loop {
+                    // il lock è rilasciato appena ricevuto il lavoro, prima di eseguirlo
+                    let job = receiver.lock().expect("mutex poisoned").recv();
+                    match job {
+                        Ok(job) => job(),
+                        Err(_) => break,
+                    }
+                })
+            })
+            .collect();
+        ThreadPool { sender: Some(sender), workers }
+    }
+
+    fn execute(&self, job: impl FnOnce() + Send + 'static) {
+        self.sender.as_ref().unwrap().send(Box::new(job)).expect("worker threads terminated");
+    }
+}
+
+impl Drop for ThreadPool {
+    fn drop(&mut self) {
+        drop(self.sender.take());
+        for worker in self.workers.drain(..) {
+            worker.join().expect("worker thread panicked");
+        }
+    }
+}
+
+
+// RENDERING
+// risultato grezzo: un valore per pixel, riga per riga, None per i punti dell'insieme
+#[derive(Debug, Clone, PartialEq)]
+pub struct EscapeMap {
+    pub width: usize,
+    pub height: usize,
+    pub max_iterations: u32,
+    pub values: Vec<Option<f64>>,
+}
+
+struct Tile {
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+}
+
+pub fn render(config: &RenderConfig) -> EscapeMap {
+    let tile_size = config.tile_size.max(1);
+    let mut tiles = Vec::new();
+    for y in (0..config.height).step_by(tile_size) {
+        for x in (0..config.width).step_by(tile_size) {
+            tiles.push(Tile { x, y, width: tile_size.min(config.width - x), height: tile_size.min(config.height - y) });
+        }
+    }
+
+    let (results, received) = mpsc::channel();
+    let pool = ThreadPool::new(config.threads.min(tiles.len()));
+    for tile in tiles {
+        let config = config.clone();
+        let results = results.clone();
+        pool.execute(move || {
+            let values = render_tile(&config, &tile);
+            results.send((tile, values)).expect("renderer stopped listening");
+        });
+    }
+    // restano solo i Sender dei lavori: il ciclo termina quando tutte le tile sono state inviate
+    drop(results);
+
+    let mut values = vec![None; config.width * config.height];
+    for (tile, tile_values) in received {
+        for (row, chunk) in tile_values.chunks_exact(tile.width).enumerate() {
+            let start = (tile.y + row) * config.width + tile.x;
+            values[start..start + tile.width].copy_from_slice(chunk);
+        }
+    }
+    EscapeMap { width: config.width, height: config.height, max_iterations: config.max_iterations, values }
+}
+
+fn render_tile(config: &RenderConfig, tile: &Tile) -> Vec<Option<f64>> {
+    let mut values = Vec::with_capacity(tile.width * tile.height);
+    for y in tile.y..tile.y + tile.height {
+        for x in tile.x..tile.x + tile.width {
+            let p = config.point(x, y);
+            values.push(match config.fractal {
+                Fractal::Mandelbrot => escape_time(ComplexNumber::default(), p, config.max_iterations),
+                Fractal::Julia(c) => escape_time(p, c, config.max_iterations),
+            });
+        }
+    }
+    values
+}
+
+
+// COLORI E FILE
+impl Palette {
+    // t in [0, 1], interpolazione lineare tra i colori di riferimento
+    pub fn color(&self, t: f64) -> [u8; 3] {
+        let stops: &[[f64; 3]] = match self {
+            Palette::Grayscale => &[[0.0, 0.0, 0.0], [255.0, 255.0, 255.0]],
+            Palette::Fire => &[[0.0, 0.0, 0.0], [180.0, 20.0, 0.0], [255.0, 140.0, 0.0], [255.0, 230.0, 80.0], [255.0, 255.0, 255.0]],
+            Palette::Ocean => &[[0.0, 0.0, 40.0], [0.0, 60.0, 160.0], [0.0, 180.0, 220.0], [240.0, 255.0, 255.0]],
+            Palette::Rainbow => &[
+                [255.0, 0.0, 0.0], [255.0, 255.0, 0.0], [0.0, 255.0, 0.0],
+                [0.0, 255.0, 255.0], [0.0, 0.0, 255.0], [255.0, 0.0, 255.0],
+            ],
+        };
+        let t = t.clamp(0.0, 1.0) * (stops.len() - 1) as f64;
+        let i = (t as usize).min(stops.len() - 2);
+        let f = t - i as f64;
+        let mix = |k: usize| (stops[i][k] + (stops[i + 1][k] - stops[i][k]) * f).round() as u8;
+        [mix(0), mix(1), mix(2)]
+    }
+}
+
+impl EscapeMap {
+    // scala logaritmica: i punti che fuggono subito occupano poco dell'intervallo, così si vedono
+    // i dettagli vicino al bordo dell'insieme
+    fn intensity(&self, value: f64) -> f64 {
+        (1.0 + value).ln() / (1.0 + self.max_iterations as f64).ln()
+    }
+
+    // i punti dell'insieme sono neri
+    pub fn to_ppm(&self, palette: Palette) -> Vec<u8> {
+        let mut out = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+        for value in &self.values {
+            out.extend(value.map_or([0, 0, 0], |v| palette.color(self.intensity(v))));
+        }
+        out
+    }
+
+    pub fn to_pgm(&self) -> Vec<u8> {
+        let mut out = format!("P5\n{} {}\n255\n", self.width, self.height).into_bytes();
+        for value in &self.values {
+            out.push(value.map_or(0, |v| (self.intensity(v) * 255.0).round() as u8));
+        }
+        out
+    }
+}
+
+// FNV-1a a 64 bit, per confrontare due immagini senza salvarle
+pub fn checksum(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, b| (hash ^ *b as u64).wrapping_mul(0x100000001b3))
+}
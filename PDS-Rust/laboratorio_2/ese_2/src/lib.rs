@@ -22,95 +22,403 @@
 /// - Missing dependency declarations
 */
 
+pub mod fft;
+pub mod fractal;
+pub mod linalg;
+pub mod polynomial;
+
 pub mod solution{
     use std::{error, fmt};
     use std::cmp::Ordering;
-    use std::ops::{Add, AddAssign};
+    use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
     use std::default::Default;
     use std::hash::Hasher;
     use std::hash::Hash;
+    use std::str::FromStr;
+
+    use num::traits::FloatConst;
+    use num::{Float, Num};
 
+    // Il tipo delle parti è generico: f64 (il default, ComplexNumber senza parametri è ComplexNumber<f64>),
+    // f32, oppure un intero per gli interi di Gauss (ComplexNumber<i64>). Somma, sottrazione e prodotto
+    // valgono per ogni T: Num; le funzioni che richiedono radici, logaritmi o funzioni trigonometriche
+    // (modulo, argomento, exp, sqrt, ...) esistono solo quando T: Float.
     #[derive(Debug, Copy, Clone, Default)] //used for {:?} debug; Copy Clone used for posses
-    pub struct ComplexNumber{
-        real:f64,
-        imag:f64,
+    pub struct ComplexNumber<T = f64>{
+        real:T,
+        imag:T,
     }
 
     /// Orphan rule: Rust, impedisce di implementare un trait esterno (Default) su un tipo
     /// esterno direttamente (e.g. un array [ComplexNumber; N]).
     /// Bisogna Usare un <! WRAPPER STRUCT !> e definire un nuovo tipo che incapsula l'array
     /// e implementare Default su di esso.
-    pub struct ComplexArray<const N: usize>(pub [ComplexNumber; N]);
-    impl<const N: usize> Default for ComplexArray<N> {
+    #[derive(Debug, Copy, Clone)]
+    pub struct ComplexArray<const N: usize, T = f64>(pub [ComplexNumber<T>; N]);
+    impl<const N: usize, T: Copy + Default> Default for ComplexArray<N, T> {
         fn default() -> Self {
             ComplexArray([ComplexNumber::default(); N])
         }
     }
-    impl fmt::Display for ComplexNumber{
+    // Stampa "a + bi" oppure "a - bi": il segno della parte immaginaria diventa l'operatore, così
+    // la stringa si può rileggere con parse(). Le opzioni del formato valgono per entrambe le parti:
+    //   {:.3}  precisione          -> 1.000 + 2.500i
+    //   {:+}   segno anche se +    -> +1 - 2i
+    //   {:#}   forma compatta      -> 1-2i
+    //   {:e}   notazione esponenziale (anche {:E}, {:.2e}, ...)
+    //   {:>12} larghezza e allineamento della stringa intera
+    impl<T: fmt::Display> fmt::Display for ComplexNumber<T>{
         fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result{
-            write!(f, "{} + {}i", self.real, self.imag)
+            self.fmt_parts(f, |x, precision| match precision {
+                Some(p) => format!("{:.*}", p, x),
+                None => format!("{}", x),
+            })
         }
     }
 
-    impl ComplexNumber{
-        pub fn new(real:f64, imag:f64) -> ComplexNumber{
+    impl<T: fmt::LowerExp> fmt::LowerExp for ComplexNumber<T>{
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result{
+            self.fmt_parts(f, |x, precision| match precision {
+                Some(p) => format!("{:.*e}", p, x),
+                None => format!("{:e}", x),
+            })
+        }
+    }
+
+    impl<T: fmt::UpperExp> fmt::UpperExp for ComplexNumber<T>{
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result{
+            self.fmt_parts(f, |x, precision| match precision {
+                Some(p) => format!("{:.*E}", p, x),
+                None => format!("{:E}", x),
+            })
+        }
+    }
+
+    impl<T> ComplexNumber<T>{
+        // part formatta una parte con la precisione richiesta; il segno viene tolto dalla stringa e
+        // rimesso da fmt_parts, così funziona per ogni tipo (anche -0.0 viene stampato come "-0")
+        fn fmt_parts(&self, f: &mut fmt::Formatter, part: fn(&T, Option<usize>) -> String) -> fmt::Result{
+            let real = part(&self.real, f.precision());
+            let imag = part(&self.imag, f.precision());
+            let (real_negative, real) = match real.strip_prefix('-') {
+                Some(abs) => (true, abs),
+                None => (false, real.as_str()),
+            };
+            let (imag_negative, imag) = match imag.strip_prefix('-') {
+                Some(abs) => (true, abs),
+                None => (false, imag.as_str()),
+            };
+            let sign = if real_negative { "-" } else if f.sign_plus() { "+" } else { "" };
+            let op = match (imag_negative, f.alternate()) {
+                (false, false) => " + ",
+                (true, false) => " - ",
+                (false, true) => "+",
+                (true, true) => "-",
+            };
+            let s = format!("{}{}{}{}i", sign, real, op, imag);
+
+            // f.pad non va bene: userebbe la precisione per troncare la stringa
+            let len = s.chars().count();
+            let width = f.width().unwrap_or(0);
+            if len >= width {
+                return f.write_str(&s);
+            }
+            let fill = |f: &mut fmt::Formatter, n: usize| -> fmt::Result {
+                (0..n).try_for_each(|_| write!(f, "{}", f.fill()))
+            };
+            let (before, after) = match f.align() {
+                Some(fmt::Alignment::Left) => (0, width - len),
+                Some(fmt::Alignment::Center) => ((width - len) / 2, width - len - (width - len) / 2),
+                _ => (width - len, 0),
+            };
+            fill(f, before)?;
+            f.write_str(&s)?;
+            fill(f, after)
+        }
+    }
+
+    impl<T: Num + Copy> ComplexNumber<T>{
+        pub fn new(real:T, imag:T) -> ComplexNumber<T>{
             ComplexNumber{
                 real, imag
             }
         }
 
-        pub fn from_real(real:f64) -> ComplexNumber{
+        pub fn from_real(real:T) -> ComplexNumber<T>{
             ComplexNumber{
-                real, imag:0.0
+                real, imag:T::zero()
             }
         }
 
-        pub fn to_tuple(&self) -> (f64, f64){
+        // unità immaginaria
+        pub fn i() -> ComplexNumber<T>{
+            ComplexNumber::new(T::zero(), T::one())
+        }
+
+        pub fn to_tuple(&self) -> (T, T){
             (self.real, self.imag)
         }
 
-        pub fn real(&self) -> f64{
+        pub fn real(&self) -> T{
             self.real
         }
 
-        pub fn imag(&self) -> f64{
+        pub fn imag(&self) -> T{
             self.imag
         }
 
-        // Computes the modulus of the complex number
-        fn modulus(&self) -> f64 {
-            self.real.hypot(self.imag) // Equivalent to sqrt(real^2 + imag^2)
+        // norma al quadrato, a^2 + b^2 (come std::norm in C++): evita la radice quando basta confrontare,
+        // ed è definita anche per gli interi
+        pub fn norm(&self) -> T {
+            self.real * self.real + self.imag * self.imag
         }
+    }
+
+    impl ComplexNumber{
+        // come costante esiste solo per f64: i trait di num non hanno costanti, per gli altri tipi c'è i()
+        pub const I: ComplexNumber = ComplexNumber { real: 0.0, imag: 1.0 };
+    }
+
+    impl<T: Num + Copy + Neg<Output = T>> ComplexNumber<T>{
+        // coniugato: a - bi
+        pub fn conj(&self) -> ComplexNumber<T> {
+            ComplexNumber::new(self.real, -self.imag)
+        }
+    }
 
-        pub fn as_ref(&self) -> &f64{
+    // riferimento alla parte reale: a.as_ref() restituisce &f64
+    impl<T> AsRef<T> for ComplexNumber<T> {
+        fn as_ref(&self) -> &T {
             &self.real
         }
+    }
 
-        pub fn as_mut(&mut self) -> &mut f64{
+    impl<T> AsMut<T> for ComplexNumber<T> {
+        fn as_mut(&mut self) -> &mut T {
             &mut self.real
         }
     }
 
 
-    /// IMPLEMENTAIONE STRUTTURA DATI HASH
-    impl Hash for ComplexNumber {
+    // FUNZIONI MATEMATICHE
+    // Le funzioni a più valori (arg, ln, sqrt, potenze, funzioni trigonometriche inverse) restituiscono
+    // il valore principale. I tagli (branch cut) seguono le convenzioni di C99 e di num-complex:
+    // sul taglio il segno dello zero della parte immaginaria sceglie il lato, es. sqrt(-4 + 0i) = 2i
+    // mentre sqrt(-4 - 0i) = -2i.
+    impl<T: Float> ComplexNumber<T> {
+        // Computes the modulus of the complex number
+        pub fn modulus(&self) -> T {
+            self.real.hypot(self.imag) // Equivalent to sqrt(real^2 + imag^2)
+        }
+
+        // argomento (angolo) in (-pi, pi]; il taglio è sul semiasse reale negativo
+        pub fn arg(&self) -> T {
+            self.imag.atan2(self.real)
+        }
+
+        pub fn from_polar(r: T, theta: T) -> ComplexNumber<T> {
+            ComplexNumber::new(r * theta.cos(), r * theta.sin())
+        }
+
+        // (modulo, argomento)
+        pub fn to_polar(&self) -> (T, T) {
+            (self.modulus(), self.arg())
+        }
+
+        // 1 / z
+        pub fn recip(&self) -> ComplexNumber<T> {
+            smith_div(ComplexNumber::from_real(T::one()), *self)
+        }
+
+        // moltiplicazione per i, senza passare dal prodotto (che sporcherebbe gli zeri con segno)
+        fn mul_i(self) -> ComplexNumber<T> {
+            ComplexNumber::new(-self.imag, self.real)
+        }
+
+        fn is_zero(&self) -> bool {
+            self.real.is_zero() && self.imag.is_zero()
+        }
+
+        // e^z = e^a (cos b + i sin b)
+        pub fn exp(&self) -> ComplexNumber<T> {
+            ComplexNumber::from_polar(self.real.exp(), self.imag)
+        }
+
+        // logaritmo principale: ln|z| + i arg(z)
+        pub fn ln(&self) -> ComplexNumber<T> {
+            ComplexNumber::new(self.modulus().ln(), self.arg())
+        }
+
+        // radice principale, parte reale >= 0. La formula evita la cancellazione che si avrebbe
+        // calcolando sqrt((|z| - a) / 2) quando a è positivo
+        pub fn sqrt(&self) -> ComplexNumber<T> {
+            if self.is_zero() {
+                return ComplexNumber::new(T::zero(), self.imag);
+            }
+            let two = T::one() + T::one();
+            let four = two + two;
+            // |re| + |z| va oltre il massimo quando le parti sono enormi: si somma un quarto dei valori
+            // e si moltiplica per sqrt(2), sqrt((|re| + |z|) / 2) = sqrt(2) * sqrt((|re| + |z|) / 4)
+            let limit = T::max_value() / four;
+            let t = if self.real.abs() > limit || self.imag.abs() > limit {
+                (self.real.abs() / four + (self.real / four).hypot(self.imag / four)).sqrt() * two.sqrt()
+            } else {
+                ((self.real.abs() + self.modulus()) / two).sqrt()
+            };
+            if self.real >= T::zero() {
+                ComplexNumber::new(t, self.imag / (two * t))
+            } else {
+                ComplexNumber::new(self.imag.abs() / (two * t), t.copysign(self.imag))
+            }
+        }
+
+        // z^p con esponente reale, in forma polare: |z|^p (cos p*arg + i sin p*arg)
+        pub fn powf(&self, exp: T) -> ComplexNumber<T> {
+            if self.is_zero() {
+                // come f64::powf: 0^0 = 1, 0 con p > 0, infinito con p < 0, NaN con p NaN
+                return ComplexNumber::from_real(T::zero().powf(exp));
+            }
+            let (r, theta) = self.to_polar();
+            ComplexNumber::from_polar(r.powf(exp), theta * exp)
+        }
+
+        // z^w = e^(w ln z)
+        pub fn powc(&self, exp: ComplexNumber<T>) -> ComplexNumber<T> {
+            if self.is_zero() {
+                // esponente reale: come powf. Altrimenti |0^w| = 0 solo con Re(w) > 0, negli altri
+                // casi il modulo è infinito o indefinito e la fase è indefinita
+                if exp.imag.is_zero() {
+                    return self.powf(exp.real);
+                }
+                return if exp.real > T::zero() { ComplexNumber::from_real(T::zero()) } else { ComplexNumber::new(T::nan(), T::nan()) };
+            }
+            (exp * self.ln()).exp()
+        }
+
+        // sin(a + bi) = sin a cosh b + i cos a sinh b
+        pub fn sin(&self) -> ComplexNumber<T> {
+            ComplexNumber::new(self.real.sin() * self.imag.cosh(), self.real.cos() * self.imag.sinh())
+        }
+
+        // cos(a + bi) = cos a cosh b - i sin a sinh b
+        pub fn cos(&self) -> ComplexNumber<T> {
+            ComplexNumber::new(self.real.cos() * self.imag.cosh(), -self.real.sin() * self.imag.sinh())
+        }
+
+        // tan(a + bi) = (sin 2a + i sinh 2b) / (cos 2a + cosh 2b)
+        pub fn tan(&self) -> ComplexNumber<T> {
+            let two = T::one() + T::one();
+            let (a, b) = (two * self.real, two * self.imag);
+            if b.abs() > T::from(40).unwrap() {
+                // sinh e cosh vanno a infinito, il rapporto tende a ±1
+                return ComplexNumber::new(T::zero(), T::one().copysign(b));
+            }
+            let d = a.cos() + b.cosh();
+            ComplexNumber::new(a.sin() / d, b.sinh() / d)
+        }
+
+        pub fn sinh(&self) -> ComplexNumber<T> {
+            ComplexNumber::new(self.real.sinh() * self.imag.cos(), self.real.cosh() * self.imag.sin())
+        }
+
+        pub fn cosh(&self) -> ComplexNumber<T> {
+            ComplexNumber::new(self.real.cosh() * self.imag.cos(), self.real.sinh() * self.imag.sin())
+        }
+
+        // tanh(z) = -i tan(iz)
+        pub fn tanh(&self) -> ComplexNumber<T> {
+            let t = self.mul_i().tan();
+            ComplexNumber::new(t.imag, -t.real)
+        }
+
+        // asin(z) = -i ln(iz + sqrt(1 - z^2)), tagli sul semiasse reale oltre -1 e oltre 1
+        pub fn asin(&self) -> ComplexNumber<T> {
+            let w = (self.mul_i() + real_sub(T::one(), *self * *self).sqrt()).ln();
+            ComplexNumber::new(w.imag, -w.real)
+        }
+
+        // acos(z) = pi/2 - asin(z), stessi tagli di asin
+        pub fn acos(&self) -> ComplexNumber<T> where T: FloatConst {
+            real_sub(T::FRAC_PI_2(), self.asin())
+        }
+
+        // atan(z) = i/2 (ln(1 - iz) - ln(1 + iz)), tagli sull'asse immaginario oltre -i e oltre i
+        pub fn atan(&self) -> ComplexNumber<T> {
+            let iz = self.mul_i();
+            let w = real_sub(T::one(), iz).ln() - (iz + T::one()).ln();
+            let two = T::one() + T::one();
+            ComplexNumber::new(-w.imag / two, w.real / two)
+        }
+    }
+
+    // r - z: il reale non ha parte immaginaria, il risultato è -imag e non 0 - imag, che perderebbe il
+    // segno dello zero (serve per i tagli di asin e atan)
+    fn real_sub<T: Num + Copy + Neg<Output = T>>(r: T, z: ComplexNumber<T>) -> ComplexNumber<T> {
+        ComplexNumber::new(r - z.real, -z.imag)
+    }
+
+    // Algoritmo di Smith: divide per la componente più grande del divisore, così c^2 + d^2 non va in
+    // overflow (o underflow) quando il risultato è rappresentabile
+    fn smith_div<T: Float>(lhs: ComplexNumber<T>, rhs: ComplexNumber<T>) -> ComplexNumber<T> {
+        let (a, b, c, d) = (lhs.real, lhs.imag, rhs.real, rhs.imag);
+        if c.abs() >= d.abs() {
+            let r = d / c;
+            let den = c + d * r;
+            ComplexNumber{real: (a + b * r) / den, imag: (b - a * r) / den}
+        } else {
+            let r = c / d;
+            let den = c * r + d;
+            ComplexNumber{real: (a * r + b) / den, imag: (b * r - a) / den}
+        }
+    }
+
+    // (a + bi) / (c + di) = (a + bi)(c - di) / (c^2 + d^2); per gli interi ogni parte è troncata come
+    // nella divisione intera (quoziente degli interi di Gauss arrotondato verso lo zero)
+    fn textbook_div<T: Num + Copy>(lhs: ComplexNumber<T>, rhs: ComplexNumber<T>) -> ComplexNumber<T> {
+        let den = rhs.norm();
+        ComplexNumber{
+            real: (lhs.real * rhs.real + lhs.imag * rhs.imag) / den,
+            imag: (lhs.imag * rhs.real - lhs.real * rhs.imag) / den,
+        }
+    }
+
+
+    // Operazioni che dipendono dal tipo delle parti e che num non fornisce in modo generico: uguaglianza
+    // e hash (f32 e f64 non implementano Eq e Hash), i confronti e la divisione (Smith per i float,
+    // formula diretta per gli interi). È implementato per i float e per gli interi con segno.
+    pub trait Scalar: Num + Copy + Neg<Output = Self> {
+        // uguaglianza riflessiva: per i float NaN == NaN (e 0.0 == -0.0 come in IEEE 754)
+        fn eq_part(&self, other: &Self) -> bool;
+        // deve essere coerente con eq_part: valori uguali danno lo stesso hash
+        fn hash_part<H: Hasher>(&self, state: &mut H);
+        // ordine totale delle parti (total_cmp per i float)
+        fn cmp_part(&self, other: &Self) -> Ordering;
+        fn cmp_modulus(a: &ComplexNumber<Self>, b: &ComplexNumber<Self>) -> Ordering;
+        fn div_complex(a: ComplexNumber<Self>, b: ComplexNumber<Self>) -> ComplexNumber<Self>;
+    }
+
+
+    // IMPLEMENTAIONE STRUTTURA DATI HASH
+    impl<T: Scalar> Hash for ComplexNumber<T> {
         // Implementation of the `Hash` trait for the `ComplexNumber` struct.
         // This ensures that the struct can be used in hashed collections like HashMap and HashSet.
-        // The `real` and `imag` fields, which are of type `f64`, are converted to raw bits
-        // using the `to_bits()` method before being fed into the hasher to calculate the hash.
+        // The `real` and `imag` fields are fed into the hasher through `Scalar::hash_part`: floats are
+        // converted to raw bits using the `to_bits()` method, integers are hashed directly.
+        // Hash must agree with PartialEq (a == b implies equal hashes), so -0.0 is hashed as 0.0 and
+        // every NaN as the same NaN.
 
         // In Rust, .hash(state) is a method call used within the implementation of the Hash trait.
         // It is used to combine the hash value of the current object with the existing state of the hasher.
         fn hash<H: Hasher>(&self, state: &mut H) {
-            self.real.to_bits().hash(state);
-            self.imag.to_bits().hash(state);
+            self.real.hash_part(state);
+            self.imag.hash_part(state);
         }
     }
 
 
-    /// IMPLEMENTAZIONE 'operator overloading' (sovraccarico degli operatori) come la somma
-    /// Questa tecnica si chiama syntactic sugar (zucchero sintattico). Serve per rendere il codice
-    /// più leggibile e pulito, permettendo di scrivere a + b invece di a.add(b).
+    // IMPLEMENTAZIONE 'operator overloading' (sovraccarico degli operatori) come la somma
+    // Questa tecnica si chiama syntactic sugar (zucchero sintattico). Serve per rendere il codice
+    // più leggibile e pulito, permettendo di scrivere a + b invece di a.add(b).
 
     /* Nota: 'Self' si riferisce a chi è il soggetto di 'for'
        perciò nelle funzioni si passa come parametro 'self' se il soggetto di 'for' va utilizzato
@@ -119,11 +427,11 @@ pub mod solution{
         Add ha come soggetto ComplexNumber e necessita di lui perciò lo passiamo come parametro insieme
         al secondo valore della somma che però va esplicitato, un solo self.
     **/
-    impl Add for ComplexNumber{
+    impl<T: Num + Copy> Add for ComplexNumber<T>{
         // Addizione con lo stesso tipo (ComplexNumber + ComplexNumber)
-        type Output = ComplexNumber;
-        fn add(self, rhs: ComplexNumber) -> Self::Output {
-            return ComplexNumber{real: self.real + rhs.real, imag: self.imag + rhs.imag}
+        type Output = ComplexNumber<T>;
+        fn add(self, rhs: ComplexNumber<T>) -> Self::Output {
+            ComplexNumber{real: self.real + rhs.real, imag: self.imag + rhs.imag}
         }
     }
 
@@ -131,80 +439,322 @@ pub mod solution{
     // Add<tipo di b> mentre for indica la tipologia di a
     // se Add e basta allora si indica self e dipende da cos'è b
 
-    impl Add<f64> for ComplexNumber{
+    impl<T: Num + Copy> Add<T> for ComplexNumber<T>{
         // Addizione con f64 (ComplexNumber + f64)
-        type Output = ComplexNumber;
-        fn add(self, rhs: f64) -> Self::Output {
-            return ComplexNumber{real: self.real + rhs, imag: self.imag}
+        type Output = ComplexNumber<T>;
+        fn add(self, rhs: T) -> Self::Output {
+            ComplexNumber{real: self.real + rhs, imag: self.imag}
+        }
+    }
+
+    impl<T: Num + Copy> Sub for ComplexNumber<T>{
+        type Output = ComplexNumber<T>;
+        fn sub(self, rhs: ComplexNumber<T>) -> Self::Output {
+            ComplexNumber{real: self.real - rhs.real, imag: self.imag - rhs.imag}
         }
     }
 
-    impl AddAssign for ComplexNumber{
-        // Auto incremento di self con ComplexNumber(ComplexNumber += ComplexNumber)
-        fn add_assign(&mut self, rhs: ComplexNumber) {
-            self.real += rhs.real;
-            self.imag += rhs.imag;
+    impl<T: Num + Copy> Sub<T> for ComplexNumber<T>{
+        type Output = ComplexNumber<T>;
+        fn sub(self, rhs: T) -> Self::Output {
+            ComplexNumber{real: self.real - rhs, imag: self.imag}
         }
     }
 
-    impl Add<&ComplexNumber> for ComplexNumber{
-        // Addizione con &ComplexNumber come riferimento (ComplexNumber + &ComplexNumber)
-        type Output = ComplexNumber;
-        fn add(self, rhs: &ComplexNumber) -> Self::Output {
-            ComplexNumber {
-                real: self.real + rhs.real,
-                imag: self.imag + rhs.imag
+    // (a + bi)(c + di) = (ac - bd) + (ad + bc)i
+    impl<T: Num + Copy> Mul for ComplexNumber<T>{
+        type Output = ComplexNumber<T>;
+        fn mul(self, rhs: ComplexNumber<T>) -> Self::Output {
+            ComplexNumber{
+                real: self.real * rhs.real - self.imag * rhs.imag,
+                imag: self.real * rhs.imag + self.imag * rhs.real,
             }
         }
     }
 
-    impl Add<&ComplexNumber> for &ComplexNumber{
-        // Addizione tra &ComplexNumber come riferimento (&ComplexNumber + &ComplexNumber)
-        type Output = ComplexNumber;
-        fn add(self, rhs: &ComplexNumber) -> Self::Output {
-            ComplexNumber {
-                real: self.real + rhs.real,
-                imag: self.imag + rhs.imag
+    impl<T: Num + Copy> Mul<T> for ComplexNumber<T>{
+        type Output = ComplexNumber<T>;
+        fn mul(self, rhs: T) -> Self::Output {
+            ComplexNumber{real: self.real * rhs, imag: self.imag * rhs}
+        }
+    }
+
+    impl<T: Scalar> Div for ComplexNumber<T>{
+        type Output = ComplexNumber<T>;
+        fn div(self, rhs: ComplexNumber<T>) -> Self::Output {
+            T::div_complex(self, rhs)
+        }
+    }
+
+    impl<T: Num + Copy> Div<T> for ComplexNumber<T>{
+        type Output = ComplexNumber<T>;
+        fn div(self, rhs: T) -> Self::Output {
+            ComplexNumber{real: self.real / rhs, imag: self.imag / rhs}
+        }
+    }
+
+    impl<T: Num + Copy + Neg<Output = T>> Neg for ComplexNumber<T>{
+        type Output = ComplexNumber<T>;
+        fn neg(self) -> Self::Output {
+            ComplexNumber{real: -self.real, imag: -self.imag}
+        }
+    }
+
+    impl<T: Num + Copy + Neg<Output = T>> Neg for &ComplexNumber<T>{
+        type Output = ComplexNumber<T>;
+        fn neg(self) -> Self::Output {
+            -*self
+        }
+    }
+
+    // I riferimenti sono tipi diversi per Rust: ogni combinazione (ComplexNumber + &ComplexNumber,
+    // &ComplexNumber + &ComplexNumber, &ComplexNumber + f64, ...) e le versioni con assegnamento (+=)
+    // vanno implementate. Essendo ComplexNumber Copy basta dereferenziare e richiamare le implementazioni
+    // per valore qui sopra; la macro le genera per tutte e quattro le operazioni.
+    macro_rules! forward_ops {
+        ($Op:ident, $op:ident, $OpAssign:ident, $op_assign:ident, $($bound:tt)+) => {
+            impl<T: $($bound)+> $Op<&ComplexNumber<T>> for ComplexNumber<T> {
+                type Output = ComplexNumber<T>;
+                fn $op(self, rhs: &ComplexNumber<T>) -> ComplexNumber<T> {
+                    $Op::$op(self, *rhs)
+                }
+            }
+
+            impl<T: $($bound)+> $Op<ComplexNumber<T>> for &ComplexNumber<T> {
+                type Output = ComplexNumber<T>;
+                fn $op(self, rhs: ComplexNumber<T>) -> ComplexNumber<T> {
+                    $Op::$op(*self, rhs)
+                }
+            }
+
+            impl<T: $($bound)+> $Op<&ComplexNumber<T>> for &ComplexNumber<T> {
+                type Output = ComplexNumber<T>;
+                fn $op(self, rhs: &ComplexNumber<T>) -> ComplexNumber<T> {
+                    $Op::$op(*self, *rhs)
+                }
+            }
+
+            impl<T: $($bound)+> $Op<T> for &ComplexNumber<T> {
+                type Output = ComplexNumber<T>;
+                fn $op(self, rhs: T) -> ComplexNumber<T> {
+                    $Op::$op(*self, rhs)
+                }
+            }
+
+            impl<T: $($bound)+> $Op<&T> for ComplexNumber<T> {
+                type Output = ComplexNumber<T>;
+                fn $op(self, rhs: &T) -> ComplexNumber<T> {
+                    $Op::$op(self, *rhs)
+                }
+            }
+
+            impl<T: $($bound)+> $OpAssign for ComplexNumber<T> {
+                fn $op_assign(&mut self, rhs: ComplexNumber<T>) {
+                    *self = $Op::$op(*self, rhs);
+                }
+            }
+
+            impl<T: $($bound)+> $OpAssign<&ComplexNumber<T>> for ComplexNumber<T> {
+                fn $op_assign(&mut self, rhs: &ComplexNumber<T>) {
+                    *self = $Op::$op(*self, *rhs);
+                }
+            }
+
+            impl<T: $($bound)+> $OpAssign<T> for ComplexNumber<T> {
+                fn $op_assign(&mut self, rhs: T) {
+                    *self = $Op::$op(*self, rhs);
+                }
+            }
+        };
+    }
+
+    forward_ops!(Add, add, AddAssign, add_assign, Num + Copy);
+    forward_ops!(Sub, sub, SubAssign, sub_assign, Num + Copy);
+    forward_ops!(Mul, mul, MulAssign, mul_assign, Num + Copy);
+    forward_ops!(Div, div, DivAssign, div_assign, Scalar);
+
+    // IMPLEMENTAZIONE TRATTI DI CONFRONTO
+    // L'uguaglianza è strutturale: due numeri sono uguali se lo sono entrambe le parti (3+4i != 5).
+    // Confrontare i moduli renderebbe uguali numeri con hash diversi e HashMap non li ritroverebbe.
+    // Non esiste un ordine "naturale" dei complessi, perciò ComplexNumber non implementa Ord: per
+    // ordinare si sceglie esplicitamente un wrapper (ByModulus, Lexicographic, ByArgument) e per
+    // confrontare risultati di calcoli si usa ApproxEq.
+
+    impl<T: Scalar> PartialEq for ComplexNumber<T> {
+        fn eq(&self, other: &Self) -> bool {
+            self.real.eq_part(&other.real) && self.imag.eq_part(&other.imag)
+        }
+    }
+
+    impl<T: Scalar> Eq for ComplexNumber<T> {}
+
+    // scritto a mano: derive chiederebbe solo T: PartialEq, ma ComplexNumber<T> è confrontabile se T: Scalar
+    impl<const N: usize, T: Scalar> PartialEq for ComplexArray<N, T> {
+        fn eq(&self, other: &Self) -> bool {
+            self.0 == other.0
+        }
+    }
+
+    impl<const N: usize, T: Scalar> Eq for ComplexArray<N, T> {}
+
+    // Ogni wrapper implementa Ord e un'uguaglianza coerente con esso (uguali se cmp dà Equal), che può
+    // essere diversa da quella di ComplexNumber: ByModulus(3+4i) == ByModulus(5).
+    // v.sort_by_key(|z| ByModulus(*z)) oppure BTreeSet<Lexicographic>.
+    macro_rules! ordering_wrapper {
+        ($name:ident, $($bound:tt)+) => {
+            impl<T: $($bound)+> PartialOrd for $name<T> {
+                fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                    Some(self.cmp(other))
+                }
             }
+
+            impl<T: $($bound)+> PartialEq for $name<T> {
+                fn eq(&self, other: &Self) -> bool {
+                    self.cmp(other) == Ordering::Equal
+                }
+            }
+
+            impl<T: $($bound)+> Eq for $name<T> {}
+        };
+    }
+
+    // ordine per modulo: se |a| > |b| allora a > b
+    #[derive(Debug, Copy, Clone)]
+    pub struct ByModulus<T = f64>(pub ComplexNumber<T>);
+
+    impl<T: Scalar> Ord for ByModulus<T> {
+        fn cmp(&self, other: &Self) -> Ordering {
+            T::cmp_modulus(&self.0, &other.0)
         }
     }
 
-    /// IMPLEMENTAZIONE TRATTI DI CONFRONTO
+    ordering_wrapper!(ByModulus, Scalar);
 
-    impl Ord for ComplexNumber {
+    // prima la parte reale, a parità la parte immaginaria
+    #[derive(Debug, Copy, Clone)]
+    pub struct Lexicographic<T = f64>(pub ComplexNumber<T>);
+
+    impl<T: Scalar> Ord for Lexicographic<T> {
         fn cmp(&self, other: &Self) -> Ordering {
-            self.modulus().total_cmp(&other.modulus())
+            self.0.real.cmp_part(&other.0.real).then_with(|| self.0.imag.cmp_part(&other.0.imag))
         }
     }
 
-    impl PartialOrd for ComplexNumber {
-        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-            Some(self.cmp(other))
+    ordering_wrapper!(Lexicographic, Scalar);
+
+    // per argomento in (-pi, pi], a parità di argomento per modulo: ordina i punti in senso antiorario
+    // partendo dal semiasse reale negativo
+    #[derive(Debug, Copy, Clone)]
+    pub struct ByArgument<T = f64>(pub ComplexNumber<T>);
+
+    impl<T: Float + Scalar> Ord for ByArgument<T> {
+        fn cmp(&self, other: &Self) -> Ordering {
+            self.0.arg().cmp_part(&other.0.arg()).then_with(|| T::cmp_modulus(&self.0, &other.0))
         }
     }
 
-    impl PartialEq for ComplexNumber {
-        fn eq(&self, other: &Self) -> bool {
-            self.modulus() == other.modulus()
+    ordering_wrapper!(ByArgument, Float + Scalar);
+
+
+    // CONFRONTO APPROSSIMATO
+    // I risultati dei calcoli in virgola mobile vanno confrontati con una tolleranza. Tre criteri:
+    //   abs_diff_eq   |a - b| <= epsilon, adatto vicino allo zero
+    //   relative_eq   |a - b| <= max_relative * max(|a|, |b|), adatto a valori grandi
+    //   ulps_eq       a e b distano al più max_ulps float rappresentabili
+    // relative_eq e ulps_eq accettano comunque le differenze entro epsilon, altrimenti due valori
+    // vicinissimi allo zero (o di segno diverso) non sarebbero mai uguali.
+    // Per ComplexNumber il criterio vale per ciascuna delle due parti.
+    pub trait ApproxEq {
+        type Epsilon: Copy;
+
+        fn default_epsilon() -> Self::Epsilon;
+        fn abs_diff_eq(&self, other: &Self, epsilon: Self::Epsilon) -> bool;
+        fn relative_eq(&self, other: &Self, epsilon: Self::Epsilon, max_relative: Self::Epsilon) -> bool;
+        fn ulps_eq(&self, other: &Self, epsilon: Self::Epsilon, max_ulps: u32) -> bool;
+
+        // relative_eq con epsilon della macchina come tolleranza assoluta e relativa
+        fn approx_eq(&self, other: &Self) -> bool {
+            self.relative_eq(other, Self::default_epsilon(), Self::default_epsilon())
         }
     }
 
-    impl Eq for ComplexNumber {}
+    macro_rules! float_approx_eq {
+        ($($t:ty, $bits:ty);+) => {$(
+            impl ApproxEq for $t {
+                type Epsilon = $t;
+
+                fn default_epsilon() -> $t {
+                    <$t>::EPSILON
+                }
+
+                fn abs_diff_eq(&self, other: &$t, epsilon: $t) -> bool {
+                    // self == other per gli infiniti, la cui differenza è NaN
+                    self == other || (self - other).abs() <= epsilon
+                }
+
+                fn relative_eq(&self, other: &$t, epsilon: $t, max_relative: $t) -> bool {
+                    if self.abs_diff_eq(other, epsilon) {
+                        return true;
+                    }
+                    if self.is_infinite() || other.is_infinite() {
+                        return false;
+                    }
+                    (self - other).abs() <= self.abs().max(other.abs()) * max_relative
+                }
+
+                fn ulps_eq(&self, other: &$t, epsilon: $t, max_ulps: u32) -> bool {
+                    if self.abs_diff_eq(other, epsilon) {
+                        return true;
+                    }
+                    if self.is_nan() || other.is_nan() || self.is_sign_negative() != other.is_sign_negative() {
+                        return false;
+                    }
+                    // con lo stesso segno i bit, letti come interi, sono ordinati come i float
+                    // e float consecutivi differiscono di 1
+                    let diff = (self.to_bits() as $bits).wrapping_sub(other.to_bits() as $bits).unsigned_abs();
+                    diff <= max_ulps as _
+                }
+            }
+        )+};
+    }
+
+    float_approx_eq!(f32, i32; f64, i64);
 
+    impl<T: ApproxEq> ApproxEq for ComplexNumber<T> {
+        type Epsilon = T::Epsilon;
 
-    /// CONVERSIONE DA UN TIPO AD UN ALTRO
+        fn default_epsilon() -> T::Epsilon {
+            T::default_epsilon()
+        }
+
+        fn abs_diff_eq(&self, other: &Self, epsilon: T::Epsilon) -> bool {
+            self.real.abs_diff_eq(&other.real, epsilon) && self.imag.abs_diff_eq(&other.imag, epsilon)
+        }
+
+        fn relative_eq(&self, other: &Self, epsilon: T::Epsilon, max_relative: T::Epsilon) -> bool {
+            self.real.relative_eq(&other.real, epsilon, max_relative)
+                && self.imag.relative_eq(&other.imag, epsilon, max_relative)
+        }
+
+        fn ulps_eq(&self, other: &Self, epsilon: T::Epsilon, max_ulps: u32) -> bool {
+            self.real.ulps_eq(&other.real, epsilon, max_ulps) && self.imag.ulps_eq(&other.imag, epsilon, max_ulps)
+        }
+    }
+
+
+    // CONVERSIONE DA UN TIPO AD UN ALTRO
 
     // da f64 a ComplexNumber
-    impl From<f64> for ComplexNumber {
+    impl<T: Num + Copy> From<T> for ComplexNumber<T> {
         // self qui è ComplexNumber!
-        fn from(value: f64) -> ComplexNumber {
-            ComplexNumber{ real: value, imag: 0.0 }
+        fn from(value: T) -> ComplexNumber<T> {
+            ComplexNumber::from_real(value)
         }
     }
 
     // into chiama from, bisogna implementare lui
     // questo permette di avere in automatico la conversione della struct in un tipo voluto
-    /** nota: diverso da Add, qui si ha from <valore iniziale> for ..output voluto..  **/
+    /* nota: diverso da Add, qui si ha from <valore iniziale> for ..output voluto..  */
     /*impl From<ComplexNumber> for f64 {
         fn from(complex: ComplexNumber) -> Self {
             complex.real
@@ -221,39 +771,226 @@ pub mod solution{
         }
     }*/
 
-    
-    /// Commentati per implementare test try_into. CONVERSIONE E GESTIONE ERRORE CON USO DI ENUM.
 
+    /// Commentati per implementare test try_into. CONVERSIONE E GESTIONE ERRORE CON USO DI ENUM.
     // Definizione dell'errore per la conversione TryInto
     #[derive(Debug, PartialEq)]
     pub enum ComplexNumberError {
         ImaginaryNotZero,
+        // errori di parse()
+        Empty,
+        InvalidPart(String),   // una delle due parti non è un numero
+        InvalidFormat(String), // la stringa non ha la forma di un numero complesso
     }
 
     impl fmt::Display for ComplexNumberError {
         fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             match self {
                 ComplexNumberError::ImaginaryNotZero => write!(f, "Cannot convert complex number with non-zero imaginary part"),
+                ComplexNumberError::Empty => write!(f, "Cannot parse a complex number from an empty string"),
+                ComplexNumberError::InvalidPart(part) => write!(f, "Invalid number '{}' in complex number", part),
+                ComplexNumberError::InvalidFormat(s) => write!(f, "'{}' is not a complex number", s),
             }
         }
     }
 
     impl error::Error for ComplexNumberError {}
 
-    // Implementazione del trait TryFrom (necessario per TryInto)
-    // Nota:implementiamo TryFrom<ComplexNumber> per f64 invece di TryInto<f64> per ComplexNumber
-    // perché Rust automaticamente implementa TryInto<U> per T quando esiste TryFrom<T> per U.
-    // Questo avviene grazie a un'implementazione generica nella libreria standard:
-    //      impl<T, U> TryInto<U> for T where U: TryFrom<T>
-    impl TryFrom<ComplexNumber> for f64 {
-        type Error = ComplexNumberError;
 
-        fn try_from(complex: ComplexNumber) -> Result<Self, Self::Error> {
-            if complex.imag == 0.0 {
-                Ok(complex.real)
-            } else {
-                Err(ComplexNumberError::ImaginaryNotZero)
+    // IMPLEMENTAZIONI PER I SINGOLI TIPI
+    // Per la orphan rule i trait con il tipo primitivo come soggetto di 'for' (f64 + ComplexNumber,
+    // TryFrom<ComplexNumber<f64>> for f64) non si possono scrivere con un T generico: la macro le
+    // scrive per ogni tipo.
+    macro_rules! primitive_impls {
+        ($($t:ty),+) => {$(
+            // Implementazione del trait TryFrom (necessario per TryInto)
+            // Nota:implementiamo TryFrom<ComplexNumber> per f64 invece di TryInto<f64> per ComplexNumber
+            // perché Rust automaticamente implementa TryInto<U> per T quando esiste TryFrom<T> per U.
+            // Questo avviene grazie a un'implementazione generica nella libreria standard:
+            //      impl<T, U> TryInto<U> for T where U: TryFrom<T>
+            impl TryFrom<ComplexNumber<$t>> for $t {
+                type Error = ComplexNumberError;
+
+                fn try_from(complex: ComplexNumber<$t>) -> Result<Self, Self::Error> {
+                    if complex.imag == (0 as $t) {
+                        Ok(complex.real)
+                    } else {
+                        Err(ComplexNumberError::ImaginaryNotZero)
+                    }
+                }
+            }
+
+            // f64 + ComplexNumber: qui il soggetto di 'for' è f64
+            impl Add<ComplexNumber<$t>> for $t {
+                type Output = ComplexNumber<$t>;
+                fn add(self, rhs: ComplexNumber<$t>) -> Self::Output {
+                    ComplexNumber{real: self + rhs.real, imag: rhs.imag}
+                }
+            }
+
+            impl Sub<ComplexNumber<$t>> for $t {
+                type Output = ComplexNumber<$t>;
+                fn sub(self, rhs: ComplexNumber<$t>) -> Self::Output {
+                    real_sub(self, rhs)
+                }
+            }
+
+            impl Mul<ComplexNumber<$t>> for $t {
+                type Output = ComplexNumber<$t>;
+                fn mul(self, rhs: ComplexNumber<$t>) -> Self::Output {
+                    rhs * self
+                }
+            }
+
+            impl Div<ComplexNumber<$t>> for $t {
+                type Output = ComplexNumber<$t>;
+                fn div(self, rhs: ComplexNumber<$t>) -> Self::Output {
+                    ComplexNumber::from_real(self) / rhs
+                }
+            }
+
+            impl Add<&ComplexNumber<$t>> for $t {
+                type Output = ComplexNumber<$t>;
+                fn add(self, rhs: &ComplexNumber<$t>) -> Self::Output {
+                    self + *rhs
+                }
+            }
+
+            impl Sub<&ComplexNumber<$t>> for $t {
+                type Output = ComplexNumber<$t>;
+                fn sub(self, rhs: &ComplexNumber<$t>) -> Self::Output {
+                    self - *rhs
+                }
             }
+
+            impl Mul<&ComplexNumber<$t>> for $t {
+                type Output = ComplexNumber<$t>;
+                fn mul(self, rhs: &ComplexNumber<$t>) -> Self::Output {
+                    self * *rhs
+                }
+            }
+
+            impl Div<&ComplexNumber<$t>> for $t {
+                type Output = ComplexNumber<$t>;
+                fn div(self, rhs: &ComplexNumber<$t>) -> Self::Output {
+                    self / *rhs
+                }
+            }
+        )+};
+    }
+
+    primitive_impls!(f32, f64, i8, i16, i32, i64, i128, isize);
+
+    macro_rules! float_scalar {
+        ($($t:ty),+) => {$(
+            impl Scalar for $t {
+                fn eq_part(&self, other: &$t) -> bool {
+                    self == other || (self.is_nan() && other.is_nan())
+                }
+
+                // the raw bits: f64 does not implement Hash because of NaN and -0.0,
+                // so the values that eq_part considers equal are hashed with the same bits
+                fn hash_part<H: Hasher>(&self, state: &mut H) {
+                    let canonical = if self.is_nan() { <$t>::NAN } else if *self == 0.0 { 0.0 } else { *self };
+                    canonical.to_bits().hash(state);
+                }
+
+                fn cmp_part(&self, other: &$t) -> Ordering {
+                    self.total_cmp(other)
+                }
+
+                fn cmp_modulus(a: &ComplexNumber<$t>, b: &ComplexNumber<$t>) -> Ordering {
+                    a.modulus().total_cmp(&b.modulus())
+                }
+
+                fn div_complex(a: ComplexNumber<$t>, b: ComplexNumber<$t>) -> ComplexNumber<$t> {
+                    smith_div(a, b)
+                }
+            }
+        )+};
+    }
+
+    macro_rules! int_scalar {
+        ($($t:ty),+) => {$(
+            impl Scalar for $t {
+                fn eq_part(&self, other: &$t) -> bool {
+                    self == other
+                }
+
+                fn hash_part<H: Hasher>(&self, state: &mut H) {
+                    self.hash(state);
+                }
+
+                fn cmp_part(&self, other: &$t) -> Ordering {
+                    self.cmp(other)
+                }
+
+                // the squares are compared in u128, which holds the square of any i64
+                fn cmp_modulus(a: &ComplexNumber<$t>, b: &ComplexNumber<$t>) -> Ordering {
+                    let norm = |z: &ComplexNumber<$t>| {
+                        let (re, im) = ((z.real as i128).unsigned_abs(), (z.imag as i128).unsigned_abs());
+                        re.saturating_mul(re).saturating_add(im.saturating_mul(im))
+                    };
+                    norm(a).cmp(&norm(b))
+                }
+
+                fn div_complex(a: ComplexNumber<$t>, b: ComplexNumber<$t>) -> ComplexNumber<$t> {
+                    textbook_div(a, b)
+                }
+            }
+        )+};
+    }
+
+    float_scalar!(f32, f64);
+    int_scalar!(i8, i16, i32, i64, i128, isize);
+
+
+    // PARSING DA STRINGA
+    // Forme accettate (gli spazi sono ignorati, per l'unità immaginaria vanno bene i e j):
+    //   3+4i   3 - 4j   -2.5i   i   -i   1e3 - 2j   7   (3,4)
+    // la parte reale viene prima di quella immaginaria, come la stampa Display.
+    impl<T: Num + Copy + FromStr> FromStr for ComplexNumber<T> {
+        type Err = ComplexNumberError;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            // gli spazi sono ammessi solo agli estremi, attorno all'operatore e dentro "( , )":
+            // uno spazio dentro un numero ("3 4", "1 e3") è un errore, non viene ignorato
+            let text = s.trim();
+            if text.is_empty() {
+                return Err(ComplexNumberError::Empty);
+            }
+            let number = |part: &str| {
+                if part.contains(char::is_whitespace) {
+                    return Err(ComplexNumberError::InvalidPart(part.to_string()));
+                }
+                part.parse::<T>().map_err(|_| ComplexNumberError::InvalidPart(part.to_string()))
+            };
+
+            // coppia (reale, immaginaria)
+            if let Some(pair) = text.strip_prefix('(').and_then(|p| p.strip_suffix(')')) {
+                let (real, imag) = pair.split_once(',').ok_or(ComplexNumberError::InvalidFormat(s.to_string()))?;
+                return Ok(ComplexNumber::new(number(real.trim())?, number(imag.trim())?));
+            }
+
+            let Some(body) = text.strip_suffix(['i', 'j']) else {
+                return Ok(ComplexNumber::from_real(number(text)?));
+            };
+            // l'operatore tra le due parti è l'ultimo + o - che non segue l'esponente (1e-3)
+            let bytes = body.as_bytes();
+            let split = (1..bytes.len())
+                .rev()
+                .find(|&k| matches!(bytes[k], b'+' | b'-') && !matches!(bytes[k - 1], b'e' | b'E'));
+            let (real, imag) = match split {
+                Some(k) => (number(body[..k].trim_end())?, format!("{}{}", &body[k..k + 1], body[k + 1..].trim_start())),
+                None => (T::zero(), body.to_string()),
+            };
+            // "i", "+i" e "-i" hanno coefficiente 1
+            let imag = match imag.as_str() {
+                "" | "+" => T::one(),
+                "-" => number("-1")?,
+                _ => number(&imag)?,
+            };
+            Ok(ComplexNumber::new(real, imag))
         }
     }
-}
\ No newline at end of file
+}
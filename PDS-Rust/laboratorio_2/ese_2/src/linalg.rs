@@ -0,0 +1,301 @@
+// ALGEBRA LINEARE COMPLESSA
+// ComplexArray<N> fa da vettore, ComplexMatrix<R, C> è una matrice R x C memorizzata per righe
+// (ogni riga è un ComplexArray<C>). Le dimensioni sono parametri const: moltiplicare matrici di
+// dimensioni incompatibili è un errore di compilazione, non di esecuzione.
+// Le operazioni che dividono (LU, determinante, inversa, sistemi lineari) richiedono parti Float.
+use std::ops::{Add, Index, IndexMut, Mul, Neg, Sub};
+use std::{error, fmt};
+
+use num::{Float, Num};
+
+use crate::solution::{ComplexArray, ComplexNumber, Scalar};
+
+fn zero<T: Num + Copy>() -> ComplexNumber<T> {
+    ComplexNumber::from_real(T::zero())
+}
+
+
+// VETTORI
+impl<const N: usize, T: Num + Copy> ComplexArray<N, T> {
+    // prodotto scalare bilineare: sum a_i b_i, senza coniugare
+    pub fn dot(&self, other: &Self) -> ComplexNumber<T> {
+        self.0.iter().zip(&other.0).fold(zero(), |acc, (a, b)| acc + *a * *b)
+    }
+}
+
+impl<const N: usize, T: Num + Copy + Neg<Output = T>> ComplexArray<N, T> {
+    // prodotto interno hermitiano <a, b> = sum conj(a_i) b_i: lineare nel secondo argomento,
+    // <a, a> = |a|^2 è reale e non negativo
+    pub fn inner(&self, other: &Self) -> ComplexNumber<T> {
+        self.0.iter().zip(&other.0).fold(zero(), |acc, (a, b)| acc + a.conj() * *b)
+    }
+
+    pub fn conj(&self) -> Self {
+        ComplexArray(self.0.map(|z| z.conj()))
+    }
+}
+
+impl<const N: usize, T: Float> ComplexArray<N, T> {
+    // sqrt(<a, a>)
+    pub fn euclidean_norm(&self) -> T {
+        self.0.iter().fold(T::zero(), |acc, z| acc + z.norm()).sqrt()
+    }
+}
+
+impl<const N: usize, T: Num + Copy> Add for ComplexArray<N, T> {
+    type Output = ComplexArray<N, T>;
+    fn add(self, rhs: ComplexArray<N, T>) -> Self::Output {
+        ComplexArray(std::array::from_fn(|i| self.0[i] + rhs.0[i]))
+    }
+}
+
+impl<const N: usize, T: Num + Copy> Sub for ComplexArray<N, T> {
+    type Output = ComplexArray<N, T>;
+    fn sub(self, rhs: ComplexArray<N, T>) -> Self::Output {
+        ComplexArray(std::array::from_fn(|i| self.0[i] - rhs.0[i]))
+    }
+}
+
+// vettore per scalare
+impl<const N: usize, T: Num + Copy> Mul<ComplexNumber<T>> for ComplexArray<N, T> {
+    type Output = ComplexArray<N, T>;
+    fn mul(self, rhs: ComplexNumber<T>) -> Self::Output {
+        ComplexArray(self.0.map(|z| z * rhs))
+    }
+}
+
+
+// MATRICI
+#[derive(Debug, Copy, Clone)]
+pub struct ComplexMatrix<const R: usize, const C: usize, T = f64>(pub [ComplexArray<C, T>; R]);
+
+impl<const R: usize, const C: usize, T: Copy + Default> Default for ComplexMatrix<R, C, T> {
+    fn default() -> Self {
+        ComplexMatrix([ComplexArray::default(); R])
+    }
+}
+
+impl<const R: usize, const C: usize, T: Scalar> PartialEq for ComplexMatrix<R, C, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+// m[(riga, colonna)]
+impl<const R: usize, const C: usize, T> Index<(usize, usize)> for ComplexMatrix<R, C, T> {
+    type Output = ComplexNumber<T>;
+    fn index(&self, (i, j): (usize, usize)) -> &ComplexNumber<T> {
+        &self.0[i].0[j]
+    }
+}
+
+impl<const R: usize, const C: usize, T> IndexMut<(usize, usize)> for ComplexMatrix<R, C, T> {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut ComplexNumber<T> {
+        &mut self.0[i].0[j]
+    }
+}
+
+impl<const R: usize, const C: usize, T: Num + Copy> ComplexMatrix<R, C, T> {
+    pub fn from_rows(rows: [[ComplexNumber<T>; C]; R]) -> Self {
+        ComplexMatrix(rows.map(ComplexArray))
+    }
+
+    pub fn zeros() -> Self {
+        ComplexMatrix([ComplexArray([zero(); C]); R])
+    }
+
+    pub fn row(&self, i: usize) -> &ComplexArray<C, T> {
+        &self.0[i]
+    }
+
+    pub fn column(&self, j: usize) -> ComplexArray<R, T> {
+        ComplexArray(std::array::from_fn(|i| self[(i, j)]))
+    }
+
+    pub fn transpose(&self) -> ComplexMatrix<C, R, T> {
+        ComplexMatrix(std::array::from_fn(|j| self.column(j)))
+    }
+}
+
+impl<const R: usize, const C: usize, T: Num + Copy + Neg<Output = T>> ComplexMatrix<R, C, T> {
+    // trasposta coniugata (aggiunta) A^H
+    pub fn conj_transpose(&self) -> ComplexMatrix<C, R, T> {
+        ComplexMatrix(std::array::from_fn(|j| self.column(j).conj()))
+    }
+}
+
+impl<const N: usize, T: Num + Copy> ComplexMatrix<N, N, T> {
+    pub fn identity() -> Self {
+        let mut m = Self::zeros();
+        for i in 0..N {
+            m[(i, i)] = ComplexNumber::from_real(T::one());
+        }
+        m
+    }
+}
+
+// (R x C) (C x K) = R x K
+impl<const R: usize, const C: usize, const K: usize, T: Num + Copy> Mul<ComplexMatrix<C, K, T>> for ComplexMatrix<R, C, T> {
+    type Output = ComplexMatrix<R, K, T>;
+    fn mul(self, rhs: ComplexMatrix<C, K, T>) -> Self::Output {
+        let columns = rhs.transpose();
+        ComplexMatrix(self.0.map(|row| ComplexArray(std::array::from_fn(|k| row.dot(&columns.0[k])))))
+    }
+}
+
+// matrice per vettore colonna
+impl<const R: usize, const C: usize, T: Num + Copy> Mul<ComplexArray<C, T>> for ComplexMatrix<R, C, T> {
+    type Output = ComplexArray<R, T>;
+    fn mul(self, rhs: ComplexArray<C, T>) -> Self::Output {
+        ComplexArray(self.0.map(|row| row.dot(&rhs)))
+    }
+}
+
+
+// DECOMPOSIZIONE LU
+#[derive(Debug, PartialEq)]
+pub enum LinalgError {
+    // nessun pivot utilizzabile nella colonna: la matrice è singolare (o quasi)
+    Singular { column: usize },
+}
+
+impl fmt::Display for LinalgError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LinalgError::Singular { column } => write!(f, "Matrix is singular: no pivot in column {}", column),
+        }
+    }
+}
+
+impl error::Error for LinalgError {}
+
+// PA = LU con pivoting parziale. L (triangolare inferiore con diagonale 1) e U sono memorizzate
+// nella stessa matrice: U sopra e sulla diagonale, i moltiplicatori di L sotto.
+// permutation[i] è la riga di A finita in posizione i.
+#[derive(Debug, Copy, Clone)]
+pub struct Lu<const N: usize, T = f64> {
+    factors: ComplexMatrix<N, N, T>,
+    permutation: [usize; N],
+    swaps: usize,
+}
+
+impl<const N: usize, T: Float + Scalar> ComplexMatrix<N, N, T> {
+    // Eliminazione di Gauss: a ogni passo il pivot è l'elemento di modulo massimo della colonna, il
+    // che limita la crescita degli errori. Una colonna senza pivot (tutta nulla) viene saltata: la
+    // fattorizzazione esiste comunque e il determinante è 0.
+    fn decompose(&self) -> Lu<N, T> {
+        let mut a = *self;
+        let mut permutation: [usize; N] = std::array::from_fn(|i| i);
+        let mut swaps = 0;
+
+        for k in 0..N {
+            let p = (k..N).max_by(|&i, &j| a[(i, k)].modulus().cmp_part(&a[(j, k)].modulus())).unwrap();
+            if p != k {
+                a.0.swap(p, k);
+                permutation.swap(p, k);
+                swaps += 1;
+            }
+            let pivot = a[(k, k)];
+            if pivot == zero() {
+                continue;
+            }
+            for i in k + 1..N {
+                let factor = a[(i, k)] / pivot;
+                a[(i, k)] = factor;
+                for j in k + 1..N {
+                    let update = factor * a[(k, j)];
+                    a[(i, j)] -= update;
+                }
+            }
+        }
+        Lu { factors: a, permutation, swaps }
+    }
+
+    // Un pivot più piccolo di N * epsilon * max|a_ij| della sua riga di A è considerato zero: sotto
+    // quella soglia è indistinguibile dagli errori di arrotondamento. La soglia è della riga e non
+    // di tutta la matrice, così una matrice con righe di scala molto diversa non risulta singolare.
+    pub fn lu(&self) -> Result<Lu<N, T>, LinalgError> {
+        let lu = self.decompose();
+        for k in 0..N {
+            let row = &self.0[lu.permutation[k]];
+            let scale = row.0.iter().fold(T::zero(), |m, z| m.max(z.modulus()));
+            if lu.factors[(k, k)].modulus() <= scale * T::epsilon() * T::from(N).unwrap() {
+                return Err(LinalgError::Singular { column: k });
+            }
+        }
+        Ok(lu)
+    }
+
+    // prodotto dei pivot: 0 solo se un pivot è esattamente zero
+    pub fn determinant(&self) -> ComplexNumber<T> {
+        self.decompose().determinant()
+    }
+
+    pub fn inverse(&self) -> Result<Self, LinalgError> {
+        Ok(self.lu()?.inverse())
+    }
+
+    // x tale che A x = b
+    pub fn solve(&self, b: &ComplexArray<N, T>) -> Result<ComplexArray<N, T>, LinalgError> {
+        Ok(self.lu()?.solve(b))
+    }
+}
+
+impl<const N: usize, T: Float + Scalar> Lu<N, T> {
+    // sostituzione in avanti con L (diagonale 1), poi all'indietro con U
+    pub fn solve(&self, b: &ComplexArray<N, T>) -> ComplexArray<N, T> {
+        let a = &self.factors;
+        let mut x: [ComplexNumber<T>; N] = std::array::from_fn(|i| b.0[self.permutation[i]]);
+        for i in 0..N {
+            for j in 0..i {
+                let update = a[(i, j)] * x[j];
+                x[i] -= update;
+            }
+        }
+        for i in (0..N).rev() {
+            for j in i + 1..N {
+                let update = a[(i, j)] * x[j];
+                x[i] -= update;
+            }
+            x[i] /= a[(i, i)];
+        }
+        ComplexArray(x)
+    }
+
+    // prodotto della diagonale di U, cambiato di segno per ogni scambio di righe
+    pub fn determinant(&self) -> ComplexNumber<T> {
+        let det = (0..N).fold(ComplexNumber::from_real(T::one()), |acc, i| acc * self.factors[(i, i)]);
+        if self.swaps % 2 == 1 { -det } else { det }
+    }
+
+    // la colonna j dell'inversa risolve A x = e_j
+    pub fn inverse(&self) -> ComplexMatrix<N, N, T> {
+        let identity = ComplexMatrix::<N, N, T>::identity();
+        let columns = ComplexMatrix(std::array::from_fn(|j| self.solve(&identity.0[j])));
+        columns.transpose()
+    }
+
+    pub fn l(&self) -> ComplexMatrix<N, N, T> {
+        let mut l = ComplexMatrix::identity();
+        for i in 0..N {
+            for j in 0..i {
+                l[(i, j)] = self.factors[(i, j)];
+            }
+        }
+        l
+    }
+
+    pub fn u(&self) -> ComplexMatrix<N, N, T> {
+        let mut u = ComplexMatrix::zeros();
+        for i in 0..N {
+            for j in i..N {
+                u[(i, j)] = self.factors[(i, j)];
+            }
+        }
+        u
+    }
+
+    pub fn permutation(&self) -> [usize; N] {
+        self.permutation
+    }
+}
@@ -0,0 +1,299 @@
+// POLINOMI A COEFFICIENTI COMPLESSI
+// p(x) = a_0 + a_1 x + ... + a_n x^n, i coefficienti sono memorizzati dal grado più basso:
+// Polynomial::new(vec![a_0, a_1, ..., a_n]). Gli zeri in testa (a_n = 0) vengono tolti, così il
+// grado è sempre quello reale; il polinomio nullo ha zero coefficienti.
+use std::ops::Mul;
+use std::{error, fmt};
+
+use num::traits::FloatConst;
+use num::{Float, Num};
+
+use crate::solution::{ComplexNumber, Scalar};
+
+#[derive(Debug, Clone)]
+pub struct Polynomial<T = f64> {
+    coefficients: Vec<ComplexNumber<T>>,
+}
+
+impl<T: Scalar> PartialEq for Polynomial<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.coefficients == other.coefficients
+    }
+}
+
+fn is_zero<T: Num + Copy>(z: &ComplexNumber<T>) -> bool {
+    z.real().is_zero() && z.imag().is_zero()
+}
+
+impl<T: Num + Copy> Polynomial<T> {
+    pub fn new(mut coefficients: Vec<ComplexNumber<T>>) -> Polynomial<T> {
+        while coefficients.last().is_some_and(is_zero) {
+            coefficients.pop();
+        }
+        Polynomial { coefficients }
+    }
+
+    // coefficienti reali, es. i polinomi dei filtri
+    pub fn from_real(coefficients: &[T]) -> Polynomial<T> {
+        Polynomial::new(coefficients.iter().map(|a| ComplexNumber::from_real(*a)).collect())
+    }
+
+    // (x - r_1)(x - r_2)...(x - r_n)
+    pub fn from_roots(roots: &[ComplexNumber<T>]) -> Polynomial<T> {
+        let one = ComplexNumber::from_real(T::one());
+        roots.iter().fold(Polynomial::new(vec![one]), |p, r| {
+            p * Polynomial::new(vec![ComplexNumber::from_real(T::zero()) - *r, one])
+        })
+    }
+
+    pub fn coefficients(&self) -> &[ComplexNumber<T>] {
+        &self.coefficients
+    }
+
+    // il polinomio nullo e le costanti hanno grado 0
+    pub fn degree(&self) -> usize {
+        self.coefficients.len().saturating_sub(1)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coefficients.is_empty()
+    }
+
+    // schema di Horner: a_0 + x (a_1 + x (a_2 + ...)), n moltiplicazioni e nessuna potenza
+    pub fn eval(&self, x: ComplexNumber<T>) -> ComplexNumber<T> {
+        self.coefficients.iter().rev().fold(ComplexNumber::from_real(T::zero()), |acc, a| acc * x + *a)
+    }
+
+    pub fn derivative(&self) -> Polynomial<T> {
+        let mut k = T::zero();
+        let coefficients = self.coefficients.iter().skip(1).map(|a| {
+            k = k + T::one();
+            *a * k
+        });
+        Polynomial::new(coefficients.collect())
+    }
+
+    // divisione sintetica per (x - r): restituisce il quoziente e il resto, che vale p(r).
+    // Se r è una radice il quoziente contiene le radici rimanenti (deflazione)
+    pub fn deflate(&self, r: ComplexNumber<T>) -> (Polynomial<T>, ComplexNumber<T>) {
+        let mut quotient = vec![ComplexNumber::from_real(T::zero()); self.degree()];
+        let mut acc = ComplexNumber::from_real(T::zero());
+        for (k, a) in self.coefficients.iter().enumerate().rev() {
+            acc = acc * r + *a;
+            if k > 0 {
+                quotient[k - 1] = acc;
+            }
+        }
+        (Polynomial::new(quotient), acc)
+    }
+}
+
+impl<T: Num + Copy> Mul for &Polynomial<T> {
+    type Output = Polynomial<T>;
+    fn mul(self, rhs: &Polynomial<T>) -> Polynomial<T> {
+        if self.is_zero() || rhs.is_zero() {
+            return Polynomial::new(Vec::new());
+        }
+        let mut product = vec![ComplexNumber::from_real(T::zero()); self.coefficients.len() + rhs.coefficients.len() - 1];
+        for (i, a) in self.coefficients.iter().enumerate() {
+            for (j, b) in rhs.coefficients.iter().enumerate() {
+                product[i + j] += *a * *b;
+            }
+        }
+        Polynomial::new(product)
+    }
+}
+
+impl<T: Num + Copy> Mul for Polynomial<T> {
+    type Output = Polynomial<T>;
+    fn mul(self, rhs: Polynomial<T>) -> Polynomial<T> {
+        &self * &rhs
+    }
+}
+
+
+// RICERCA DELLE RADICI
+#[derive(Debug, PartialEq)]
+pub enum RootError {
+    // ogni numero è radice del polinomio nullo
+    ZeroPolynomial,
+    NoConvergence { iterations: usize },
+}
+
+impl fmt::Display for RootError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RootError::ZeroPolynomial => write!(f, "The zero polynomial has no finite set of roots"),
+            RootError::NoConvergence { iterations } => write!(f, "Root finder did not converge in {} iterations", iterations),
+        }
+    }
+}
+
+impl error::Error for RootError {}
+
+// tolerance è l'errore all'indietro accettato per una radice: z è radice se
+//   |p(z)| <= tolerance * sum |a_k| |z|^k
+// cioè se z è radice esatta di un polinomio con coefficienti perturbati al più di tolerance in
+// senso relativo. Non dipende dalla scala dei coefficienti e vale anche per le radici multiple,
+// che in virgola mobile si possono calcolare solo con precisione ridotta.
+#[derive(Debug, Copy, Clone)]
+pub struct RootOptions<T = f64> {
+    pub tolerance: T,
+    pub max_iterations: usize,
+    // qualche passo di Newton sul polinomio originale per ogni radice trovata
+    pub polish: bool,
+}
+
+impl<T: Float> Default for RootOptions<T> {
+    fn default() -> Self {
+        RootOptions { tolerance: T::epsilon() * T::from(1000).unwrap(), max_iterations: 500, polish: true }
+    }
+}
+
+impl<T: Float> Polynomial<T> {
+    // sum |a_k| |z|^k: il valore di |p(z)| sotto il quale contano solo gli errori di arrotondamento
+    fn magnitude_at(&self, z: ComplexNumber<T>) -> T {
+        let r = z.modulus();
+        self.coefficients.iter().rev().fold(T::zero(), |acc, a| acc * r + a.modulus())
+    }
+
+    // verifica di una radice: vedi RootOptions per il significato di tolerance
+    pub fn is_root(&self, z: ComplexNumber<T>, tolerance: T) -> bool {
+        self.eval(z).modulus() <= tolerance * self.magnitude_at(z)
+    }
+}
+
+impl<T: Float + FloatConst + Scalar> Polynomial<T> {
+    pub fn roots(&self) -> Result<Vec<ComplexNumber<T>>, RootError> {
+        self.roots_with(&RootOptions::default())
+    }
+
+    // Metodo di Aberth-Ehrlich: tutte le radici vengono approssimate insieme. Il passo di Newton
+    // N = p(z_i) / p'(z_i) di ogni stima è corretto dalla repulsione delle altre stime:
+    //   z_i <- z_i - N / (1 - N sum_{j != i} 1 / (z_i - z_j))
+    // così due stime non convergono alla stessa radice semplice. La convergenza è cubica per le
+    // radici semplici, lineare per quelle multiple.
+    pub fn roots_with(&self, options: &RootOptions<T>) -> Result<Vec<ComplexNumber<T>>, RootError> {
+        if self.is_zero() {
+            return Err(RootError::ZeroPolynomial);
+        }
+
+        // le radici nulle esatte si tolgono subito: a_0 = 0 vuol dire che x divide p
+        let zeros = self.coefficients.iter().take_while(|a| is_zero(a)).count();
+        let reduced = Polynomial::new(self.coefficients[zeros..].to_vec());
+        let mut roots = vec![ComplexNumber::from_real(T::zero()); zeros];
+        let n = reduced.degree();
+        if n == 0 {
+            return Ok(roots);
+        }
+
+        let mut z = reduced.initial_guesses();
+        let derivative = reduced.derivative();
+        let mut converged = vec![false; n];
+        let mut iterations = 0;
+        while converged.iter().any(|c| !c) {
+            if iterations == options.max_iterations {
+                return Err(RootError::NoConvergence { iterations });
+            }
+            iterations += 1;
+
+            for i in 0..n {
+                if converged[i] {
+                    continue;
+                }
+                if reduced.is_root(z[i], options.tolerance) {
+                    converged[i] = true;
+                    continue;
+                }
+                let newton = reduced.eval(z[i]) / derivative.eval(z[i]);
+                let repulsion = (0..n)
+                    .filter(|&j| j != i)
+                    .fold(ComplexNumber::from_real(T::zero()), |acc, j| acc + (z[i] - z[j]).recip());
+                let step = newton / (ComplexNumber::from_real(T::one()) - newton * repulsion);
+                if !(step.real().is_finite() && step.imag().is_finite()) {
+                    // p'(z_i) = 0 oppure due stime coincidenti: si sposta un poco la stima e si riprova
+                    let nudge = options.tolerance * (T::one() + z[i].modulus());
+                    z[i] += ComplexNumber::new(nudge, nudge);
+                    continue;
+                }
+                z[i] -= step;
+                // passo sotto la precisione della macchina: la stima non può più migliorare
+                if step.modulus() <= T::epsilon() * z[i].modulus() {
+                    converged[i] = true;
+                }
+            }
+        }
+
+        if options.polish {
+            let derivative = self.derivative();
+            for zi in z.iter_mut() {
+                *zi = self.polish(*zi, &derivative);
+            }
+        }
+        if z.iter().any(|zi| !self.is_root(*zi, options.tolerance)) {
+            return Err(RootError::NoConvergence { iterations });
+        }
+        roots.extend(z);
+        Ok(roots)
+    }
+
+    // Stime iniziali sul cerchio di centro -a_{n-1} / (n a_n) (il baricentro delle radici) e raggio
+    // pari al limite di Fujiwara, 2 max |a_{n-k} / a_n|^(1/k), che contiene tutte le radici. L'angolo
+    // di partenza evita simmetrie con polinomi reali, le cui radici sono coniugate a coppie
+    fn initial_guesses(&self) -> Vec<ComplexNumber<T>> {
+        let n = self.degree();
+        let lead = self.coefficients[n];
+        let center = ComplexNumber::from_real(T::zero()) - self.coefficients[n - 1] / (lead * T::from(n).unwrap());
+        let radius = (1..=n)
+            .map(|k| (self.coefficients[n - k] / lead).modulus().powf(T::one() / T::from(k).unwrap()))
+            .fold(T::zero(), T::max);
+        let radius = (radius + radius).max(T::epsilon());
+        let offset = T::from(0.4).unwrap();
+        (0..n)
+            .map(|k| {
+                let angle = (T::PI() + T::PI()) * T::from(k).unwrap() / T::from(n).unwrap() + offset;
+                center + ComplexNumber::from_polar(radius, angle)
+            })
+            .collect()
+    }
+
+    // Newton sul polinomio originale (le radici di Aberth sono calcolate sul polinomio senza gli zeri
+    // in testa e le correzioni successive non si propagano): un passo viene accettato solo se |p|
+    // diminuisce, così le radici già esatte e quelle multiple non peggiorano
+    fn polish(&self, mut z: ComplexNumber<T>, derivative: &Polynomial<T>) -> ComplexNumber<T> {
+        let mut value = self.eval(z).modulus();
+        for _ in 0..5 {
+            if value.is_zero() {
+                break;
+            }
+            let next = z - self.eval(z) / derivative.eval(z);
+            let next_value = self.eval(next).modulus();
+            if next_value.is_nan() || next_value >= value {
+                break;
+            }
+            z = next;
+            value = next_value;
+        }
+        z
+    }
+}
+
+impl<T: fmt::Display + Num + Copy> fmt::Display for Polynomial<T> {
+    // (a_0) + (a_1)x + (a_2)x^2 + ...
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+        for (k, a) in self.coefficients.iter().enumerate() {
+            if k > 0 {
+                write!(f, " + ")?;
+            }
+            match k {
+                0 => write!(f, "({})", a)?,
+                1 => write!(f, "({})x", a)?,
+                _ => write!(f, "({})x^{}", a, k)?,
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,5 +1,14 @@
+#![allow(clippy::op_ref)] // the sums with references are what the tests are about
+// the original tests keep their style
+#![allow(clippy::assertions_on_constants, clippy::useless_vec)]
+
+use ese_2::solution::ApproxEq;
+use ese_2::solution::ByArgument;
+use ese_2::solution::ByModulus;
+use ese_2::solution::ComplexArray;
 use ese_2::solution::ComplexNumber;
 use ese_2::solution::ComplexNumberError;
+use ese_2::solution::Lexicographic;
 
 
 // for this execise see https://doc.rust-lang.org/beta/std/primitive.f64.html
@@ -207,12 +216,15 @@ pub fn test_sorting() {
     
     // Other option is to use < > explicitly in the cmp method
 
+    // Ord is not implemented on ComplexNumber itself (it would disagree with ==): the ordering is
+    // chosen with a wrapper, here ByModulus
+
     let a = ComplexNumber::new(1.0, 2.0);
     let b = ComplexNumber::new(2.0, 4.0);
     let c = ComplexNumber::new(3.0, 6.0);
     let mut v = vec![c, b, a];
 
-    v.sort();
+    v.sort_by_key(|z| ByModulus(*z));
 
     assert_eq!(v[0], a);
     assert_eq!(v[1], b);
@@ -287,7 +299,8 @@ pub fn test_deque() {
     }
 
     // if not sorted the result is meaningless
-    match q.binary_search(&ComplexNumber::new(2.0, 2.0)) {
+    let key = Lexicographic(ComplexNumber::new(2.0, 2.0));
+    match q.binary_search_by_key(&key, |z| Lexicographic(*z)) {
         Ok(idx) => {
             // ...either it may find and index, but this could fail randomly
             // assert_neq!(q[idx].to_tuple(), (2.0, 2.0));
@@ -301,12 +314,361 @@ pub fn test_deque() {
         },
     }
 
-    q.make_contiguous().sort();
+    q.make_contiguous().sort_by_key(|z| Lexicographic(*z));
 
     // once contiguous and sorted we can safely use binary_search
-    if let Ok(idx) = q.binary_search(&ComplexNumber::new(2.0, 2.0)) {
+    if let Ok(idx) = q.binary_search_by_key(&key, |z| Lexicographic(*z)) {
         assert_eq!(q[idx].to_tuple(), (2.0, 2.0));
     } else {
         assert!(false);
     }
-}
\ No newline at end of file
+}
+// the parts are compared one by one with an absolute tolerance
+fn assert_close(z: ComplexNumber, expected: (f64, f64)) {
+    assert!(z.abs_diff_eq(&ComplexNumber::new(expected.0, expected.1), 1e-12), "{} != {:?}", z, expected);
+}
+
+#[test]
+pub fn test_sub_mul_div_neg() {
+    let a = ComplexNumber::new(1.0, 2.0);
+    let b = ComplexNumber::new(3.0, -4.0);
+
+    assert_eq!((a - b).to_tuple(), (-2.0, 6.0));
+    assert_eq!((a * b).to_tuple(), (11.0, 2.0));
+    assert_close(a / b, (-0.2, 0.4));
+    assert_close(a / b * b, a.to_tuple());
+    assert_eq!((-a).to_tuple(), (-1.0, -2.0));
+    assert_eq!((-&a).to_tuple(), (-1.0, -2.0));
+
+    // huge parts: the naive c^2 + d^2 would overflow
+    let big = ComplexNumber::new(1e300, 1e300);
+    assert_close(big / big, (1.0, 0.0));
+    assert!((1.0 / ComplexNumber::new(0.0f64, 0.0)).real().is_nan());
+}
+
+#[test]
+pub fn test_ops_with_references_and_reals() {
+    let a: ComplexNumber = ComplexNumber::new(1.0, 2.0);
+    let b = ComplexNumber::new(2.0, 1.0);
+
+    assert_eq!((&a - &b).to_tuple(), (-1.0, 1.0));
+    assert_eq!((&a * b).to_tuple(), (0.0, 5.0));
+    assert_eq!((a / &b).to_tuple(), (a / b).to_tuple());
+    assert_eq!((&a + 1.0).to_tuple(), (2.0, 2.0));
+    assert_eq!((a * &2.0).to_tuple(), (2.0, 4.0));
+    assert_eq!((10.0 - a).to_tuple(), (9.0, -2.0));
+    assert_eq!((2.0 * &a).to_tuple(), (2.0, 4.0));
+    assert_eq!((1.0 + a).to_tuple(), (2.0, 2.0));
+    assert_close(5.0 / b, (2.0, -1.0));
+
+    let mut c = a;
+    c -= b;
+    c *= &b;
+    c /= 2.0;
+    c += &a;
+    assert_close(c, (-0.5, 2.5));
+}
+
+#[test]
+pub fn test_polar_and_conj() {
+    let a = ComplexNumber::new(3.0, 4.0);
+    assert_eq!(a.modulus(), 5.0);
+    assert_eq!(a.norm(), 25.0);
+    assert_eq!(a.conj().to_tuple(), (3.0, -4.0));
+    assert_eq!((a * a.conj()).to_tuple(), (25.0, 0.0));
+
+    let (r, theta) = a.to_polar();
+    assert_close(ComplexNumber::from_polar(r, theta), (3.0, 4.0));
+    assert_eq!(ComplexNumber::new(0.0, -2.0).arg(), -std::f64::consts::FRAC_PI_2);
+    assert_close(ComplexNumber::new(0.0, 2.0).recip(), (0.0, -0.5));
+}
+
+#[test]
+pub fn test_exp_ln_pow() {
+    use std::f64::consts::PI;
+
+    // e^(i pi) = -1
+    assert_close(ComplexNumber::new(0.0, PI).exp(), (-1.0, 0.0));
+    let a = ComplexNumber::new(1.5, -0.5);
+    assert_close(a.ln().exp(), a.to_tuple());
+    assert_close(a.sqrt() * a.sqrt(), a.to_tuple());
+    assert_close(ComplexNumber::new(-3.0, 4.0).sqrt(), (1.0, 2.0));
+
+    assert_close(a.powf(3.0), (a * a * a).to_tuple());
+    assert_close(a.powc(ComplexNumber::from_real(2.0)), (a * a).to_tuple());
+    // i^i = e^(-pi/2), a real number
+    assert_close(ComplexNumber::I.powc(ComplexNumber::I), ((-PI / 2.0).exp(), 0.0));
+    let zero = ComplexNumber::default();
+    assert_eq!(zero.powf(0.0).to_tuple(), (1.0, 0.0));
+    assert_eq!(zero.powf(2.5).to_tuple(), (0.0, 0.0));
+    assert_eq!(zero.powf(-1.0).to_tuple(), (f64::INFINITY, 0.0));
+    assert!(zero.powf(f64::NAN).real().is_nan());
+    assert_eq!(zero.powc(ComplexNumber::from_real(0.0)).to_tuple(), (1.0, 0.0));
+    assert_eq!(zero.powc(ComplexNumber::new(2.0, 3.0)).to_tuple(), (0.0, 0.0));
+    assert_eq!(zero.powc(ComplexNumber::from_real(-2.0)).to_tuple(), (f64::INFINITY, 0.0));
+    // 0^i and 0^(-1 + i): no modulus or no phase
+    let (re, im) = zero.powc(ComplexNumber::I).to_tuple();
+    assert!(re.is_nan() && im.is_nan());
+    let (re, im) = zero.powc(ComplexNumber::new(-1.0, 1.0)).to_tuple();
+    assert!(re.is_nan() && im.is_nan());
+    assert_eq!(zero.sqrt().to_tuple(), (0.0, 0.0));
+
+    // |re| + |z| would overflow: the result is compared with the scaled square root of a small number
+    let huge = [(1e308, 1e308, 1e154), (-f64::MAX, f64::MAX, f64::MAX.sqrt())];
+    for (re, im, scale) in huge {
+        let (a, b) = ComplexNumber::new(re, im).sqrt().to_tuple();
+        let expected = ComplexNumber::new(re.signum(), im.signum()).sqrt() * scale;
+        assert!((a / expected.real() - 1.0).abs() < 1e-12 && (b / expected.imag() - 1.0).abs() < 1e-12, "{} {}", a, b);
+    }
+}
+
+#[test]
+pub fn test_trigonometric() {
+    let a = ComplexNumber::new(0.5, -1.25);
+    let one = ComplexNumber::from_real(1.0);
+
+    // sin^2 + cos^2 = 1 and cosh^2 - sinh^2 = 1 hold for complex numbers too
+    assert_close(a.sin() * a.sin() + a.cos() * a.cos(), one.to_tuple());
+    assert_close(a.cosh() * a.cosh() - a.sinh() * a.sinh(), one.to_tuple());
+    assert_close(a.tan(), (a.sin() / a.cos()).to_tuple());
+    assert_close(a.tanh(), (a.sinh() / a.cosh()).to_tuple());
+    assert_close(ComplexNumber::new(1.0, 400.0).tan(), (0.0, 1.0));
+
+    assert_close(a.asin().sin(), a.to_tuple());
+    assert_close(a.acos().cos(), a.to_tuple());
+    assert_close(a.atan().tan(), a.to_tuple());
+}
+
+#[test]
+pub fn test_branch_cuts() {
+    use std::f64::consts::{FRAC_PI_2, PI};
+
+    // negative real axis: the sign of the zero imaginary part picks the side of the cut
+    let above = ComplexNumber::new(-4.0, 0.0);
+    let below = ComplexNumber::new(-4.0, -0.0);
+    assert_eq!(above.arg(), PI);
+    assert_eq!(below.arg(), -PI);
+    assert_close(above.sqrt(), (0.0, 2.0));
+    assert_close(below.sqrt(), (0.0, -2.0));
+    assert_close(above.ln(), (4f64.ln(), PI));
+    assert_close(below.ln(), (4f64.ln(), -PI));
+    assert_close(above.powf(0.5), (0.0, 2.0));
+    assert_close(below.powf(0.5), (0.0, -2.0));
+
+    // asin and acos: real axis beyond 1, same values as C99 casin/cacos
+    let k = (2.0 + 3f64.sqrt()).ln();
+    assert_close(ComplexNumber::new(2.0, 0.0).asin(), (FRAC_PI_2, k));
+    assert_close(ComplexNumber::new(2.0, -0.0).asin(), (FRAC_PI_2, -k));
+    assert_close(ComplexNumber::new(-2.0, 0.0).asin(), (-FRAC_PI_2, k));
+    assert_close(ComplexNumber::new(2.0, 0.0).acos(), (0.0, -k));
+    assert_close(ComplexNumber::new(2.0, -0.0).acos(), (0.0, k));
+
+    // atan: imaginary axis beyond i
+    let h = 3f64.ln() / 2.0;
+    assert_close(ComplexNumber::new(0.0, 2.0).atan(), (FRAC_PI_2, h));
+    assert_close(ComplexNumber::new(-0.0, 2.0).atan(), (-FRAC_PI_2, h));
+    assert_close(ComplexNumber::new(0.0, -2.0).atan(), (FRAC_PI_2, -h));
+}
+
+#[test]
+pub fn test_parse() {
+    let parse = |s: &str| s.parse::<ComplexNumber>().map(|z| z.to_tuple());
+
+    assert_eq!(parse("3+4i"), Ok((3.0, 4.0)));
+    assert_eq!(parse(" 3 - 4j "), Ok((3.0, -4.0)));
+    assert_eq!(parse("-2.5i"), Ok((0.0, -2.5)));
+    assert_eq!(parse("1e3 - 2j"), Ok((1000.0, -2.0)));
+    assert_eq!(parse("1e-3+2E+2i"), Ok((0.001, 200.0)));
+    assert_eq!(parse("(3, 4)"), Ok((3.0, 4.0)));
+    assert_eq!(parse("-7"), Ok((-7.0, 0.0)));
+    assert_eq!(parse("i"), Ok((0.0, 1.0)));
+    assert_eq!(parse("2 - i"), Ok((2.0, -1.0)));
+
+    assert_eq!(parse(""), Err(ComplexNumberError::Empty));
+    assert_eq!(parse("3+4"), Err(ComplexNumberError::InvalidPart(String::from("3+4"))));
+    assert_eq!(parse("x+4i"), Err(ComplexNumberError::InvalidPart(String::from("x"))));
+    assert_eq!(parse("(3;4)"), Err(ComplexNumberError::InvalidFormat(String::from("(3;4)"))));
+    assert_eq!(parse("4i+3"), Err(ComplexNumberError::InvalidPart(String::from("4i+3"))));
+
+    // spaces inside a number are not ignored
+    assert_eq!(parse("( 3 ,4 )"), Ok((3.0, 4.0)));
+    assert_eq!(parse("3 + i"), Ok((3.0, 1.0)));
+    assert_eq!(parse("3 4"), Err(ComplexNumberError::InvalidPart(String::from("3 4"))));
+    assert_eq!(parse("1 e3"), Err(ComplexNumberError::InvalidPart(String::from("1 e3"))));
+    assert_eq!(parse("1 e3 + 2i"), Err(ComplexNumberError::InvalidPart(String::from("1 e3"))));
+    assert_eq!(parse("3 + 4 i"), Err(ComplexNumberError::InvalidPart(String::from("+4 "))));
+    assert_eq!(parse("3 + 4 5i"), Err(ComplexNumberError::InvalidPart(String::from("+4 5"))));
+    assert_eq!(parse("(3 0, 4)"), Err(ComplexNumberError::InvalidPart(String::from("3 0"))));
+    assert_eq!(parse("- 2i"), Err(ComplexNumberError::InvalidPart(String::from("- 2"))));
+}
+
+#[test]
+pub fn test_format_flags() {
+    let a = ComplexNumber::new(1.0, -2.5);
+
+    assert_eq!(format!("{a}"), "1 - 2.5i");
+    assert_eq!(format!("{a:.3}"), "1.000 - 2.500i");
+    assert_eq!(format!("{a:+}"), "+1 - 2.5i");
+    assert_eq!(format!("{a:#}"), "1-2.5i");
+    assert_eq!(format!("{a:e}"), "1e0 - 2.5e0i");
+    assert_eq!(format!("{:.2E}", ComplexNumber::new(-1234.5, 0.0)), "-1.23E3 + 0.00E0i");
+    assert_eq!(format!("{a:>12}|"), "    1 - 2.5i|");
+    assert_eq!(format!("{a:*<10.1}|"), "1.0 - 2.5i|");
+    assert_eq!(format!("{a:^12}|"), "  1 - 2.5i  |");
+}
+
+#[test]
+pub fn test_parse_display_roundtrip() {
+    let values = [
+        ComplexNumber::new(0.1, -1.0 / 3.0),
+        ComplexNumber::new(-1e-300, 6.02e23),
+        ComplexNumber::new(-0.0, -0.0),
+        ComplexNumber::new(f64::INFINITY, f64::NEG_INFINITY),
+    ];
+    for z in values {
+        for s in [format!("{z}"), format!("{z:#}"), format!("{z:e}"), format!("{z:+E}")] {
+            let parsed: ComplexNumber = s.parse().unwrap();
+            assert_eq!(parsed.real().to_bits(), z.real().to_bits(), "{}", s);
+            assert_eq!(parsed.imag().to_bits(), z.imag().to_bits(), "{}", s);
+        }
+    }
+}
+
+#[test]
+pub fn test_f32() {
+    let a = ComplexNumber::new(3.0f32, 4.0);
+    let b = ComplexNumber::<f32>::i();
+
+    assert_eq!(a.modulus(), 5.0f32);
+    assert_eq!((a * b).to_tuple(), (-4.0f32, 3.0));
+    assert_eq!((a / a).to_tuple(), (1.0f32, 0.0));
+    assert_eq!((1.0f32 + a).to_tuple(), (4.0, 4.0));
+    assert!((a.sqrt() * a.sqrt() - a).modulus() < 1e-6);
+    assert_eq!(format!("{:.1}", a.conj()), "3.0 - 4.0i");
+    assert_eq!("1.5-2i".parse::<ComplexNumber<f32>>().unwrap().to_tuple(), (1.5, -2.0));
+
+    let r: Result<f32, _> = ComplexNumber::from(2.5f32).try_into();
+    assert_eq!(r, Ok(2.5));
+    let arr = ComplexArray::<4, f32>::default();
+    assert_eq!(arr.0[3].to_tuple(), (0.0f32, 0.0));
+}
+
+#[test]
+pub fn test_gaussian_integers() {
+    let a = ComplexNumber::new(3i64, 4);
+    let b = ComplexNumber::new(1i64, 2);
+
+    assert_eq!((a + b).to_tuple(), (4, 6));
+    assert_eq!((a - b).to_tuple(), (2, 2));
+    assert_eq!((a * b).to_tuple(), (-5, 10));
+    assert_eq!((a * b.conj()).to_tuple(), (11, -2));
+    assert_eq!(a.norm(), 25);
+    // exact division, and the truncated quotient when b does not divide a
+    assert_eq!(((a * b) / b).to_tuple(), (3, 4));
+    assert_eq!((a / b).to_tuple(), (2, 0));
+    assert_eq!((10 - a).to_tuple(), (7, -4));
+
+    let mut c = a;
+    c *= ComplexNumber::i();
+    c += 1;
+    assert_eq!(c.to_tuple(), (-3, 3));
+
+    // ordering by modulus without overflow on large parts
+    let big = ComplexNumber::new(i64::MAX, i64::MIN);
+    assert!(ByModulus(big) > ByModulus(a));
+
+    let mut counts = std::collections::HashMap::new();
+    *counts.entry(a).or_insert(0) += 1;
+    *counts.entry(ComplexNumber::new(3, 4)).or_insert(0) += 1;
+    assert_eq!(counts[&a], 2);
+
+    assert_eq!(i64::try_from(ComplexNumber::from(7i64)), Ok(7));
+    assert_eq!(i64::try_from(a), Err(ComplexNumberError::ImaginaryNotZero));
+    assert_eq!(format!("{}", b.conj()), "1 - 2i");
+    assert_eq!("-i".parse::<ComplexNumber<i64>>().unwrap().to_tuple(), (0, -1));
+    assert_eq!("2+3.5i".parse::<ComplexNumber<i64>>(), Err(ComplexNumberError::InvalidPart("+3.5".to_string())));
+}
+
+#[test]
+pub fn test_structural_equality_and_hash() {
+    use std::collections::HashMap;
+
+    // same modulus is not enough
+    assert_ne!(ComplexNumber::new(3.0, 4.0), ComplexNumber::from_real(5.0));
+    assert_eq!(ComplexNumber::new(3.0, 4.0), ComplexNumber::new(3.0, 4.0));
+    assert_ne!(ComplexNumber::new(3i64, 4), ComplexNumber::new(4, 3));
+
+    // values that compare equal must find each other in a map
+    let mut map = HashMap::new();
+    map.insert(ComplexNumber::new(3.0, 4.0), "a");
+    map.insert(ComplexNumber::from_real(5.0), "b");
+    map.insert(ComplexNumber::new(0.0, 0.0), "zero");
+    map.insert(ComplexNumber::new(f64::NAN, 1.0), "nan");
+    assert_eq!(map.len(), 4);
+    assert_eq!(map[&ComplexNumber::new(3.0, 4.0)], "a");
+    assert_eq!(map[&ComplexNumber::new(-0.0, -0.0)], "zero");
+    assert_eq!(map[&ComplexNumber::new(-f64::NAN, 1.0)], "nan");
+}
+
+#[test]
+pub fn test_approx_eq() {
+    let a = ComplexNumber::new(0.1 + 0.2, 1.0);
+    let b = ComplexNumber::new(0.3, 1.0);
+    assert_ne!(a, b);
+    assert!(a.approx_eq(&b));
+    assert!(a.ulps_eq(&b, 0.0, 1));
+    assert!(!a.ulps_eq(&ComplexNumber::new(0.3 + 1e-15, 1.0), 0.0, 1));
+
+    // absolute tolerance near zero, relative tolerance for large values
+    let tiny = ComplexNumber::new(1e-20, 0.0);
+    assert!(tiny.abs_diff_eq(&ComplexNumber::new(-1e-20, 0.0), 1e-12));
+    assert!(!tiny.relative_eq(&ComplexNumber::new(-1e-20, 0.0), 0.0, 1e-6));
+    let big = ComplexNumber::new(1e20, -1e20);
+    assert!(!big.abs_diff_eq(&(big * (1.0 + 1e-12)), 1.0));
+    assert!(big.relative_eq(&(big * (1.0 + 1e-12)), 0.0, 1e-9));
+
+    // each part is checked on its own
+    assert!(!ComplexNumber::new(1.0, 0.0).abs_diff_eq(&ComplexNumber::new(1.0, 1e-3), 1e-6));
+    // infinities are equal to themselves, NaN to nothing
+    let inf = ComplexNumber::new(f64::INFINITY, 0.0);
+    assert!(inf.relative_eq(&inf, 1e-9, 1e-9) && inf.ulps_eq(&inf, 0.0, 4));
+    assert!(!inf.relative_eq(&ComplexNumber::new(f64::MAX, 0.0), 1e-9, 1e-9));
+    let nan = ComplexNumber::new(f64::NAN, 0.0);
+    assert!(!nan.abs_diff_eq(&nan, 1.0) && !nan.ulps_eq(&nan, 1.0, u32::MAX));
+
+    let c = ComplexNumber::new(1.0f32, 2.0).sqrt();
+    assert!((c * c).relative_eq(&ComplexNumber::new(1.0, 2.0), f32::EPSILON, 1e-6));
+}
+
+#[test]
+pub fn test_orderings() {
+    let values = [
+        ComplexNumber::new(-1.0, 0.0),
+        ComplexNumber::new(3.0, 4.0),
+        ComplexNumber::new(0.0, -2.0),
+        ComplexNumber::new(5.0, 0.0),
+        ComplexNumber::new(1.0, 1.0),
+    ];
+
+    let mut v = values;
+    v.sort_by_key(|z| ByModulus(*z));
+    assert_eq!(v.map(|z| z.to_tuple()), [(-1.0, 0.0), (1.0, 1.0), (0.0, -2.0), (3.0, 4.0), (5.0, 0.0)]);
+    // equal moduli are equal for the wrapper, not for ComplexNumber
+    assert_eq!(ByModulus(values[1]), ByModulus(values[3]));
+
+    v.sort_by_key(|z| Lexicographic(*z));
+    assert_eq!(v.map(|z| z.to_tuple()), [(-1.0, 0.0), (0.0, -2.0), (1.0, 1.0), (3.0, 4.0), (5.0, 0.0)]);
+
+    // counterclockwise from the negative real axis: -pi/2, 0, pi/4, atan(4/3), pi
+    v.sort_by_key(|z| ByArgument(*z));
+    assert_eq!(v.map(|z| z.to_tuple()), [(0.0, -2.0), (5.0, 0.0), (1.0, 1.0), (3.0, 4.0), (-1.0, 0.0)]);
+
+    let set: std::collections::BTreeSet<_> = values.iter().map(|z| Lexicographic(*z)).collect();
+    assert_eq!(set.first().unwrap().0.to_tuple(), (-1.0, 0.0));
+
+    let max = [ComplexNumber::new(3i64, 4), ComplexNumber::new(-6, 0), ComplexNumber::new(0, 1)]
+        .into_iter()
+        .max_by_key(|z| ByModulus(*z))
+        .unwrap();
+    assert_eq!(max, ComplexNumber::new(-6, 0));
+}
@@ -0,0 +1,142 @@
+use ese_2::fft::{self, Direction, FftError};
+use ese_2::solution::ApproxEq;
+use ese_2::solution::ComplexArray;
+use ese_2::solution::ComplexNumber;
+
+// deterministic test signal, no two samples alike
+fn signal(n: usize) -> Vec<ComplexNumber> {
+    (0..n)
+        .map(|k| ComplexNumber::new((k as f64 * 1.3).sin() + (k % 3) as f64, (k as f64 * 0.7).cos() - 0.5))
+        .collect()
+}
+
+// the error of the FFT grows with log n, of the naive DFT with n: the tolerance scales with the size
+fn assert_all_close(a: &[ComplexNumber], b: &[ComplexNumber]) {
+    assert_eq!(a.len(), b.len());
+    let tolerance = 1e-12 * (a.len().max(1) as f64);
+    for (k, (x, y)) in a.iter().zip(b).enumerate() {
+        assert!(x.abs_diff_eq(y, tolerance), "index {}: {} != {}", k, x, y);
+    }
+}
+
+#[test]
+pub fn test_radix2_matches_dft() {
+    for n in [1, 2, 4, 8, 64, 256] {
+        let x = signal(n);
+        let mut y = x.clone();
+        fft::radix2(&mut y, Direction::Forward).unwrap();
+        assert_all_close(&y, &fft::dft(&x, Direction::Forward));
+
+        fft::radix2(&mut y, Direction::Inverse).unwrap();
+        assert_all_close(&y, &x);
+    }
+}
+
+#[test]
+pub fn test_radix2_rejects_other_lengths() {
+    let mut x = signal(12);
+    assert_eq!(fft::radix2(&mut x, Direction::Forward), Err(FftError::NotPowerOfTwo(12)));
+    assert_eq!(x, signal(12));
+    assert_eq!(FftError::NotPowerOfTwo(0).to_string(), "Radix-2 FFT needs a power of two length, got 0");
+}
+
+#[test]
+pub fn test_bluestein_matches_dft() {
+    for n in [2, 3, 5, 7, 12, 100, 127] {
+        let x = signal(n);
+        let mut y = x.clone();
+        fft::bluestein(&mut y, Direction::Forward);
+        assert_all_close(&y, &fft::dft(&x, Direction::Forward));
+
+        fft::bluestein(&mut y, Direction::Inverse);
+        assert_all_close(&y, &x);
+    }
+}
+
+#[test]
+pub fn test_fft_any_length() {
+    for n in 0..40 {
+        let x = signal(n);
+        let mut y = x.clone();
+        fft::fft(&mut y);
+        assert_all_close(&y, &fft::dft(&x, Direction::Forward));
+        fft::ifft(&mut y);
+        assert_all_close(&y, &x);
+    }
+}
+
+#[test]
+pub fn test_known_spectra() {
+    // an impulse has a flat spectrum
+    let mut impulse = [ComplexNumber::default(); 6];
+    impulse[0] = ComplexNumber::from_real(1.0);
+    fft::fft(&mut impulse);
+    assert_all_close(&impulse, &[ComplexNumber::from_real(1.0); 6]);
+
+    // e^(2 pi i 3k / 16) is a single frequency, bin 3
+    let mut tone: Vec<ComplexNumber> = (0..16)
+        .map(|k| ComplexNumber::from_polar(1.0, 2.0 * std::f64::consts::PI * 3.0 * k as f64 / 16.0))
+        .collect();
+    fft::fft(&mut tone);
+    for (k, z) in tone.iter().enumerate() {
+        let expected = if k == 3 { 16.0 } else { 0.0 };
+        assert!(z.abs_diff_eq(&ComplexNumber::from_real(expected), 1e-12), "bin {}: {}", k, z);
+    }
+}
+
+#[test]
+pub fn test_rfft() {
+    for n in [1, 2, 3, 8, 10, 15, 64] {
+        let x: Vec<f64> = signal(n).iter().map(|z| z.real()).collect();
+        let complex: Vec<ComplexNumber> = x.iter().map(|r| ComplexNumber::from_real(*r)).collect();
+
+        let spectrum = fft::rfft(&x);
+        assert_eq!(spectrum.len(), n / 2 + 1);
+        assert_all_close(&spectrum, &fft::dft(&complex, Direction::Forward)[..n / 2 + 1]);
+
+        let back = fft::irfft(&spectrum, n);
+        for (a, b) in back.iter().zip(&x) {
+            assert!(a.abs_diff_eq(b, 1e-12), "{} != {}", a, b);
+        }
+    }
+    assert!(fft::rfft::<f64>(&[]).is_empty());
+}
+
+#[test]
+pub fn test_convolution() {
+    let a = signal(7);
+    let b = signal(20)[10..].to_vec();
+    let mut naive = vec![ComplexNumber::default(); a.len() + b.len() - 1];
+    for (i, x) in a.iter().enumerate() {
+        for (j, y) in b.iter().enumerate() {
+            naive[i + j] += x * y;
+        }
+    }
+    assert_all_close(&fft::convolve(&a, &b), &naive);
+    assert!(fft::convolve(&a, &[]).is_empty());
+
+    // polynomial product: (1 + 2x + 3x^2)(4 + 5x) = 4 + 13x + 22x^2 + 15x^3
+    let product = fft::convolve_real(&[1.0, 2.0, 3.0], &[4.0, 5.0]);
+    for (a, b) in product.iter().zip([4.0, 13.0, 22.0, 15.0]) {
+        assert!(a.abs_diff_eq(&b, 1e-12), "{} != {}", a, b);
+    }
+}
+
+#[test]
+pub fn test_complex_array_and_f32() {
+    let mut arr = ComplexArray::<8>::default();
+    arr.0.copy_from_slice(&signal(8));
+    arr.fft();
+    assert_all_close(&arr.0, &fft::dft(&signal(8), Direction::Forward));
+    arr.ifft();
+    assert_all_close(&arr.0, &signal(8));
+
+    let mut small = ComplexArray::<5, f32>::default();
+    small.0[1] = ComplexNumber::new(1.0, -1.0);
+    small.fft();
+    // a shifted impulse: X[k] = (1 - i) e^(-2 pi i k / 5)
+    for (k, z) in small.0.iter().enumerate() {
+        let expected = ComplexNumber::new(1.0f32, -1.0) * ComplexNumber::from_polar(1.0, -2.0 * std::f32::consts::PI * k as f32 / 5.0);
+        assert!(z.abs_diff_eq(&expected, 1e-5), "bin {}: {} != {}", k, z, expected);
+    }
+}
@@ -0,0 +1,86 @@
+use ese_2::fractal::{self, Fractal, Palette, RenderConfig, Viewport};
+use ese_2::solution::ComplexNumber;
+
+fn small(fractal: Fractal, threads: usize, tile_size: usize) -> RenderConfig {
+    let mut config = RenderConfig::new(fractal, 48, 32);
+    config.max_iterations = 100;
+    config.threads = threads;
+    config.tile_size = tile_size;
+    config
+}
+
+#[test]
+pub fn test_escape_time() {
+    let zero = ComplexNumber::default();
+    // in the set: 0, the boundary point -2 and the period-2 bulb
+    assert_eq!(fractal::escape_time(zero, zero, 1000), None);
+    assert_eq!(fractal::escape_time(zero, ComplexNumber::from_real(-2.0), 1000), None);
+    assert_eq!(fractal::escape_time(zero, ComplexNumber::from_real(-1.0), 1000), None);
+
+    // outside: escapes, and farther points escape sooner
+    let near = fractal::escape_time(zero, ComplexNumber::new(0.4, 0.4), 1000).unwrap();
+    let far = fractal::escape_time(zero, ComplexNumber::new(1.0, 1.0), 1000).unwrap();
+    assert!(far < near, "{} {}", far, near);
+
+    // smooth: close points have close values, no jump of a whole iteration
+    let a = fractal::escape_time(zero, ComplexNumber::new(0.4, 0.3), 1000).unwrap();
+    let b = fractal::escape_time(zero, ComplexNumber::new(0.4, 0.3 + 1e-6), 1000).unwrap();
+    assert!((a - b).abs() < 1e-3, "{} {}", a, b);
+}
+
+#[test]
+pub fn test_viewport_mapping() {
+    let mut config = RenderConfig::new(Fractal::Mandelbrot, 4, 2);
+    config.viewport = Viewport { center: ComplexNumber::new(1.0, 1.0), width: 4.0 };
+    // one unit per pixel, pixel centres at half units, imaginary part grows upwards
+    assert_eq!(config.point(0, 0), ComplexNumber::new(-0.5, 1.5));
+    assert_eq!(config.point(3, 1), ComplexNumber::new(2.5, 0.5));
+}
+
+#[test]
+pub fn test_tiles_and_threads_do_not_change_the_image() {
+    let reference = fractal::render(&small(Fractal::Mandelbrot, 1, 64));
+    assert_eq!(reference.values.len(), 48 * 32);
+    for (threads, tile) in [(4, 7), (3, 16), (8, 1), (2, 100)] {
+        assert_eq!(fractal::render(&small(Fractal::Mandelbrot, threads, tile)), reference);
+    }
+}
+
+#[test]
+pub fn test_render_checksum() {
+    let image = fractal::render(&small(Fractal::Mandelbrot, 4, 8));
+    let pgm = image.to_pgm();
+    assert!(pgm.starts_with(b"P5\n48 32\n255\n"));
+    assert_eq!(pgm.len(), 13 + 48 * 32);
+    assert_eq!(fractal::checksum(&pgm), 0x9a714a498b177f33);
+
+    let ppm = image.to_ppm(Palette::Fire);
+    assert!(ppm.starts_with(b"P6\n48 32\n255\n"));
+    assert_eq!(ppm.len(), 13 + 3 * 48 * 32);
+    assert_eq!(fractal::checksum(&ppm), 0x3ac760d5663fd300);
+}
+
+#[test]
+pub fn test_julia_symmetry() {
+    // for z <- z^2 + c the Julia set is symmetric with respect to the origin
+    let config = small(Fractal::Julia(ComplexNumber::new(-0.8, 0.156)), 4, 16);
+    let image = fractal::render(&config);
+    let (w, h) = (config.width, config.height);
+    for y in 0..h {
+        for x in 0..w {
+            assert_eq!(image.values[y * w + x], image.values[(h - 1 - y) * w + (w - 1 - x)]);
+        }
+    }
+    assert!(image.values.iter().any(|v| v.is_none()) && image.values.iter().any(|v| v.is_some()));
+}
+
+#[test]
+pub fn test_palettes() {
+    assert_eq!(Palette::Grayscale.color(0.0), [0, 0, 0]);
+    assert_eq!(Palette::Grayscale.color(0.5), [128, 128, 128]);
+    assert_eq!(Palette::Fire.color(1.0), [255, 255, 255]);
+    assert_eq!(Palette::Rainbow.color(0.0), [255, 0, 0]);
+    // out of range values are clamped
+    assert_eq!(Palette::Ocean.color(7.0), Palette::Ocean.color(1.0));
+    assert_eq!(Palette::Ocean.color(-1.0), [0, 0, 40]);
+}
@@ -0,0 +1,184 @@
+use ese_2::linalg::{ComplexMatrix, LinalgError};
+use ese_2::solution::ApproxEq;
+use ese_2::solution::ComplexArray;
+use ese_2::solution::ComplexNumber;
+
+fn c(re: f64, im: f64) -> ComplexNumber {
+    ComplexNumber::new(re, im)
+}
+
+fn r(re: f64) -> ComplexNumber {
+    ComplexNumber::from_real(re)
+}
+
+fn assert_vec_close<const N: usize>(a: &ComplexArray<N>, b: &ComplexArray<N>) {
+    for (x, y) in a.0.iter().zip(&b.0) {
+        assert!(x.abs_diff_eq(y, 1e-12), "{:?} != {:?}", a, b);
+    }
+}
+
+fn assert_matrix_close<const R: usize, const C: usize>(a: &ComplexMatrix<R, C>, b: &ComplexMatrix<R, C>) {
+    for (x, y) in a.0.iter().zip(&b.0) {
+        assert_vec_close(x, y);
+    }
+}
+
+// a well conditioned complex matrix, diagonally dominant
+fn sample() -> ComplexMatrix<4, 4> {
+    ComplexMatrix::from_rows([
+        [c(4.0, 1.0), c(1.0, -1.0), r(0.5), c(0.0, 2.0)],
+        [c(-1.0, 0.5), c(5.0, 0.0), c(2.0, 1.0), r(-1.0)],
+        [r(0.0), c(1.0, 1.0), c(-6.0, 2.0), c(1.0, 0.0)],
+        [c(2.0, -2.0), r(0.0), c(0.5, 0.5), c(3.0, -4.0)],
+    ])
+}
+
+#[test]
+pub fn test_dot_and_inner() {
+    let a = ComplexArray([c(1.0, 1.0), r(2.0)]);
+    let b = ComplexArray([r(3.0), c(0.0, -1.0)]);
+
+    assert_eq!(a.dot(&b), c(3.0, 1.0));
+    // the first argument is conjugated
+    assert_eq!(a.inner(&b), c(3.0, -5.0));
+    assert_eq!(b.inner(&a), a.inner(&b).conj());
+    assert_eq!(a.inner(&a), r(6.0));
+    assert_eq!(a.euclidean_norm(), 6.0f64.sqrt());
+
+    assert_eq!(a + b, ComplexArray([c(4.0, 1.0), c(2.0, -1.0)]));
+    assert_eq!(a - a, ComplexArray::default());
+    assert_eq!(a * ComplexNumber::i(), ComplexArray([c(-1.0, 1.0), c(0.0, 2.0)]));
+
+    // Gaussian integers: everything that does not divide works on integers too
+    let g = ComplexArray([ComplexNumber::new(1i64, 2), ComplexNumber::new(3, -1)]);
+    assert_eq!(g.inner(&g), ComplexNumber::new(15, 0));
+}
+
+#[test]
+pub fn test_matrix_product_and_transpose() {
+    let a = ComplexMatrix::from_rows([[r(1.0), c(0.0, 1.0)], [r(0.0), r(2.0)]]);
+    let b = ComplexMatrix::from_rows([[r(1.0), r(0.0)], [c(0.0, 1.0), r(1.0)]]);
+    assert_eq!(a * b, ComplexMatrix::from_rows([[r(0.0), c(0.0, 1.0)], [c(0.0, 2.0), r(2.0)]]));
+    assert_eq!(a * ComplexMatrix::identity(), a);
+
+    // 2x3 times 3x1
+    let m = ComplexMatrix::from_rows([[r(1.0), r(2.0), r(3.0)], [c(0.0, 1.0), r(0.0), c(0.0, -1.0)]]);
+    let v = ComplexMatrix::from_rows([[r(1.0)], [r(1.0)], [c(1.0, 1.0)]]);
+    assert_eq!(m * v, ComplexMatrix::from_rows([[c(6.0, 3.0)], [r(1.0)]]));
+    assert_eq!(m * v.column(0), ComplexArray([c(6.0, 3.0), r(1.0)]));
+
+    let t = m.transpose();
+    assert_eq!(t[(2, 1)], c(0.0, -1.0));
+    let h = m.conj_transpose();
+    assert_eq!(h[(2, 1)], c(0.0, 1.0));
+    assert_eq!(h.conj_transpose(), m);
+    // (AB)^H = B^H A^H
+    assert_eq!((m * v).conj_transpose(), v.conj_transpose() * m.conj_transpose());
+}
+
+#[test]
+pub fn test_determinant() {
+    assert_eq!(ComplexMatrix::from_rows([[r(1.0), r(2.0)], [r(3.0), r(4.0)]]).determinant(), r(-2.0));
+    assert_eq!(ComplexMatrix::from_rows([[c(0.0, 1.0), r(0.0)], [r(0.0), c(0.0, 1.0)]]).determinant(), r(-1.0));
+    // a row swap changes the sign
+    assert_eq!(ComplexMatrix::from_rows([[r(0.0), r(1.0)], [r(1.0), r(0.0)]]).determinant(), r(-1.0));
+
+    // cofactor expansion along the first row
+    let m = ComplexMatrix::from_rows([
+        [c(1.0, 2.0), r(3.0), c(0.0, -1.0)],
+        [r(2.0), c(1.0, 1.0), r(4.0)],
+        [c(-1.0, 0.0), r(0.5), c(2.0, -3.0)],
+    ]);
+    let minor = |i: usize, j: usize, k: usize, l: usize| m[(1, i)] * m[(2, j)] - m[(1, k)] * m[(2, l)];
+    let expected = m[(0, 0)] * minor(1, 2, 2, 1) - m[(0, 1)] * minor(0, 2, 2, 0) + m[(0, 2)] * minor(0, 1, 1, 0);
+    assert!(m.determinant().abs_diff_eq(&expected, 1e-12));
+
+    assert_eq!(ComplexMatrix::<0, 0>::identity().determinant(), r(1.0));
+}
+
+#[test]
+pub fn test_solve_known_system() {
+    // Hermitian matrix, solution x = (1, i)
+    let a = ComplexMatrix::from_rows([[r(2.0), c(1.0, 1.0)], [c(1.0, -1.0), r(3.0)]]);
+    let b = ComplexArray([c(1.0, 1.0), c(1.0, 2.0)]);
+    assert_vec_close(&a.solve(&b).unwrap(), &ComplexArray([r(1.0), c(0.0, 1.0)]));
+
+    // a zero on the diagonal needs pivoting
+    let p = ComplexMatrix::from_rows([[r(0.0), r(2.0)], [c(0.0, 1.0), r(1.0)]]);
+    assert_vec_close(&p.solve(&ComplexArray([r(4.0), r(2.0)])).unwrap(), &ComplexArray([r(0.0), r(2.0)]));
+
+    let m = sample();
+    let x = ComplexArray([c(1.0, -1.0), r(2.0), c(0.0, 3.0), c(-0.5, 0.25)]);
+    let solved = m.solve(&(m * x)).unwrap();
+    assert_vec_close(&solved, &x);
+    assert!((m * solved - m * x).euclidean_norm() < 1e-12);
+}
+
+#[test]
+pub fn test_lu_factors_and_inverse() {
+    let m = sample();
+    let lu = m.lu().unwrap();
+
+    // P A = L U
+    let permuted = ComplexMatrix(lu.permutation().map(|i| *m.row(i)));
+    assert_matrix_close(&(lu.l() * lu.u()), &permuted);
+    assert!(lu.determinant().abs_diff_eq(&m.determinant(), 1e-12));
+
+    let inv = m.inverse().unwrap();
+    assert_matrix_close(&(m * inv), &ComplexMatrix::identity());
+    assert_matrix_close(&(inv * m), &ComplexMatrix::identity());
+    assert!((inv.determinant() * m.determinant()).abs_diff_eq(&r(1.0), 1e-12));
+
+    let small = ComplexMatrix::from_rows([[r(1.0), r(2.0)], [r(3.0), r(4.0)]]);
+    assert_matrix_close(&small.inverse().unwrap(), &ComplexMatrix::from_rows([[r(-2.0), r(1.0)], [r(1.5), r(-0.5)]]));
+}
+
+#[test]
+pub fn test_singular_matrices() {
+    let exact = ComplexMatrix::from_rows([[r(1.0), c(2.0, 1.0)], [r(2.0), c(4.0, 2.0)]]);
+    assert_eq!(exact.lu().err(), Some(LinalgError::Singular { column: 1 }));
+    assert_eq!(exact.solve(&ComplexArray::default()), Err(LinalgError::Singular { column: 1 }));
+    assert_eq!(exact.inverse().err(), Some(LinalgError::Singular { column: 1 }));
+    assert_eq!(exact.determinant(), r(0.0));
+
+    // singular only up to rounding errors
+    let rounded = ComplexMatrix::from_rows([
+        [r(1.0), r(2.0), r(3.0)],
+        [r(4.0), r(5.0), r(6.0)],
+        [r(7.0), r(8.0), r(9.0)],
+    ]);
+    assert_eq!(rounded.lu().err(), Some(LinalgError::Singular { column: 2 }));
+
+    assert_eq!(ComplexMatrix::<3, 3>::zeros().lu().err(), Some(LinalgError::Singular { column: 0 }));
+    assert_eq!(LinalgError::Singular { column: 2 }.to_string(), "Matrix is singular: no pivot in column 2");
+}
+
+#[test]
+pub fn test_badly_scaled_matrices() {
+    // the rows have very different scales, the matrices are invertible anyway
+    let big = ComplexMatrix::from_rows([[r(1e20), r(0.0)], [r(0.0), r(1.0)]]);
+    assert_eq!(big.determinant(), r(1e20));
+    let x = big.solve(&ComplexArray([r(1e20), r(2.0)])).unwrap();
+    assert_eq!(x, ComplexArray([r(1.0), r(2.0)]));
+
+    let small = ComplexMatrix::from_rows([[r(1.0), r(0.0)], [r(0.0), r(1e-17)]]);
+    assert_eq!(small.determinant(), r(1e-17));
+    let inv = small.inverse().unwrap();
+    assert_eq!(inv, ComplexMatrix::from_rows([[r(1.0), r(0.0)], [r(0.0), r(1e17)]]));
+
+    let mixed = ComplexMatrix::from_rows([[r(1e20), r(1e20)], [r(1.0), r(2.0)]]);
+    assert!(mixed.determinant().abs_diff_eq(&r(1e20), 1e5));
+    assert_vec_close(&mixed.solve(&ComplexArray([r(2e20), r(3.0)])).unwrap(), &ComplexArray([r(1.0), r(1.0)]));
+}
+
+#[test]
+pub fn test_f32_matrix() {
+    let a = ComplexMatrix::from_rows([
+        [ComplexNumber::new(2.0f32, 0.0), ComplexNumber::new(0.0, 1.0)],
+        [ComplexNumber::new(0.0, -1.0), ComplexNumber::new(2.0, 0.0)],
+    ]);
+    let b = ComplexArray([ComplexNumber::new(1.0f32, 0.0), ComplexNumber::new(0.0, 0.0)]);
+    let x = a.solve(&b).unwrap();
+    assert!((a * x - b).euclidean_norm() < 1e-6);
+    assert!(a.determinant().abs_diff_eq(&ComplexNumber::from_real(3.0), 1e-6));
+}
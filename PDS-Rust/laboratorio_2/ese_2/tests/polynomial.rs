@@ -0,0 +1,132 @@
+use ese_2::polynomial::{Polynomial, RootError, RootOptions};
+use ese_2::solution::ApproxEq;
+use ese_2::solution::ComplexNumber;
+
+fn c(re: f64, im: f64) -> ComplexNumber {
+    ComplexNumber::new(re, im)
+}
+
+// every expected root must be matched by a different computed root
+fn assert_roots(found: &[ComplexNumber], expected: &[ComplexNumber], tolerance: f64) {
+    assert_eq!(found.len(), expected.len(), "{:?}", found);
+    let mut left = found.to_vec();
+    for e in expected {
+        let (k, distance) = left
+            .iter()
+            .map(|z| (*z - *e).modulus())
+            .enumerate()
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .unwrap();
+        assert!(distance < tolerance, "no root close to {} in {:?}", e, found);
+        left.remove(k);
+    }
+}
+
+#[test]
+pub fn test_eval_derivative_and_mul() {
+    let p = Polynomial::from_real(&[1.0, 2.0, 3.0]);
+    assert_eq!(p.degree(), 2);
+    assert_eq!(p.eval(c(0.0, 1.0)), c(-2.0, 2.0));
+    assert_eq!(p.eval(c(2.0, 0.0)), c(17.0, 0.0));
+    assert_eq!(p.derivative(), Polynomial::from_real(&[2.0, 6.0]));
+    assert_eq!(Polynomial::from_real(&[5.0]).derivative(), Polynomial::new(vec![]));
+
+    // (1 + x)(1 - x) = 1 - x^2
+    let product = Polynomial::from_real(&[1.0, 1.0]) * Polynomial::from_real(&[1.0, -1.0]);
+    assert_eq!(product, Polynomial::from_real(&[1.0, 0.0, -1.0]));
+    assert!((&p * &Polynomial::new(vec![])).is_zero());
+
+    // leading zeros are dropped
+    assert_eq!(Polynomial::from_real(&[1.0, 0.0, 0.0]).degree(), 0);
+    assert_eq!(Polynomial::from_roots(&[c(0.0, 1.0), c(0.0, -1.0)]), Polynomial::from_real(&[1.0, 0.0, 1.0]));
+    assert_eq!(format!("{}", Polynomial::from_real(&[1.0, -2.0, 3.0])), "(1 + 0i) + (-2 + 0i)x + (3 + 0i)x^2");
+
+    // Gaussian integer coefficients
+    let g = Polynomial::new(vec![ComplexNumber::new(1i64, 1), ComplexNumber::new(0, 2)]);
+    assert_eq!(g.eval(ComplexNumber::new(1, -1)), ComplexNumber::new(3, 3));
+}
+
+#[test]
+pub fn test_deflate() {
+    let p = Polynomial::from_real(&[-1.0, 0.0, 1.0]);
+    let (q, r) = p.deflate(c(1.0, 0.0));
+    assert_eq!(q, Polynomial::from_real(&[1.0, 1.0]));
+    assert_eq!(r, c(0.0, 0.0));
+
+    // the remainder is p(r)
+    let (q, r) = p.deflate(c(2.0, 0.0));
+    assert_eq!(q, Polynomial::from_real(&[2.0, 1.0]));
+    assert_eq!(r, p.eval(c(2.0, 0.0)));
+}
+
+#[test]
+pub fn test_roots_of_known_polynomials() {
+    let i = ComplexNumber::i();
+    assert_roots(&Polynomial::from_real(&[1.0, 0.0, 1.0]).roots().unwrap(), &[i, -i], 1e-12);
+
+    // (x - 1)(x - 2)(x - 3)
+    let cubic = Polynomial::from_real(&[-6.0, 11.0, -6.0, 1.0]);
+    assert_roots(&cubic.roots().unwrap(), &[c(1.0, 0.0), c(2.0, 0.0), c(3.0, 0.0)], 1e-12);
+
+    // complex coefficients, and a non-monic leading coefficient
+    let expected = [c(1.0, 1.0), c(-2.0, 0.0), c(0.0, 0.5), c(3.0, -2.0), c(-0.25, -4.0)];
+    let p = &Polynomial::from_roots(&expected) * &Polynomial::new(vec![c(2.0, -3.0)]);
+    let roots = p.roots().unwrap();
+    assert_roots(&roots, &expected, 1e-10);
+    for z in &roots {
+        assert!(p.is_root(*z, 1e-12), "{} is not a root", z);
+    }
+}
+
+#[test]
+pub fn test_zero_and_multiple_roots() {
+    // x^3 (x - 2): the zero roots are exact
+    let p = Polynomial::from_real(&[0.0, 0.0, 0.0, -2.0, 1.0]);
+    let roots = p.roots().unwrap();
+    assert_eq!(roots.iter().filter(|z| **z == c(0.0, 0.0)).count(), 3);
+    assert_roots(&roots, &[c(0.0, 0.0), c(0.0, 0.0), c(0.0, 0.0), c(2.0, 0.0)], 1e-12);
+
+    // (x - 1)^3 (x + 2): a triple root is only found to about a third of the digits, but every
+    // computed root is still a root of a polynomial within rounding of p
+    let p = Polynomial::from_roots(&[c(1.0, 0.0), c(1.0, 0.0), c(1.0, 0.0), c(-2.0, 0.0)]);
+    let roots = p.roots().unwrap();
+    assert_roots(&roots, &[c(1.0, 0.0), c(1.0, 0.0), c(1.0, 0.0), c(-2.0, 0.0)], 1e-4);
+    assert!(roots.iter().all(|z| p.is_root(*z, 1e-12)));
+}
+
+#[test]
+pub fn test_wilkinson() {
+    // (x - 1)(x - 2)...(x - 15): famously ill-conditioned, the roots move a lot with tiny changes of
+    // the coefficients but the backward error stays small
+    let expected: Vec<ComplexNumber> = (1..=15).map(|k| c(k as f64, 0.0)).collect();
+    let p = Polynomial::from_roots(&expected);
+    let roots = p.roots().unwrap();
+    assert!(roots.iter().all(|z| p.is_root(*z, RootOptions::default().tolerance)));
+    assert_roots(&roots, &expected, 1e-3);
+}
+
+#[test]
+pub fn test_root_errors_and_options() {
+    assert_eq!(Polynomial::<f64>::new(vec![]).roots(), Err(RootError::ZeroPolynomial));
+    assert_eq!(Polynomial::from_real(&[3.0]).roots(), Ok(vec![]));
+    assert_eq!(Polynomial::from_real(&[4.0, 2.0]).roots().unwrap(), vec![c(-2.0, 0.0)]);
+
+    let p = Polynomial::from_real(&[-6.0, 11.0, -6.0, 1.0]);
+    let options = RootOptions { max_iterations: 1, ..RootOptions::default() };
+    assert_eq!(p.roots_with(&options), Err(RootError::NoConvergence { iterations: 1 }));
+    assert_eq!(RootError::NoConvergence { iterations: 1 }.to_string(), "Root finder did not converge in 1 iterations");
+
+    // without polishing the roots still pass the check
+    let options = RootOptions { polish: false, ..RootOptions::default() };
+    assert_roots(&p.roots_with(&options).unwrap(), &[c(1.0, 0.0), c(2.0, 0.0), c(3.0, 0.0)], 1e-10);
+}
+
+#[test]
+pub fn test_f32_roots() {
+    // x^2 - 2x + 5 = (x - 1 - 2i)(x - 1 + 2i)
+    let p = Polynomial::from_real(&[5.0f32, -2.0, 1.0]);
+    let mut roots = p.roots().unwrap();
+    roots.sort_by(|a, b| a.imag().total_cmp(&b.imag()));
+    assert!(roots[0].abs_diff_eq(&ComplexNumber::new(1.0, -2.0), 1e-5));
+    assert!(roots[1].abs_diff_eq(&ComplexNumber::new(1.0, 2.0), 1e-5));
+}
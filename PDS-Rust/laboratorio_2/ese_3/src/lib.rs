@@ -1,7 +1,14 @@
+// make_contiguous copies the elements with an index loop, as in the original exercise
+#![allow(clippy::needless_range_loop)]
+
 pub mod complex_number;
 
 pub mod circular_buffer {
-    #[derive(Debug)]
+    use std::fmt;
+    use std::iter::FusedIterator;
+    use std::ops::Range;
+    use std::slice;
+
     pub struct CircularBuffer<T> {
         buffer: Vec<Option<T>>,    // vector of option: None value or Some(value) which is of type generic
         capacity: usize,    // dimension of the circular buffer
@@ -78,7 +85,35 @@ pub mod circular_buffer {
             self.size
         }
 
+        pub fn capacity(&self) -> usize {
+            self.capacity
+        }
+
+        // elemento più vecchio (il prossimo restituito da read), senza toglierlo
+        pub fn peek(&self) -> Option<&T> {
+            self.iter().next()
+        }
+
+        // elemento scritto per ultimo
+        pub fn peek_back(&self) -> Option<&T> {
+            self.iter().next_back()
+        }
+
+        // come read ma dalla coda: toglie l'elemento scritto per ultimo
+        fn read_back(&mut self) -> Option<T> {
+            if self.size == 0 {
+                return None;
+            }
+            self.tail = (self.tail + self.capacity - 1) % self.capacity;
+            self.size -= 1;
+            self.buffer[self.tail].take()
+        }
+
         pub fn overwrite(&mut self, item: T) {
+            if self.capacity == 0 {
+                // nessuna posizione da sovrascrivere: l'elemento più vecchio è item stesso
+                return;
+            }
             if self.size < self.capacity {
                 // If the buffer isn't full, just do a normal write
                 // 'unwrap' estrae il valore da un Option<T> o Result<T, E> esistente (causando panic se è None o Err)
@@ -116,7 +151,224 @@ pub mod circular_buffer {
             self.head = 0;
             self.tail = self.size % self.capacity;
         }
+
+        // Gli elementi occupano al più due tratti del vettore: da head verso la fine e, se il buffer
+        // "gira", dall'inizio fino a tail. Gli iteratori scorrono i due tratti uno dopo l'altro, così
+        // l'ordine è quello logico (da head a tail) senza dover chiamare make_contiguous.
+        fn ranges(&self) -> (Range<usize>, Range<usize>) {
+            if self.head + self.size <= self.capacity {
+                (self.head..self.head + self.size, 0..0)
+            } else {
+                (self.head..self.capacity, 0..self.head + self.size - self.capacity)
+            }
+        }
+
+        pub fn iter(&self) -> Iter<'_, T> {
+            let (front, back) = self.ranges();
+            Iter { front: self.buffer[front].iter(), back: self.buffer[back].iter() }
+        }
+
+        pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+            let (front, back) = self.ranges();
+            // split_at_mut: due prestiti mutabili disgiunti dello stesso vettore
+            let (low, high) = self.buffer.split_at_mut(front.start);
+            IterMut { front: high[..front.len()].iter_mut(), back: low[back].iter_mut() }
+        }
+
+        // toglie tutti gli elementi dal buffer restituendoli in ordine; quelli non consumati
+        // vengono eliminati quando Drain viene distrutto
+        pub fn drain(&mut self) -> Drain<'_, T> {
+            Drain { buffer: self }
+        }
+    }
+
+    // le posizioni dentro i tratti di ranges() sono sempre occupate
+    fn occupied<T>(slot: Option<T>) -> T {
+        slot.expect("empty slot inside the occupied range")
+    }
+
+    pub struct Iter<'a, T> {
+        front: slice::Iter<'a, Option<T>>,
+        back: slice::Iter<'a, Option<T>>,
+    }
+
+    impl<'a, T> Iterator for Iter<'a, T> {
+        type Item = &'a T;
+
+        fn next(&mut self) -> Option<&'a T> {
+            self.front.next().or_else(|| self.back.next()).map(|slot| occupied(slot.as_ref()))
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            let len = self.front.len() + self.back.len();
+            (len, Some(len))
+        }
+    }
+
+    impl<T> DoubleEndedIterator for Iter<'_, T> {
+        fn next_back(&mut self) -> Option<Self::Item> {
+            self.back.next_back().or_else(|| self.front.next_back()).map(|slot| occupied(slot.as_ref()))
+        }
+    }
+
+    impl<T> ExactSizeIterator for Iter<'_, T> {}
+    impl<T> FusedIterator for Iter<'_, T> {}
+
+    pub struct IterMut<'a, T> {
+        front: slice::IterMut<'a, Option<T>>,
+        back: slice::IterMut<'a, Option<T>>,
+    }
+
+    impl<'a, T> Iterator for IterMut<'a, T> {
+        type Item = &'a mut T;
+
+        fn next(&mut self) -> Option<&'a mut T> {
+            self.front.next().or_else(|| self.back.next()).map(|slot| occupied(slot.as_mut()))
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            let len = self.front.len() + self.back.len();
+            (len, Some(len))
+        }
+    }
+
+    impl<T> DoubleEndedIterator for IterMut<'_, T> {
+        fn next_back(&mut self) -> Option<Self::Item> {
+            self.back.next_back().or_else(|| self.front.next_back()).map(|slot| occupied(slot.as_mut()))
+        }
+    }
+
+    impl<T> ExactSizeIterator for IterMut<'_, T> {}
+    impl<T> FusedIterator for IterMut<'_, T> {}
+
+    pub struct Drain<'a, T> {
+        buffer: &'a mut CircularBuffer<T>,
     }
+
+    impl<T> Iterator for Drain<'_, T> {
+        type Item = T;
+
+        fn next(&mut self) -> Option<T> {
+            self.buffer.read()
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            (self.buffer.size, Some(self.buffer.size))
+        }
+    }
+
+    impl<T> DoubleEndedIterator for Drain<'_, T> {
+        fn next_back(&mut self) -> Option<T> {
+            self.buffer.read_back()
+        }
+    }
+
+    impl<T> ExactSizeIterator for Drain<'_, T> {}
+    impl<T> FusedIterator for Drain<'_, T> {}
+
+    impl<T> Drop for Drain<'_, T> {
+        fn drop(&mut self) {
+            self.buffer.clear();
+        }
+    }
+
+    // for x in buffer: consuma il buffer
+    pub struct IntoIter<T> {
+        buffer: CircularBuffer<T>,
+    }
+
+    impl<T> Iterator for IntoIter<T> {
+        type Item = T;
+
+        fn next(&mut self) -> Option<T> {
+            self.buffer.read()
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            (self.buffer.size, Some(self.buffer.size))
+        }
+    }
+
+    impl<T> DoubleEndedIterator for IntoIter<T> {
+        fn next_back(&mut self) -> Option<T> {
+            self.buffer.read_back()
+        }
+    }
+
+    impl<T> ExactSizeIterator for IntoIter<T> {}
+    impl<T> FusedIterator for IntoIter<T> {}
+
+    impl<T> IntoIterator for CircularBuffer<T> {
+        type Item = T;
+        type IntoIter = IntoIter<T>;
+
+        fn into_iter(self) -> IntoIter<T> {
+            IntoIter { buffer: self }
+        }
+    }
+
+    impl<'a, T> IntoIterator for &'a CircularBuffer<T> {
+        type Item = &'a T;
+        type IntoIter = Iter<'a, T>;
+
+        fn into_iter(self) -> Iter<'a, T> {
+            self.iter()
+        }
+    }
+
+    impl<'a, T> IntoIterator for &'a mut CircularBuffer<T> {
+        type Item = &'a mut T;
+        type IntoIter = IterMut<'a, T>;
+
+        fn into_iter(self) -> IterMut<'a, T> {
+            self.iter_mut()
+        }
+    }
+
+    // Extend usa overwrite: come per un buffer circolare che riceve un flusso di dati, quando è
+    // pieno i nuovi elementi prendono il posto dei più vecchi e restano gli ultimi `capacity`
+    impl<T> Extend<T> for CircularBuffer<T> {
+        fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+            for item in iter {
+                self.overwrite(item);
+            }
+        }
+    }
+
+    // la capacità è il numero di elementi raccolti: il buffer risulta pieno
+    impl<T> FromIterator<T> for CircularBuffer<T> {
+        fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+            let items: Vec<T> = iter.into_iter().collect();
+            let mut buffer = CircularBuffer::new(items.len());
+            buffer.extend(items);
+            buffer
+        }
+    }
+
+    // Debug, Clone e PartialEq guardano gli elementi in ordine logico e non la disposizione nel
+    // vettore: due buffer con gli stessi elementi sono uguali anche se head è in posizioni diverse.
+    // La capacità non conta per l'uguaglianza (come per VecDeque), il clone ha la stessa capacità
+    impl<T: fmt::Debug> fmt::Debug for CircularBuffer<T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_list().entries(self.iter()).finish()
+        }
+    }
+
+    impl<T: Clone> Clone for CircularBuffer<T> {
+        fn clone(&self) -> Self {
+            let mut buffer = CircularBuffer::new(self.capacity);
+            buffer.extend(self.iter().cloned());
+            buffer
+        }
+    }
+
+    impl<T: PartialEq> PartialEq for CircularBuffer<T> {
+        fn eq(&self, other: &Self) -> bool {
+            self.size == other.size && self.iter().eq(other.iter())
+        }
+    }
+
+    impl<T: Eq> Eq for CircularBuffer<T> {}
 }
 
 pub mod circular_buffer_heterogenous {
@@ -375,4 +375,135 @@ mod tests {
         let value = buffer[1].as_any().downcast_ref::<i32>().unwrap();
         assert_eq!(*value, 3);
     }
-}
\ No newline at end of file
+
+    // buffer di capacità 5 che "gira": contiene [3, 4, 5, 6, 7] con head = 2 e tail = 2
+    fn buffer_non_contiguo() -> CircularBuffer<i32> {
+        let mut buffer = CircularBuffer::new(5);
+        for i in 1..=5 {
+            buffer.write(i).unwrap();
+        }
+        buffer.read();
+        buffer.read();
+        buffer.write(6).unwrap();
+        buffer.write(7).unwrap();
+        buffer
+    }
+
+    #[test]
+    fn test_iter_in_ordine_logico() {
+        let buffer = buffer_non_contiguo();
+
+        // nessuna chiamata a make_contiguous
+        assert_eq!(buffer.iter().copied().collect::<Vec<_>>(), vec![3, 4, 5, 6, 7]);
+        assert_eq!(buffer.iter().rev().copied().collect::<Vec<_>>(), vec![7, 6, 5, 4, 3]);
+        assert_eq!(buffer.iter().len(), 5);
+
+        // dai due estremi insieme
+        let mut it = buffer.iter();
+        assert_eq!(it.next(), Some(&3));
+        assert_eq!(it.next_back(), Some(&7));
+        assert_eq!(it.next_back(), Some(&6));
+        assert_eq!(it.len(), 2);
+        assert_eq!(it.collect::<Vec<_>>(), vec![&4, &5]);
+
+        // &buffer in un for
+        let mut somma = 0;
+        for x in &buffer {
+            somma += x;
+        }
+        assert_eq!(somma, 25);
+        assert_eq!(buffer.size(), 5);
+
+        let vuoto: CircularBuffer<i32> = CircularBuffer::new(3);
+        assert_eq!(vuoto.iter().next(), None);
+    }
+
+    #[test]
+    fn test_iter_mut_e_peek() {
+        let mut buffer = buffer_non_contiguo();
+        for x in buffer.iter_mut() {
+            *x *= 10;
+        }
+        for x in &mut buffer {
+            *x += 1;
+        }
+        assert_eq!(buffer.peek(), Some(&31));
+        assert_eq!(buffer.peek_back(), Some(&71));
+        assert_eq!(buffer.read(), Some(31));
+        assert_eq!(buffer.peek(), Some(&41));
+
+        if let Some(ultimo) = buffer.iter_mut().next_back() {
+            *ultimo = 0;
+        }
+        assert_eq!(buffer.peek_back(), Some(&0));
+
+        let vuoto: CircularBuffer<i32> = CircularBuffer::new(3);
+        assert_eq!(vuoto.peek(), None);
+        assert_eq!(vuoto.peek_back(), None);
+    }
+
+    #[test]
+    fn test_drain_e_into_iter() {
+        let mut buffer = buffer_non_contiguo();
+        assert_eq!(buffer.drain().collect::<Vec<_>>(), vec![3, 4, 5, 6, 7]);
+        assert_eq!(buffer.size(), 0);
+
+        // Drain non consumato del tutto: il buffer viene comunque svuotato
+        let mut buffer = buffer_non_contiguo();
+        {
+            let mut drain = buffer.drain();
+            assert_eq!(drain.next(), Some(3));
+            assert_eq!(drain.next_back(), Some(7));
+        }
+        assert_eq!(buffer.size(), 0);
+        // e resta utilizzabile
+        buffer.write(1).unwrap();
+        assert_eq!(buffer.read(), Some(1));
+
+        let consumati: Vec<i32> = buffer_non_contiguo().into_iter().rev().collect();
+        assert_eq!(consumati, vec![7, 6, 5, 4, 3]);
+        let mut stringhe = CircularBuffer::new(2);
+        stringhe.write(String::from("a")).unwrap();
+        stringhe.write(String::from("b")).unwrap();
+        assert_eq!(stringhe.into_iter().collect::<String>(), "ab");
+    }
+
+    #[test]
+    fn test_extend_e_from_iterator() {
+        let buffer: CircularBuffer<i32> = (1..=4).collect();
+        assert_eq!(buffer.capacity(), 4);
+        assert_eq!(buffer.size(), 4);
+        assert_eq!(buffer.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+
+        // quando il buffer è pieno restano gli ultimi elementi
+        let mut buffer: CircularBuffer<i32> = CircularBuffer::new(3);
+        buffer.write(1).unwrap();
+        buffer.extend(vec![2, 3, 4, 5]);
+        assert_eq!(buffer.iter().copied().collect::<Vec<_>>(), vec![3, 4, 5]);
+
+        let mut vuoto: CircularBuffer<i32> = std::iter::empty().collect();
+        assert_eq!(vuoto.capacity(), 0);
+        vuoto.extend([1, 2]);
+        assert_eq!(vuoto.size(), 0);
+    }
+
+    #[test]
+    fn test_debug_clone_partial_eq_in_ordine_logico() {
+        let buffer = buffer_non_contiguo();
+        assert_eq!(format!("{:?}", buffer), "[3, 4, 5, 6, 7]");
+
+        // stessi elementi ma disposizione diversa nel vettore
+        let contiguo: CircularBuffer<i32> = (3..=7).collect();
+        assert_eq!(buffer, contiguo);
+
+        let clone = buffer.clone();
+        assert_eq!(clone, buffer);
+        assert_eq!(clone.capacity(), 5);
+
+        let mut diverso = buffer.clone();
+        diverso.read();
+        assert_ne!(diverso, buffer);
+        diverso.write(3).unwrap();
+        assert_ne!(diverso, buffer); // [4, 5, 6, 7, 3]
+    }
+}